@@ -1,4 +1,5 @@
 pub mod audio;
 pub mod cdrom;
 pub mod context;
+pub mod sio;
 pub mod video;
@@ -5,7 +5,6 @@ use crate::{
         audio::openal::rendering::*,
         context::*,
     },
-    system::spu::constants::VOICES_COUNT,
     Config,
 };
 use openal_sys::*;
@@ -22,8 +21,10 @@ pub(crate) fn setup(_config: &Config, backend_params: &BackendParams) {
     unsafe {
         assert_eq!(INITIALIZED, false);
 
-        alGenSources(rendering::SOURCES.len() as ALsizei, rendering::SOURCES.as_mut_ptr());
-        alGenBuffers(rendering::BUFFERS.len() as ALsizei, rendering::BUFFERS.as_mut_ptr());
+        alGenSources(1, &mut SOURCE);
+        alGenBuffers(BUFFERS.len() as ALsizei, BUFFERS.as_mut_ptr());
+        FREE_BUFFERS = BUFFERS;
+        FREE_BUFFERS_COUNT = STREAM_BUFFERS_COUNT;
 
         if alGetError() != AL_NO_ERROR as ALenum {
             panic!("Error initializing OpenAL audio backend");
@@ -38,13 +39,13 @@ pub(crate) fn teardown(_config: &Config, backend_params: &BackendParams) {
 
     unsafe {
         if INITIALIZED {
-            for i in 0..VOICES_COUNT {
-                alSourceStop(SOURCES[i]);
-                alSourcei(SOURCES[i], AL_BUFFER as ALenum, 0);
-            }
+            // Detaching the buffer unqueues everything.
+            alSourceStop(SOURCE);
+            alSourcei(SOURCE, AL_BUFFER as ALenum, 0);
 
-            alDeleteBuffers(rendering::BUFFERS.len() as ALsizei, rendering::BUFFERS.as_mut_ptr());
-            alDeleteSources(rendering::SOURCES.len() as ALsizei, rendering::SOURCES.as_mut_ptr());
+            alDeleteBuffers(BUFFERS.len() as ALsizei, BUFFERS.as_mut_ptr());
+            alDeleteSources(1, &mut SOURCE);
+            FREE_BUFFERS_COUNT = 0;
 
             INITIALIZED = false;
         }
@@ -1,8 +1,11 @@
-use crate::system::spu::constants::VOICES_COUNT;
 use openal_sys::*;
 
-pub(crate) static mut SOURCES: [ALuint; VOICES_COUNT] = [0; VOICES_COUNT];
+/// Number of buffers used to stream the mixed SPU output.
+pub(crate) const STREAM_BUFFERS_COUNT: usize = 8;
 
-// Double buffering for each SPU voice, swapping between the Nth and Nth + 1 buffers.
-pub(crate) static mut BUFFERS: [ALuint; VOICES_COUNT * 2] = [0; VOICES_COUNT * 2];
-pub(crate) static mut RENDERING_ODD_BUFFER: [bool; VOICES_COUNT] = [false; VOICES_COUNT];
+pub(crate) static mut SOURCE: ALuint = 0;
+
+// Streaming buffers; the ones not currently queued on the source are kept in the free list.
+pub(crate) static mut BUFFERS: [ALuint; STREAM_BUFFERS_COUNT] = [0; STREAM_BUFFERS_COUNT];
+pub(crate) static mut FREE_BUFFERS: [ALuint; STREAM_BUFFERS_COUNT] = [0; STREAM_BUFFERS_COUNT];
+pub(crate) static mut FREE_BUFFERS_COUNT: usize = 0;
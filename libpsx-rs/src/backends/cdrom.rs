@@ -1,5 +1,6 @@
 #![allow(unused_variables)]
 
+pub mod image;
 #[cfg(libcdio)]
 pub mod libcdio;
 #[cfg(libmirage)]
@@ -10,6 +11,7 @@ use std::path::Path;
 
 pub enum CdromBackend<'a> {
     None,
+    Image(image::BackendParams),
     #[cfg(libmirage)]
     Libmirage(libmirage::BackendParams<'a>),
     #[cfg(libcdio)]
@@ -20,6 +22,7 @@ pub enum CdromBackend<'a> {
 pub(crate) fn setup(config: &Config) {
     match config.cdrom_backend {
         CdromBackend::None => {},
+        CdromBackend::Image(ref params) => image::setup(config, params),
         #[cfg(libmirage)]
         CdromBackend::Libmirage(ref params) => libmirage::setup(config, params),
         #[cfg(libcdio)]
@@ -31,6 +34,7 @@ pub(crate) fn setup(config: &Config) {
 pub(crate) fn teardown(config: &Config) {
     match config.cdrom_backend {
         CdromBackend::None => {},
+        CdromBackend::Image(ref params) => image::teardown(config, params),
         #[cfg(libmirage)]
         CdromBackend::Libmirage(ref params) => libmirage::teardown(config, params),
         #[cfg(libcdio)]
@@ -42,6 +46,7 @@ pub(crate) fn teardown(config: &Config) {
 pub(crate) fn change_disc(config: &Config, path: &Path) -> Result<(), String> {
     match config.cdrom_backend {
         CdromBackend::None => Err("No available backend".into()),
+        CdromBackend::Image(ref params) => image::change_disc(config, params, path),
         #[cfg(libmirage)]
         CdromBackend::Libmirage(ref params) => libmirage::change_disc(config, params, path),
         #[cfg(libcdio)]
@@ -49,3 +54,29 @@ pub(crate) fn change_disc(config: &Config, path: &Path) -> Result<(), String> {
         _ => unimplemented!(),
     }
 }
+
+/// Number of discs in the loaded image (0 if none is loaded).
+pub(crate) fn disc_count(config: &Config) -> usize {
+    match config.cdrom_backend {
+        CdromBackend::None => 0,
+        CdromBackend::Image(ref params) => image::disc_count(config, params),
+        #[cfg(libmirage)]
+        CdromBackend::Libmirage(ref params) => 1,
+        #[cfg(libcdio)]
+        CdromBackend::Libcdio(ref params) => 1,
+        _ => unimplemented!(),
+    }
+}
+
+/// Selects a disc within a multi-disc image.
+pub(crate) fn select_disc(config: &Config, index: usize) -> Result<(), String> {
+    match config.cdrom_backend {
+        CdromBackend::None => Err("No available backend".into()),
+        CdromBackend::Image(ref params) => image::select_disc(config, params, index),
+        #[cfg(libmirage)]
+        CdromBackend::Libmirage(ref params) => Err("Multi-disc images are not supported by the libmirage backend".into()),
+        #[cfg(libcdio)]
+        CdromBackend::Libcdio(ref params) => Err("Multi-disc images are not supported by the libcdio backend".into()),
+        _ => unimplemented!(),
+    }
+}
@@ -0,0 +1,160 @@
+pub(crate) mod chd;
+pub(crate) mod cue;
+pub(crate) mod ecc;
+pub(crate) mod ecm;
+pub(crate) mod inflate;
+pub(crate) mod pbp;
+pub(crate) mod sbi;
+
+use crate::{
+    utilities::binary_to_bcd,
+    Config,
+};
+use log::info;
+use parking_lot::Mutex;
+use sbi::SubchannelPatches;
+use std::path::Path;
+
+pub(crate) const SECTOR_SIZE: usize = 2352;
+/// Sectors before LBA 0 (00:02:00), used as the track 1 pregap.
+pub(crate) const LEAD_IN_SECTORS: usize = 150;
+pub(crate) const SECTORS_PER_SECOND: usize = 75;
+pub(crate) const SECTORS_PER_MINUTE: usize = 60 * SECTORS_PER_SECOND;
+/// Subchannel Q: control / ADR, track, index, relative MSF, zero, absolute MSF, CRC-16.
+pub(crate) const SUBCHANNEL_Q_SIZE: usize = 12;
+const SYNC_PATTERN: [u8; 12] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum TrackKind {
+    Audio,
+    Mode1,
+    Mode2,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Track {
+    pub(crate) number: usize,
+    pub(crate) kind: TrackKind,
+    /// Absolute sector address (00:00:00 = 0) of the start of the pregap; equal to start if there is none.
+    pub(crate) pregap_start: usize,
+    /// Absolute sector address of INDEX 01.
+    pub(crate) start: usize,
+    /// Absolute sector address following the last sector of the track.
+    pub(crate) end: usize,
+}
+
+/// A disc image format, providing raw sector access.
+pub(crate) trait DiscImage: Send {
+    fn tracks(&self) -> &[Track];
+
+    /// Reads a raw 2352 byte sector at an absolute sector address (00:00:00 = 0).
+    fn read_raw_sector(&mut self, address: usize) -> Result<Vec<u8>, String>;
+
+    /// Reads the subchannel Q data for a sector, if the format stores it.
+    fn read_subchannel_q(&mut self, _address: usize) -> Result<Option<[u8; SUBCHANNEL_Q_SIZE]>, String> {
+        Ok(None)
+    }
+
+    /// Number of discs stored in the image (multi-disc PBP files).
+    fn disc_count(&self) -> usize {
+        1
+    }
+
+    /// Switches to another disc stored in the image.
+    fn select_disc(&mut self, index: usize) -> Result<(), String> {
+        match index {
+            0 => Ok(()),
+            _ => Err("The disc image contains a single disc".into()),
+        }
+    }
+}
+
+/// Disc image backend (CUE/BIN, CHD, ECM, PBP, STR/XA streams) implemented natively; no external libraries are
+/// required.
+pub struct BackendParams {
+    pub(crate) image: Mutex<Option<Box<dyn DiscImage>>>,
+    /// Subchannel Q patches (.sbi / .lsd) for the loaded disc.
+    pub(crate) subchannel_patches: Mutex<SubchannelPatches>,
+}
+
+impl BackendParams {
+    pub fn new() -> BackendParams {
+        BackendParams {
+            image: Mutex::new(None),
+            subchannel_patches: Mutex::new(SubchannelPatches::new()),
+        }
+    }
+}
+
+pub(crate) fn setup(_config: &Config, backend_params: &BackendParams) {
+    *backend_params.image.lock() = None;
+    *backend_params.subchannel_patches.lock() = SubchannelPatches::new();
+}
+
+pub(crate) fn teardown(_config: &Config, backend_params: &BackendParams) {
+    *backend_params.image.lock() = None;
+    *backend_params.subchannel_patches.lock() = SubchannelPatches::new();
+}
+
+pub(crate) fn change_disc(_config: &Config, backend_params: &BackendParams, path: &Path) -> Result<(), String> {
+    load_disc(backend_params, path)
+}
+
+/// Opens the disc image at path, chosen by its extension, replacing any loaded disc.
+pub(crate) fn load_disc(backend_params: &BackendParams, path: &Path) -> Result<(), String> {
+    let mut image = backend_params.image.lock();
+    *image = None;
+    *backend_params.subchannel_patches.lock() = SubchannelPatches::new();
+
+    info!("Changing disc to {}", path.display());
+
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let new_image: Box<dyn DiscImage> = match extension.as_ref() {
+        "cue" => Box::new(cue::CueImage::open(path)?),
+        "chd" => Box::new(chd::ChdImage::open(path)?),
+        "pbp" => Box::new(pbp::PbpImage::open(path)?),
+        "bin" | "img" | "ecm" => Box::new(cue::CueImage::open_bin(path)?),
+        "iso" => Box::new(cue::CueImage::open_iso(path)?),
+        "str" | "xa" => Box::new(cue::CueImage::open_stream(path)?),
+        _ => return Err(format!("Unsupported disc image format: {}", path.display())),
+    };
+
+    *backend_params.subchannel_patches.lock() = SubchannelPatches::load_sidecar(path)?;
+    *image = Some(new_image);
+    Ok(())
+}
+
+pub(crate) fn disc_count(_config: &Config, backend_params: &BackendParams) -> usize {
+    backend_params.image.lock().as_ref().map(|image| image.disc_count()).unwrap_or(0)
+}
+
+pub(crate) fn select_disc(_config: &Config, backend_params: &BackendParams, index: usize) -> Result<(), String> {
+    let mut image = backend_params.image.lock();
+    let image = image.as_mut().ok_or_else(|| "No disc loaded".to_owned())?;
+
+    info!("Selecting disc {} of {}", index + 1, image.disc_count());
+    image.select_disc(index)
+}
+
+pub(crate) fn find_track(tracks: &[Track], address: usize) -> Option<&Track> {
+    tracks.iter().find(|track| (address >= track.pregap_start) && (address < track.end))
+}
+
+/// Generates a sector not stored in the image (such as the lead-in or a PREGAP); empty data sectors have a valid
+/// header.
+pub(crate) fn generate_empty_sector(address: usize, kind: TrackKind) -> Vec<u8> {
+    let mut sector = vec![0; SECTOR_SIZE];
+
+    let mode = match kind {
+        TrackKind::Audio => return sector,
+        TrackKind::Mode1 => 1,
+        TrackKind::Mode2 => 2,
+    };
+
+    sector[0..12].copy_from_slice(&SYNC_PATTERN);
+    sector[12] = binary_to_bcd((address / SECTORS_PER_MINUTE) as u8);
+    sector[13] = binary_to_bcd(((address / SECTORS_PER_SECOND) % 60) as u8);
+    sector[14] = binary_to_bcd((address % SECTORS_PER_SECOND) as u8);
+    sector[15] = mode;
+    sector
+}
@@ -0,0 +1,536 @@
+pub(crate) mod codec;
+pub(crate) mod flac;
+pub(crate) mod huffman;
+pub(crate) mod lzma;
+
+use crate::backends::cdrom::image::{
+    chd::{
+        codec::*,
+        huffman::*,
+    },
+    *,
+};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    path::Path,
+};
+
+const HEADER_TAG: &[u8; 8] = b"MComprHD";
+const HEADER_SIZE_V5: usize = 124;
+const MAP_HEADER_SIZE: usize = 16;
+const METADATA_HEADER_SIZE: usize = 16;
+const TRACK_METADATA_TAG: &[u8; 4] = b"CHT2";
+const TRACK_METADATA_OLD_TAG: &[u8; 4] = b"CHTR";
+/// Tracks are padded to a multiple of this many frames within the CHD.
+const TRACK_PADDING: usize = 4;
+const HUNK_CACHE_SIZE: usize = 16;
+
+const COMPRESSION_TYPE_3: u32 = 3;
+const COMPRESSION_NONE: u32 = 4;
+const COMPRESSION_SELF: u32 = 5;
+const COMPRESSION_PARENT: u32 = 6;
+const COMPRESSION_RLE_SMALL: u32 = 7;
+const COMPRESSION_RLE_LARGE: u32 = 8;
+const COMPRESSION_SELF_0: u32 = 9;
+const COMPRESSION_SELF_1: u32 = 10;
+const COMPRESSION_PARENT_SELF: u32 = 11;
+const COMPRESSION_PARENT_0: u32 = 12;
+const COMPRESSION_PARENT_1: u32 = 13;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum HunkEntry {
+    Compressed {
+        codec: usize,
+        offset: u64,
+        length: usize,
+    },
+    Uncompressed(u64),
+    /// Same data as another hunk in this file.
+    SelfReference(usize),
+    Parent,
+    Zero,
+}
+
+/// Subcode stored alongside the sector data.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum SubcodeKind {
+    None,
+    /// Deinterleaved; 12 bytes for each of the P - W channels.
+    Cooked,
+    /// Interleaved; one bit of each channel per byte (P = bit 7).
+    Raw,
+}
+
+/// Location of the stored part of a track within the CHD frames.
+#[derive(Debug, Clone)]
+struct TrackSource {
+    /// Absolute sector address range of the frames stored in the CHD.
+    data_start: usize,
+    data_end: usize,
+    /// CHD frame index of data_start.
+    frame: usize,
+    subcode: SubcodeKind,
+}
+
+/// MAME compressed hunks of data (v5) CD image.
+pub(crate) struct ChdImage {
+    file: File,
+    hunk_size: usize,
+    codecs: [Option<Codec>; 4],
+    hunks: Vec<HunkEntry>,
+    tracks: Vec<Track>,
+    sources: Vec<TrackSource>,
+    /// Recently decompressed hunks, most recent first.
+    cache: VecDeque<(usize, Vec<u8>)>,
+}
+
+impl ChdImage {
+    pub(crate) fn open(path: &Path) -> Result<ChdImage, String> {
+        let mut file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+
+        let header = read_at(&mut file, 0, HEADER_SIZE_V5)?;
+        if &header[0..8] != HEADER_TAG {
+            return Err(format!("{} is not a CHD file", path.display()));
+        }
+
+        let version = read_u32(&header, 12);
+        if version != 5 {
+            return Err(format!("Unsupported CHD version {} (only version 5 is supported)", version));
+        }
+
+        let mut codecs = [None; 4];
+        for (index, codec) in codecs.iter_mut().enumerate() {
+            *codec = Codec::from_tag(read_u32(&header, 16 + (index * 4)))?;
+        }
+
+        let logical_size = read_u64(&header, 32);
+        let map_offset = read_u64(&header, 40);
+        let metadata_offset = read_u64(&header, 48);
+        let hunk_size = read_u32(&header, 56) as usize;
+        let unit_size = read_u32(&header, 60) as usize;
+
+        if (hunk_size == 0) || (hunk_size % CD_FRAME_SIZE != 0) || (unit_size != CD_FRAME_SIZE) {
+            return Err(format!("{} is not a CD CHD (hunk size {}, unit size {})", path.display(), hunk_size, unit_size));
+        }
+
+        let hunk_count = ((logical_size + (hunk_size as u64) - 1) / (hunk_size as u64)) as usize;
+
+        let hunks = if codecs[0].is_none() {
+            read_uncompressed_map(&mut file, map_offset, hunk_count, hunk_size)?
+        } else {
+            read_compressed_map(&mut file, map_offset, hunk_count, hunk_size)?
+        };
+
+        let (tracks, sources) = read_tracks(&mut file, metadata_offset)?;
+
+        Ok(ChdImage {
+            file,
+            hunk_size,
+            codecs,
+            hunks,
+            tracks,
+            sources,
+            cache: VecDeque::new(),
+        })
+    }
+
+    /// Decompressed hunks are kept in a small most recently used cache, so sequential sector reads only decompress each
+    /// hunk once.
+    fn read_hunk(&mut self, hunk: usize) -> Result<&[u8], String> {
+        if let Some(index) = self.cache.iter().position(|(cached_hunk, _)| *cached_hunk == hunk) {
+            if index > 0 {
+                let entry = self.cache.remove(index).unwrap();
+                self.cache.push_front(entry);
+            }
+            return Ok(&self.cache[0].1);
+        }
+
+        let entry = *self.hunks.get(hunk).ok_or_else(|| format!("CHD hunk {} out of range", hunk))?;
+        let data = match entry {
+            HunkEntry::Compressed {
+                codec,
+                offset,
+                length,
+            } => {
+                let codec = self.codecs[codec].ok_or_else(|| format!("CHD hunk {} uses an unused compressor slot", hunk))?;
+                let compressed = read_at(&mut self.file, offset, length)?;
+                decompress(codec, &compressed, self.hunk_size).map_err(|e| format!("CHD hunk {}: {}", hunk, e))?
+            },
+            HunkEntry::Uncompressed(offset) => read_at(&mut self.file, offset, self.hunk_size)?,
+            HunkEntry::SelfReference(source_hunk) => {
+                if source_hunk >= hunk {
+                    return Err(format!("CHD hunk {} references a later hunk", hunk));
+                }
+                self.read_hunk(source_hunk)?.to_vec()
+            },
+            HunkEntry::Parent => return Err("CHD files with a parent are not supported".into()),
+            HunkEntry::Zero => vec![0; self.hunk_size],
+        };
+
+        self.cache.push_front((hunk, data));
+        self.cache.truncate(HUNK_CACHE_SIZE);
+
+        Ok(&self.cache[0].1)
+    }
+
+    fn find_source(&self, address: usize) -> Result<&TrackSource, String> {
+        let track_index =
+            self.tracks.iter().position(|track| (address >= track.pregap_start) && (address < track.end)).ok_or_else(|| format!("Sector {} is outside of the disc", address))?;
+        Ok(&self.sources[track_index])
+    }
+
+    /// Reads a full (sector + subcode) frame, or None if the address is not stored in the CHD.
+    fn read_frame(&mut self, address: usize) -> Result<Option<Vec<u8>>, String> {
+        let source = self.find_source(address)?;
+
+        if (address < source.data_start) || (address >= source.data_end) {
+            return Ok(None);
+        }
+
+        let frame_offset = (source.frame + (address - source.data_start)) * CD_FRAME_SIZE;
+        let offset = frame_offset % self.hunk_size;
+        let hunk = self.read_hunk(frame_offset / self.hunk_size)?;
+
+        Ok(Some(hunk[offset..(offset + CD_FRAME_SIZE)].to_vec()))
+    }
+}
+
+impl DiscImage for ChdImage {
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn read_raw_sector(&mut self, address: usize) -> Result<Vec<u8>, String> {
+        let kind = find_track(&self.tracks, address).map(|track| track.kind).ok_or_else(|| format!("Sector {} is outside of the disc", address))?;
+
+        match self.read_frame(address)? {
+            Some(mut frame) => {
+                frame.truncate(CD_SECTOR_DATA_SIZE);
+                if kind == TrackKind::Audio {
+                    // Audio is stored big endian.
+                    for sample in frame.chunks_exact_mut(2) {
+                        sample.swap(0, 1);
+                    }
+                }
+                Ok(frame)
+            },
+            None => Ok(generate_empty_sector(address, kind)),
+        }
+    }
+
+    fn read_subchannel_q(&mut self, address: usize) -> Result<Option<[u8; SUBCHANNEL_Q_SIZE]>, String> {
+        // Outside of the tracks (lead-out), nothing is stored.
+        let subcode_kind = self.find_source(address).map_or(SubcodeKind::None, |source| source.subcode);
+        if subcode_kind == SubcodeKind::None {
+            return Ok(None);
+        }
+
+        let frame = match self.read_frame(address)? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        let subcode = &frame[CD_SECTOR_DATA_SIZE..];
+
+        let mut q = [0; SUBCHANNEL_Q_SIZE];
+        match subcode_kind {
+            SubcodeKind::None => unreachable!(),
+            SubcodeKind::Cooked => q.copy_from_slice(&subcode[SUBCHANNEL_Q_SIZE..(SUBCHANNEL_Q_SIZE * 2)]),
+            SubcodeKind::Raw => {
+                for (index, &byte) in subcode.iter().enumerate() {
+                    q[index / 8] |= ((byte >> 6) & 1) << (7 - (index % 8));
+                }
+            },
+        }
+
+        Ok(Some(q))
+    }
+}
+
+fn read_at(file: &mut File, offset: u64, length: usize) -> Result<Vec<u8>, String> {
+    let mut buffer = vec![0; length];
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("CHD seek failed: {}", e))?;
+    file.read_exact(&mut buffer).map_err(|e| format!("CHD read failed: {}", e))?;
+    Ok(buffer)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_u48(data: &[u8], offset: usize) -> u64 {
+    data[offset..(offset + 6)].iter().fold(0, |value, &byte| (value << 8) | (byte as u64))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    ((read_u32(data, offset) as u64) << 32) | (read_u32(data, offset + 4) as u64)
+}
+
+fn read_uncompressed_map(file: &mut File, map_offset: u64, hunk_count: usize, hunk_size: usize) -> Result<Vec<HunkEntry>, String> {
+    let map = read_at(file, map_offset, hunk_count * 4)?;
+
+    Ok(map
+        .chunks_exact(4)
+        .map(|entry| {
+            match read_u32(entry, 0) as u64 {
+                0 => HunkEntry::Zero,
+                index => HunkEntry::Uncompressed(index * (hunk_size as u64)),
+            }
+        })
+        .collect())
+}
+
+fn read_compressed_map(file: &mut File, map_offset: u64, hunk_count: usize, hunk_size: usize) -> Result<Vec<HunkEntry>, String> {
+    let header = read_at(file, map_offset, MAP_HEADER_SIZE)?;
+    let map_size = read_u32(&header, 0) as usize;
+    let first_offset = read_u48(&header, 4);
+    let length_bits = header[12] as usize;
+    let self_bits = header[13] as usize;
+    let parent_bits = header[14] as usize;
+
+    let map = read_at(file, map_offset + (MAP_HEADER_SIZE as u64), map_size)?;
+    let mut reader = BitReader::new(&map);
+
+    // Compression types are Huffman coded, with run length encoding.
+    let decoder = HuffmanDecoder::import_tree_rle(&mut reader, 16, 8)?;
+    let mut kinds = Vec::with_capacity(hunk_count);
+    let mut last_kind = 0;
+    let mut repeat_count = 0;
+    while kinds.len() < hunk_count {
+        if repeat_count > 0 {
+            kinds.push(last_kind);
+            repeat_count -= 1;
+            continue;
+        }
+
+        match decoder.decode_one(&mut reader) {
+            COMPRESSION_RLE_SMALL => {
+                kinds.push(last_kind);
+                repeat_count = 2 + decoder.decode_one(&mut reader);
+            },
+            COMPRESSION_RLE_LARGE => {
+                kinds.push(last_kind);
+                repeat_count = 2 + 16 + (decoder.decode_one(&mut reader) << 4);
+                repeat_count += decoder.decode_one(&mut reader);
+            },
+            kind => {
+                kinds.push(kind);
+                last_kind = kind;
+            },
+        }
+    }
+
+    let mut hunks = Vec::with_capacity(hunk_count);
+    let mut current_offset = first_offset;
+    let mut last_self = 0;
+    let mut last_parent_used = false;
+
+    for (hunk, &kind) in kinds.iter().enumerate() {
+        let entry = match kind {
+            0..=COMPRESSION_TYPE_3 => {
+                let length = reader.read(length_bits) as usize;
+                reader.read(16);
+                let entry = HunkEntry::Compressed {
+                    codec: kind as usize,
+                    offset: current_offset,
+                    length,
+                };
+                current_offset += length as u64;
+                entry
+            },
+            COMPRESSION_NONE => {
+                reader.read(16);
+                let entry = HunkEntry::Uncompressed(current_offset);
+                current_offset += hunk_size as u64;
+                entry
+            },
+            COMPRESSION_SELF => {
+                last_self = reader.read(self_bits) as usize;
+                HunkEntry::SelfReference(last_self)
+            },
+            COMPRESSION_SELF_0 => HunkEntry::SelfReference(last_self),
+            COMPRESSION_SELF_1 => {
+                last_self += 1;
+                HunkEntry::SelfReference(last_self)
+            },
+            COMPRESSION_PARENT => {
+                reader.read(parent_bits);
+                last_parent_used = true;
+                HunkEntry::Parent
+            },
+            COMPRESSION_PARENT_SELF | COMPRESSION_PARENT_0 | COMPRESSION_PARENT_1 => {
+                last_parent_used = true;
+                HunkEntry::Parent
+            },
+            _ => return Err(format!("Invalid CHD map entry type {} for hunk {}", kind, hunk)),
+        };
+
+        hunks.push(entry);
+    }
+
+    if reader.overflowed() {
+        return Err("CHD map truncated".into());
+    }
+
+    if last_parent_used {
+        log::warn!("CHD has hunks stored in a parent CHD, which are not supported; reading them will fail");
+    }
+
+    Ok(hunks)
+}
+
+struct TrackMetadata {
+    number: usize,
+    kind: TrackKind,
+    frames: usize,
+    pregap: usize,
+    pregap_stored: bool,
+    postgap: usize,
+    subcode: SubcodeKind,
+}
+
+fn read_tracks(file: &mut File, metadata_offset: u64) -> Result<(Vec<Track>, Vec<TrackSource>), String> {
+    let mut metadata = Vec::new();
+    let mut offset = metadata_offset;
+
+    while offset != 0 {
+        let header = read_at(file, offset, METADATA_HEADER_SIZE)?;
+        let tag = &header[0..4];
+        let length = (read_u32(&header, 4) & 0x00FF_FFFF) as usize;
+        let next = read_u64(&header, 8);
+
+        if (tag == TRACK_METADATA_TAG) || (tag == TRACK_METADATA_OLD_TAG) {
+            let data = read_at(file, offset + (METADATA_HEADER_SIZE as u64), length)?;
+            let text = String::from_utf8_lossy(&data);
+            metadata.push(parse_track_metadata(text.trim_end_matches('\0'))?);
+        }
+
+        offset = next;
+    }
+
+    if metadata.is_empty() {
+        return Err("CHD has no CD track metadata".into());
+    }
+
+    metadata.sort_by_key(|track| track.number);
+
+    let mut tracks = Vec::new();
+    let mut sources = Vec::new();
+    let mut position = 0;
+    let mut frame = 0;
+
+    for (index, track) in metadata.iter().enumerate() {
+        let mut pregap = track.pregap;
+        let mut pregap_stored = track.pregap_stored;
+        if (index == 0) && (pregap == 0) {
+            // The lead-in forms the (unstored) pregap of the first track.
+            pregap = LEAD_IN_SECTORS;
+            pregap_stored = false;
+        }
+
+        let data_start = if pregap_stored {
+            position
+        } else {
+            position + pregap
+        };
+        let data_end = data_start + track.frames;
+
+        tracks.push(Track {
+            number: track.number,
+            kind: track.kind,
+            pregap_start: position,
+            start: position + pregap,
+            end: data_end + track.postgap,
+        });
+
+        sources.push(TrackSource {
+            data_start,
+            data_end,
+            frame,
+            subcode: track.subcode,
+        });
+
+        position = data_end + track.postgap;
+        frame += ((track.frames + TRACK_PADDING - 1) / TRACK_PADDING) * TRACK_PADDING;
+    }
+
+    Ok((tracks, sources))
+}
+
+/// Parses track metadata of the form "TRACK:1 TYPE:MODE2_RAW SUBTYPE:NONE FRAMES:1234 PREGAP:0 PGTYPE:MODE1 ...".
+fn parse_track_metadata(text: &str) -> Result<TrackMetadata, String> {
+    let field = |name: &str| text.split_whitespace().find_map(|part| part.strip_prefix(name).and_then(|part| part.strip_prefix(':')));
+    let number_field = |name: &str| field(name).map_or(Ok(0), |value| value.parse::<usize>().map_err(|_| format!("Invalid CHD track metadata {}: {}", name, text)));
+
+    let kind = match field("TYPE") {
+        Some("MODE1_RAW") => TrackKind::Mode1,
+        Some("MODE2_RAW") => TrackKind::Mode2,
+        Some("AUDIO") => TrackKind::Audio,
+        Some(other) => return Err(format!("Unsupported CHD track type {}", other)),
+        None => return Err(format!("Missing CHD track type: {}", text)),
+    };
+
+    let subcode = match field("SUBTYPE") {
+        Some("RW") => SubcodeKind::Cooked,
+        Some("RW_RAW") => SubcodeKind::Raw,
+        _ => SubcodeKind::None,
+    };
+
+    Ok(TrackMetadata {
+        number: number_field("TRACK")?,
+        kind,
+        frames: number_field("FRAMES")?,
+        pregap: number_field("PREGAP")?,
+        pregap_stored: field("PGTYPE").map_or(false, |value| value.starts_with('V')),
+        postgap: number_field("POSTGAP")?,
+        subcode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_map() {
+        // Huffman code lengths (RLE coded) for types 0, 1, 4, 5, 7 and 9, then the types 0, RLE small (count 1 -> 3
+        // repeats), none, self, self 0, then the per hunk lengths / CRCs and the self reference index.
+        let map = [0x23, 0x00, 0x23, 0x03, 0x03, 0x10, 0x39, 0x0C, 0xB0, 0xAA, 0xBC, 0xD1, 0x4A, 0xBC, 0xD1, 0xEA, 0xBC, 0xD2, 0x8A, 0xBC, 0xD3, 0x2A, 0xBC, 0xDA, 0xBC, 0xD3];
+        let mut data = Vec::new();
+        data.extend_from_slice(&(map.len() as u32).to_be_bytes());
+        data.extend_from_slice(&0x1000u64.to_be_bytes()[2..8]);
+        data.extend_from_slice(&[0x00, 0x00, 8, 4, 0, 0]);
+        data.extend_from_slice(&map);
+
+        let path = std::env::temp_dir().join(format!("psx-rs-test-{}.chdmap", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        let hunks = read_compressed_map(&mut File::open(&path).unwrap(), 0, 8, 0x100);
+        std::fs::remove_file(&path).unwrap();
+
+        let compressed = |offset, length| {
+            HunkEntry::Compressed {
+                codec: 0,
+                offset,
+                length,
+            }
+        };
+        assert_eq!(
+            hunks.unwrap(),
+            vec![
+                compressed(0x1000, 10),
+                compressed(0x100A, 20),
+                compressed(0x101E, 30),
+                compressed(0x103C, 40),
+                compressed(0x1064, 50),
+                HunkEntry::Uncompressed(0x1096),
+                HunkEntry::SelfReference(3),
+                HunkEntry::SelfReference(3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::backends::cdrom::image::{
+    chd::{
+        flac::flac_decode,
+        lzma::lzma_decompress,
+    },
+    ecc::ecc_generate,
+    inflate::inflate,
+};
+
+pub(crate) const CD_FRAME_SIZE: usize = 2448;
+pub(crate) const CD_SECTOR_DATA_SIZE: usize = 2352;
+pub(crate) const CD_SUBCODE_DATA_SIZE: usize = 96;
+const CD_SYNC_HEADER: [u8; 12] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Codec {
+    Zlib,
+    Lzma,
+    CdZlib,
+    CdLzma,
+    CdFlac,
+}
+
+impl Codec {
+    /// Returns the codec for a header compressor tag, or None for an unused compressor slot.
+    pub(crate) fn from_tag(tag: u32) -> Result<Option<Codec>, String> {
+        match &tag.to_be_bytes() {
+            [0, 0, 0, 0] => Ok(None),
+            b"zlib" => Ok(Some(Codec::Zlib)),
+            b"lzma" => Ok(Some(Codec::Lzma)),
+            b"cdzl" => Ok(Some(Codec::CdZlib)),
+            b"cdlz" => Ok(Some(Codec::CdLzma)),
+            b"cdfl" => Ok(Some(Codec::CdFlac)),
+            bytes => Err(format!("Unsupported CHD codec '{}'", String::from_utf8_lossy(bytes))),
+        }
+    }
+}
+
+pub(crate) fn decompress(codec: Codec, data: &[u8], hunk_size: usize) -> Result<Vec<u8>, String> {
+    match codec {
+        Codec::Zlib => inflate(data, hunk_size),
+        Codec::Lzma => lzma_decompress(data, hunk_size),
+        Codec::CdZlib => decompress_cd(data, hunk_size, inflate),
+        Codec::CdLzma => decompress_cd(data, hunk_size, lzma_decompress),
+        Codec::CdFlac => decompress_cd_flac(data, hunk_size),
+    }
+}
+
+/// CD codecs store the sector data and subcode separately; the sync header and ECC may have been stripped from
+/// sectors (as flagged in the header), in which case they are regenerated.
+fn decompress_cd(data: &[u8], hunk_size: usize, base_decompress: fn(&[u8], usize) -> Result<Vec<u8>, String>) -> Result<Vec<u8>, String> {
+    let frames = hunk_size / CD_FRAME_SIZE;
+    let length_bytes = if hunk_size < 65536 {
+        2
+    } else {
+        3
+    };
+    let ecc_bytes = (frames + 7) / 8;
+    let header_bytes = ecc_bytes + length_bytes;
+
+    if data.len() < header_bytes {
+        return Err("CHD CD hunk too small".into());
+    }
+
+    let base_length = data[ecc_bytes..header_bytes].iter().fold(0, |length, &byte| (length << 8) | (byte as usize));
+    if (header_bytes + base_length) > data.len() {
+        return Err("CHD CD hunk base length out of range".into());
+    }
+
+    let sector_data = base_decompress(&data[header_bytes..(header_bytes + base_length)], frames * CD_SECTOR_DATA_SIZE)?;
+    let subcode_data = inflate(&data[(header_bytes + base_length)..], frames * CD_SUBCODE_DATA_SIZE)?;
+
+    let mut hunk = assemble_frames(&sector_data, &subcode_data, frames);
+
+    for frame in 0..frames {
+        if (data[frame / 8] & (1 << (frame % 8))) > 0 {
+            let sector = &mut hunk[(frame * CD_FRAME_SIZE)..((frame * CD_FRAME_SIZE) + CD_SECTOR_DATA_SIZE)];
+            sector[0..12].copy_from_slice(&CD_SYNC_HEADER);
+            ecc_generate(sector);
+        }
+    }
+
+    Ok(hunk)
+}
+
+/// The FLAC CD codec stores all sectors as 16-bit stereo audio (big endian once decoded), followed by the deflated
+/// subcode.
+fn decompress_cd_flac(data: &[u8], hunk_size: usize) -> Result<Vec<u8>, String> {
+    let frames = hunk_size / CD_FRAME_SIZE;
+
+    let (samples, consumed) = flac_decode(data, 2, (frames * CD_SECTOR_DATA_SIZE) / 4)?;
+    let sector_data: Vec<u8> = samples.iter().flat_map(|sample| sample.to_be_bytes().to_vec()).collect();
+    let subcode_data = inflate(&data[consumed..], frames * CD_SUBCODE_DATA_SIZE)?;
+
+    Ok(assemble_frames(&sector_data, &subcode_data, frames))
+}
+
+fn assemble_frames(sector_data: &[u8], subcode_data: &[u8], frames: usize) -> Vec<u8> {
+    let mut hunk = Vec::with_capacity(frames * CD_FRAME_SIZE);
+
+    for frame in 0..frames {
+        hunk.extend_from_slice(&sector_data[(frame * CD_SECTOR_DATA_SIZE)..((frame + 1) * CD_SECTOR_DATA_SIZE)]);
+        hunk.extend_from_slice(&subcode_data[(frame * CD_SUBCODE_DATA_SIZE)..((frame + 1) * CD_SUBCODE_DATA_SIZE)]);
+    }
+
+    hunk
+}
@@ -0,0 +1,305 @@
+//! FLAC frame decoder, as used by the CHD FLAC codecs (a sequence of frames with no stream header).
+
+const DEFAULT_SAMPLE_BITS: usize = 16;
+const SYNC_CODE: u32 = 0b11_1111_1111_1110;
+
+/// MSB first bit reader.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read_bit(&mut self) -> Result<u32, String> {
+        let byte = *self.data.get(self.position / 8).ok_or_else(|| "Unexpected end of FLAC stream".to_owned())?;
+        let bit = (byte >> (7 - (self.position % 8))) & 1;
+        self.position += 1;
+        Ok(bit as u32)
+    }
+
+    fn read(&mut self, count: usize) -> Result<u32, String> {
+        let mut value = 0u32;
+        for _ in 0..count {
+            value = (value << 1) | self.read_bit()?;
+        }
+        Ok(value)
+    }
+
+    fn read_signed(&mut self, count: usize) -> Result<i32, String> {
+        if count == 0 {
+            return Ok(0);
+        }
+        let value = self.read(count)?;
+        let shift = 32 - count;
+        Ok(((value << shift) as i32) >> shift)
+    }
+
+    fn read_unary(&mut self) -> Result<u32, String> {
+        let mut count = 0;
+        while self.read_bit()? == 0 {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn align_to_byte(&mut self) {
+        self.position = (self.position + 7) & !7;
+    }
+}
+
+/// Decodes FLAC frames until sample_count samples (per channel) have been produced.
+/// Returns the interleaved samples and the number of bytes consumed.
+pub(crate) fn flac_decode(data: &[u8], channels: usize, sample_count: usize) -> Result<(Vec<i16>, usize), String> {
+    let mut reader = BitReader {
+        data,
+        position: 0,
+    };
+    let mut output = Vec::with_capacity(sample_count * channels);
+
+    while output.len() < (sample_count * channels) {
+        let block = decode_frame(&mut reader, channels)?;
+        let block_size = block[0].len();
+        for i in 0..block_size {
+            for channel in block.iter() {
+                output.push(channel[i] as i16);
+            }
+        }
+    }
+
+    output.truncate(sample_count * channels);
+    Ok((output, reader.position / 8))
+}
+
+fn decode_frame(reader: &mut BitReader, channels: usize) -> Result<Vec<Vec<i32>>, String> {
+    if reader.read(14)? != SYNC_CODE {
+        return Err("FLAC frame sync not found".into());
+    }
+    reader.read(2)?;
+
+    let block_size_code = reader.read(4)?;
+    let sample_rate_code = reader.read(4)?;
+    let channel_assignment = reader.read(4)? as usize;
+    let sample_bits = match reader.read(3)? {
+        0 => DEFAULT_SAMPLE_BITS,
+        1 => 8,
+        2 => 12,
+        4 => 16,
+        5 => 20,
+        6 => 24,
+        code => return Err(format!("Unsupported FLAC sample size code {}", code)),
+    };
+    reader.read(1)?;
+
+    // Frame / sample number (UTF-8 style coding).
+    let first = reader.read(8)?;
+    let continuation_bytes = ((first as u8).leading_ones() as usize).saturating_sub(1);
+    reader.read(8 * continuation_bytes)?;
+
+    let block_size = match block_size_code {
+        1 => 192,
+        2..=5 => 576 << (block_size_code - 2),
+        6 => reader.read(8)? as usize + 1,
+        7 => reader.read(16)? as usize + 1,
+        8..=15 => 256 << (block_size_code - 8),
+        _ => return Err("Reserved FLAC block size".into()),
+    };
+
+    match sample_rate_code {
+        12 => {
+            reader.read(8)?;
+        },
+        13 | 14 => {
+            reader.read(16)?;
+        },
+        _ => {},
+    }
+
+    // CRC-8 (not checked).
+    reader.read(8)?;
+
+    let frame_channels = if channel_assignment < 8 {
+        channel_assignment + 1
+    } else {
+        2
+    };
+    if frame_channels != channels {
+        return Err(format!("FLAC frame has {} channels (expected {})", frame_channels, channels));
+    }
+
+    let mut block = Vec::with_capacity(channels);
+    for channel in 0..channels {
+        // The side channel has an extra bit.
+        let side = match channel_assignment {
+            8 | 10 => channel == 1,
+            9 => channel == 0,
+            _ => false,
+        };
+        block.push(decode_subframe(reader, block_size, sample_bits + (side as usize))?);
+    }
+
+    match channel_assignment {
+        8 => {
+            for i in 0..block_size {
+                block[1][i] = block[0][i] - block[1][i];
+            }
+        },
+        9 => {
+            for i in 0..block_size {
+                block[0][i] += block[1][i];
+            }
+        },
+        10 => {
+            for i in 0..block_size {
+                let side = block[1][i];
+                let mid = (block[0][i] << 1) | (side & 1);
+                block[0][i] = (mid + side) >> 1;
+                block[1][i] = (mid - side) >> 1;
+            }
+        },
+        _ => {},
+    }
+
+    // Padding and CRC-16 (not checked).
+    reader.align_to_byte();
+    reader.read(16)?;
+
+    Ok(block)
+}
+
+fn decode_subframe(reader: &mut BitReader, block_size: usize, sample_bits: usize) -> Result<Vec<i32>, String> {
+    reader.read(1)?;
+    let kind = reader.read(6)? as usize;
+    let wasted_bits = if reader.read(1)? > 0 {
+        reader.read_unary()? as usize + 1
+    } else {
+        0
+    };
+    let sample_bits = sample_bits - wasted_bits;
+
+    let mut samples = match kind {
+        0 => vec![reader.read_signed(sample_bits)?; block_size],
+        1 => (0..block_size).map(|_| reader.read_signed(sample_bits)).collect::<Result<Vec<i32>, String>>()?,
+        8..=12 => {
+            let order = kind - 8;
+            let mut samples = (0..order).map(|_| reader.read_signed(sample_bits)).collect::<Result<Vec<i32>, String>>()?;
+            decode_residual(reader, block_size, order, &mut samples)?;
+            restore_fixed(&mut samples, order);
+            samples
+        },
+        32..=63 => {
+            let order = kind - 31;
+            let mut samples = (0..order).map(|_| reader.read_signed(sample_bits)).collect::<Result<Vec<i32>, String>>()?;
+            let precision = reader.read(4)? as usize + 1;
+            if precision == 16 {
+                return Err("Invalid FLAC LPC precision".into());
+            }
+            let shift = reader.read_signed(5)?;
+            if shift < 0 {
+                return Err("Invalid FLAC LPC shift".into());
+            }
+            let coefficients = (0..order).map(|_| reader.read_signed(precision)).collect::<Result<Vec<i32>, String>>()?;
+            decode_residual(reader, block_size, order, &mut samples)?;
+            restore_lpc(&mut samples, &coefficients, shift as u32);
+            samples
+        },
+        _ => return Err(format!("Reserved FLAC subframe type {}", kind)),
+    };
+
+    if wasted_bits > 0 {
+        for sample in samples.iter_mut() {
+            *sample <<= wasted_bits;
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Appends the residual values to the warmup samples.
+fn decode_residual(reader: &mut BitReader, block_size: usize, order: usize, samples: &mut Vec<i32>) -> Result<(), String> {
+    let parameter_bits = match reader.read(2)? {
+        0 => 4,
+        1 => 5,
+        _ => return Err("Reserved FLAC residual coding method".into()),
+    };
+    let escape = (1 << parameter_bits) - 1;
+
+    let partition_order = reader.read(4)?;
+    let partitions = 1 << partition_order;
+    if ((block_size >> partition_order) < order) || ((block_size % partitions) != 0) {
+        return Err("Invalid FLAC residual partition order".into());
+    }
+
+    for partition in 0..partitions {
+        let count = (block_size >> partition_order)
+            - (if partition == 0 {
+                order
+            } else {
+                0
+            });
+        let parameter = reader.read(parameter_bits)?;
+
+        if parameter == escape {
+            let bits = reader.read(5)? as usize;
+            for _ in 0..count {
+                samples.push(reader.read_signed(bits)?);
+            }
+        } else {
+            for _ in 0..count {
+                let quotient = reader.read_unary()?;
+                let value = (quotient << parameter) | reader.read(parameter as usize)?;
+                samples.push(((value >> 1) as i32) ^ -((value & 1) as i32));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn restore_fixed(samples: &mut [i32], order: usize) {
+    for i in order..samples.len() {
+        let prediction = match order {
+            0 => 0,
+            1 => samples[i - 1],
+            2 => (2 * samples[i - 1]) - samples[i - 2],
+            3 => (3 * samples[i - 1]) - (3 * samples[i - 2]) + samples[i - 3],
+            _ => (4 * samples[i - 1]) - (6 * samples[i - 2]) + (4 * samples[i - 3]) - samples[i - 4],
+        };
+        samples[i] += prediction;
+    }
+}
+
+fn restore_lpc(samples: &mut [i32], coefficients: &[i32], shift: u32) {
+    let order = coefficients.len();
+    for i in order..samples.len() {
+        let prediction: i64 = coefficients.iter().enumerate().map(|(j, &coefficient)| (coefficient as i64) * (samples[i - 1 - j] as i64)).sum();
+        samples[i] += (prediction >> shift) as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stereo frame of 8 16-bit samples: a verbatim left subframe, and an order 2 LPC right subframe
+    /// (coefficients [2, -1], shift 0, Rice coded residual [1, -1, -1, 3, -4, 1]).
+    const FRAME: [u8; 37] = [
+        0xFF, 0xF8, 0x69, 0x18, 0x00, 0x07, 0xAA, 0x02, 0x00, 0x64, 0xFF, 0x38, 0x01, 0x2C, 0xFE, 0x70, 0x01, 0xF4, 0xFD, 0xA8, 0x02, 0xBC, 0xFC, 0xE0, 0x42, 0x00, 0x0A, 0x00,
+        0x14, 0x30, 0x17, 0x80, 0x2B, 0xC4, 0x34, 0xB1, 0x2F,
+    ];
+
+    #[test]
+    fn test_verbatim_lpc_frame() {
+        let (samples, consumed) = flac_decode(&FRAME, 2, 8).unwrap();
+
+        let left = [100, -200, 300, -400, 500, -600, 700, -800];
+        let right = [10, 20, 31, 41, 50, 62, 70, 79];
+        let expected: Vec<i16> = left.iter().zip(right.iter()).flat_map(|(&l, &r)| vec![l, r]).collect();
+        assert_eq!(samples, expected);
+        assert_eq!(consumed, FRAME.len());
+    }
+
+    #[test]
+    fn test_channel_mismatch() {
+        assert!(flac_decode(&FRAME, 1, 8).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! CHD bitstream and Huffman decoder, used for the compressed hunk map.
+
+/// MSB first bit reader; reading past the end returns zero bits.
+pub(crate) struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            data,
+            position: 0,
+        }
+    }
+
+    pub(crate) fn peek(&self, count: usize) -> u32 {
+        let mut value = 0;
+        for i in 0..count {
+            let position = self.position + i;
+            let byte = self.data.get(position / 8).copied().unwrap_or(0);
+            value = (value << 1) | (((byte >> (7 - (position % 8))) & 1) as u32);
+        }
+        value
+    }
+
+    pub(crate) fn read(&mut self, count: usize) -> u32 {
+        let value = self.peek(count);
+        self.position += count;
+        value
+    }
+
+    pub(crate) fn overflowed(&self) -> bool {
+        self.position > (self.data.len() * 8)
+    }
+}
+
+/// Huffman decoder with canonical codes, as imported from the RLE encoded code lengths.
+pub(crate) struct HuffmanDecoder {
+    max_bits: usize,
+    /// Indexed by max_bits of lookahead; contains (symbol, code length).
+    lookup: Vec<(u32, usize)>,
+}
+
+impl HuffmanDecoder {
+    pub(crate) fn import_tree_rle(reader: &mut BitReader, symbol_count: usize, max_bits: usize) -> Result<HuffmanDecoder, String> {
+        let length_bits = if max_bits >= 16 {
+            5
+        } else if max_bits >= 8 {
+            4
+        } else {
+            3
+        };
+
+        let mut lengths = vec![0; symbol_count];
+        let mut symbol = 0;
+        while symbol < symbol_count {
+            let length = reader.read(length_bits) as usize;
+            if length != 1 {
+                lengths[symbol] = length;
+                symbol += 1;
+            } else {
+                let length = reader.read(length_bits) as usize;
+                if length == 1 {
+                    lengths[symbol] = length;
+                    symbol += 1;
+                } else {
+                    let repeat_count = reader.read(length_bits) as usize + 3;
+                    if symbol + repeat_count > symbol_count {
+                        return Err("Huffman tree RLE overflow".into());
+                    }
+                    for _ in 0..repeat_count {
+                        lengths[symbol] = length;
+                        symbol += 1;
+                    }
+                }
+            }
+        }
+
+        HuffmanDecoder::from_lengths(&lengths, max_bits)
+    }
+
+    fn from_lengths(lengths: &[usize], max_bits: usize) -> Result<HuffmanDecoder, String> {
+        // Codes are assigned starting from the longest length.
+        let mut histogram = [0u32; 33];
+        for &length in lengths.iter() {
+            if length > max_bits {
+                return Err("Huffman code length too large".into());
+            }
+            histogram[length] += 1;
+        }
+
+        let mut start = 0u32;
+        for length in (1..=32).rev() {
+            let next_start = (start + histogram[length]) >> 1;
+            if (length != 1) && ((next_start * 2) != (start + histogram[length])) {
+                return Err("Invalid Huffman tree".into());
+            }
+            histogram[length] = start;
+            start = next_start;
+        }
+
+        let mut lookup = vec![(0, 0); 1 << max_bits];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length == 0 {
+                continue;
+            }
+
+            let code = histogram[length];
+            histogram[length] += 1;
+
+            let shift = max_bits - length;
+            let base = (code as usize) << shift;
+            if (base + (1 << shift)) > lookup.len() {
+                return Err("Invalid Huffman tree".into());
+            }
+            for entry in lookup[base..(base + (1 << shift))].iter_mut() {
+                *entry = (symbol as u32, length);
+            }
+        }
+
+        Ok(HuffmanDecoder {
+            max_bits,
+            lookup,
+        })
+    }
+
+    pub(crate) fn decode_one(&self, reader: &mut BitReader) -> u32 {
+        let (symbol, length) = self.lookup[reader.peek(self.max_bits) as usize];
+        reader.read(length.max(1));
+        symbol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_tree_rle() {
+        // Lengths (4 bit RLE): 1, 1 (escaped), 0 x 3 (repeat), 2, 3, 0, 3, 0 x 8 (repeat); canonical codes are
+        // assigned from the longest length: 5 = 000, 7 = 001, 4 = 01, 0 = 1.
+        let tree = [0x11, 0x10, 0x02, 0x30, 0x31, 0x05];
+        let mut reader = BitReader::new(&tree);
+        let decoder = HuffmanDecoder::import_tree_rle(&mut reader, 16, 8).unwrap();
+
+        let data = [0b1010_0000, 0b1100_0000];
+        let mut reader = BitReader::new(&data);
+        let symbols: Vec<u32> = (0..5).map(|_| decoder.decode_one(&mut reader)).collect();
+        assert_eq!(symbols, vec![0, 4, 5, 7, 0]);
+        assert!(!reader.overflowed());
+    }
+
+    #[test]
+    fn test_invalid_tree() {
+        // Three codes of length 2 are not a valid tree.
+        let tree = [0x22, 0x21, 0x0A];
+        let mut reader = BitReader::new(&tree);
+        assert!(HuffmanDecoder::import_tree_rle(&mut reader, 16, 8).is_err());
+    }
+}
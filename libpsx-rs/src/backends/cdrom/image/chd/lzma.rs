@@ -0,0 +1,314 @@
+//! Raw LZMA decoder, as used by the CHD LZMA codecs (no header; lc = 3, lp = 0, pb = 2).
+
+const LITERAL_CONTEXT_BITS: usize = 3;
+const LITERAL_POSITION_BITS: usize = 0;
+const POSITION_BITS: usize = 2;
+const STATE_COUNT: usize = 12;
+const POSITION_STATES_MAX: usize = 1 << 4;
+const LENGTH_TO_POSITION_STATES: usize = 4;
+const END_POSITION_MODEL_INDEX: usize = 14;
+const FULL_DISTANCES: usize = 1 << (END_POSITION_MODEL_INDEX >> 1);
+const ALIGN_BITS: usize = 4;
+const MATCH_MIN_LENGTH: usize = 2;
+const PROBABILITY_INITIAL: u16 = 1 << 10;
+
+struct RangeDecoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    range: u32,
+    code: u32,
+}
+
+impl<'a> RangeDecoder<'a> {
+    fn new(data: &'a [u8]) -> Result<RangeDecoder<'a>, String> {
+        let mut decoder = RangeDecoder {
+            data,
+            position: 0,
+            range: 0xFFFF_FFFF,
+            code: 0,
+        };
+
+        if decoder.next_byte()? != 0 {
+            return Err("Invalid LZMA stream".into());
+        }
+
+        for _ in 0..4 {
+            decoder.code = (decoder.code << 8) | (decoder.next_byte()? as u32);
+        }
+
+        Ok(decoder)
+    }
+
+    fn next_byte(&mut self) -> Result<u8, String> {
+        let value = *self.data.get(self.position).ok_or_else(|| "Unexpected end of LZMA stream".to_owned())?;
+        self.position += 1;
+        Ok(value)
+    }
+
+    fn normalize(&mut self) -> Result<(), String> {
+        if self.range < (1 << 24) {
+            self.range <<= 8;
+            self.code = (self.code << 8) | (self.next_byte()? as u32);
+        }
+        Ok(())
+    }
+
+    fn decode_bit(&mut self, probability: &mut u16) -> Result<usize, String> {
+        let bound = (self.range >> 11) * (*probability as u32);
+
+        let bit = if self.code < bound {
+            *probability += ((1 << 11) - *probability) >> 5;
+            self.range = bound;
+            0
+        } else {
+            *probability -= *probability >> 5;
+            self.code -= bound;
+            self.range -= bound;
+            1
+        };
+
+        self.normalize()?;
+        Ok(bit)
+    }
+
+    fn decode_direct_bits(&mut self, count: usize) -> Result<usize, String> {
+        let mut value = 0;
+        for _ in 0..count {
+            self.range >>= 1;
+            let bit = if self.code >= self.range {
+                self.code -= self.range;
+                1
+            } else {
+                0
+            };
+            self.normalize()?;
+            value = (value << 1) | bit;
+        }
+        Ok(value)
+    }
+
+    fn decode_tree(&mut self, probabilities: &mut [u16], bits: usize) -> Result<usize, String> {
+        let mut m = 1;
+        for _ in 0..bits {
+            m = (m << 1) + self.decode_bit(&mut probabilities[m])?;
+        }
+        Ok(m - (1 << bits))
+    }
+
+    fn decode_reverse_tree(&mut self, probabilities: &mut [u16], bits: usize) -> Result<usize, String> {
+        let mut m = 1;
+        let mut symbol = 0;
+        for i in 0..bits {
+            let bit = self.decode_bit(&mut probabilities[m])?;
+            m = (m << 1) + bit;
+            symbol |= bit << i;
+        }
+        Ok(symbol)
+    }
+}
+
+struct LengthDecoder {
+    choice: u16,
+    choice2: u16,
+    low: Vec<[u16; 1 << 3]>,
+    mid: Vec<[u16; 1 << 3]>,
+    high: [u16; 1 << 8],
+}
+
+impl LengthDecoder {
+    fn new() -> LengthDecoder {
+        LengthDecoder {
+            choice: PROBABILITY_INITIAL,
+            choice2: PROBABILITY_INITIAL,
+            low: vec![[PROBABILITY_INITIAL; 1 << 3]; POSITION_STATES_MAX],
+            mid: vec![[PROBABILITY_INITIAL; 1 << 3]; POSITION_STATES_MAX],
+            high: [PROBABILITY_INITIAL; 1 << 8],
+        }
+    }
+
+    fn decode(&mut self, decoder: &mut RangeDecoder, position_state: usize) -> Result<usize, String> {
+        if decoder.decode_bit(&mut self.choice)? == 0 {
+            return decoder.decode_tree(&mut self.low[position_state], 3);
+        }
+
+        if decoder.decode_bit(&mut self.choice2)? == 0 {
+            return Ok(8 + decoder.decode_tree(&mut self.mid[position_state], 3)?);
+        }
+
+        Ok(16 + decoder.decode_tree(&mut self.high, 8)?)
+    }
+}
+
+/// Decompresses a headerless LZMA stream, producing exactly output_size bytes.
+pub(crate) fn lzma_decompress(data: &[u8], output_size: usize) -> Result<Vec<u8>, String> {
+    let mut decoder = RangeDecoder::new(data)?;
+    let mut output: Vec<u8> = Vec::with_capacity(output_size);
+
+    let mut literal = vec![PROBABILITY_INITIAL; 0x300 << (LITERAL_CONTEXT_BITS + LITERAL_POSITION_BITS)];
+    let mut is_match = [PROBABILITY_INITIAL; STATE_COUNT << 4];
+    let mut is_rep = [PROBABILITY_INITIAL; STATE_COUNT];
+    let mut is_rep_g0 = [PROBABILITY_INITIAL; STATE_COUNT];
+    let mut is_rep_g1 = [PROBABILITY_INITIAL; STATE_COUNT];
+    let mut is_rep_g2 = [PROBABILITY_INITIAL; STATE_COUNT];
+    let mut is_rep0_long = [PROBABILITY_INITIAL; STATE_COUNT << 4];
+    let mut position_slot = vec![[PROBABILITY_INITIAL; 1 << 6]; LENGTH_TO_POSITION_STATES];
+    let mut position_decoders = [PROBABILITY_INITIAL; 1 + FULL_DISTANCES - END_POSITION_MODEL_INDEX];
+    let mut align = [PROBABILITY_INITIAL; 1 << ALIGN_BITS];
+    let mut length_decoder = LengthDecoder::new();
+    let mut rep_length_decoder = LengthDecoder::new();
+
+    let mut state = 0;
+    let mut reps = [0usize; 4];
+
+    while output.len() < output_size {
+        let position_state = output.len() & ((1 << POSITION_BITS) - 1);
+
+        if decoder.decode_bit(&mut is_match[(state << 4) + position_state])? == 0 {
+            let previous = output.last().copied().unwrap_or(0) as usize;
+            let literal_state = ((output.len() & ((1 << LITERAL_POSITION_BITS) - 1)) << LITERAL_CONTEXT_BITS) + (previous >> (8 - LITERAL_CONTEXT_BITS));
+            let probabilities = &mut literal[(0x300 * literal_state)..(0x300 * (literal_state + 1))];
+            let mut symbol = 1;
+
+            if state >= 7 {
+                let mut match_byte = match_source(&output, reps[0] + 1)? as usize;
+                while symbol < 0x100 {
+                    let match_bit = (match_byte >> 7) & 1;
+                    match_byte <<= 1;
+                    let bit = decoder.decode_bit(&mut probabilities[((1 + match_bit) << 8) + symbol])?;
+                    symbol = (symbol << 1) | bit;
+                    if match_bit != bit {
+                        break;
+                    }
+                }
+            }
+
+            while symbol < 0x100 {
+                symbol = (symbol << 1) | decoder.decode_bit(&mut probabilities[symbol])?;
+            }
+
+            output.push((symbol - 0x100) as u8);
+            state = if state < 4 {
+                0
+            } else if state < 10 {
+                state - 3
+            } else {
+                state - 6
+            };
+            continue;
+        }
+
+        let length;
+
+        if decoder.decode_bit(&mut is_rep[state])? != 0 {
+            if output.is_empty() {
+                return Err("Invalid LZMA stream: repeat match at the start".into());
+            }
+
+            if decoder.decode_bit(&mut is_rep_g0[state])? == 0 {
+                if decoder.decode_bit(&mut is_rep0_long[(state << 4) + position_state])? == 0 {
+                    // Short repeat (single byte).
+                    state = if state < 7 {
+                        9
+                    } else {
+                        11
+                    };
+                    let value = match_source(&output, reps[0] + 1)?;
+                    output.push(value);
+                    continue;
+                }
+            } else {
+                let distance;
+                if decoder.decode_bit(&mut is_rep_g1[state])? == 0 {
+                    distance = reps[1];
+                } else {
+                    if decoder.decode_bit(&mut is_rep_g2[state])? == 0 {
+                        distance = reps[2];
+                    } else {
+                        distance = reps[3];
+                        reps[3] = reps[2];
+                    }
+                    reps[2] = reps[1];
+                }
+                reps[1] = reps[0];
+                reps[0] = distance;
+            }
+
+            length = rep_length_decoder.decode(&mut decoder, position_state)?;
+            state = if state < 7 {
+                8
+            } else {
+                11
+            };
+        } else {
+            reps[3] = reps[2];
+            reps[2] = reps[1];
+            reps[1] = reps[0];
+            length = length_decoder.decode(&mut decoder, position_state)?;
+            state = if state < 7 {
+                7
+            } else {
+                10
+            };
+
+            let length_state = length.min(LENGTH_TO_POSITION_STATES - 1);
+            let slot = decoder.decode_tree(&mut position_slot[length_state], 6)?;
+            reps[0] = if slot < 4 {
+                slot
+            } else {
+                let direct_bits = (slot >> 1) - 1;
+                let base = (2 | (slot & 1)) << direct_bits;
+                if slot < END_POSITION_MODEL_INDEX {
+                    base + decoder.decode_reverse_tree(&mut position_decoders[(base - slot)..], direct_bits)?
+                } else {
+                    base + (decoder.decode_direct_bits(direct_bits - ALIGN_BITS)? << ALIGN_BITS) + decoder.decode_reverse_tree(&mut align, ALIGN_BITS)?
+                }
+            };
+
+            if reps[0] == 0xFFFF_FFFF {
+                // End marker.
+                break;
+            }
+        }
+
+        let length = (length + MATCH_MIN_LENGTH).min(output_size - output.len());
+        for _ in 0..length {
+            let value = match_source(&output, reps[0] + 1)?;
+            output.push(value);
+        }
+    }
+
+    if output.len() != output_size {
+        return Err(format!("LZMA stream size mismatch: {} bytes (expected {})", output.len(), output_size));
+    }
+
+    Ok(output)
+}
+
+fn match_source(output: &[u8], distance: usize) -> Result<u8, String> {
+    if distance > output.len() {
+        return Err("LZMA distance too far back".into());
+    }
+    Ok(output[output.len() - distance])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompress() {
+        let data = [
+            0x00, 0x3A, 0x1A, 0x08, 0xCE, 0x76, 0xC7, 0xE5, 0xE9, 0xD6, 0x07, 0x34, 0xC3, 0xD1, 0x0E, 0xBF, 0xCE, 0x55, 0xE1, 0xAA, 0xBD, 0xE0, 0xE4, 0x8F, 0x98, 0x01, 0xDD, 0x8D,
+            0xE5, 0x07, 0x54, 0x9E, 0x65, 0x25, 0x5F, 0x27, 0x3A, 0x6A, 0x7E, 0xB4, 0xD3, 0x49, 0x05, 0x51, 0x22, 0xA3, 0x48, 0xEB, 0xC9, 0x60, 0xC2, 0x62, 0x7A, 0xD3, 0xFF, 0xEF,
+            0x5D, 0x80, 0x00,
+        ];
+        let expected: &[u8] = b"the quick brown fox jumps over the lazy dog; the quick brown fox jumps over the lazy cat";
+
+        assert_eq!(lzma_decompress(&data, expected.len()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_invalid_stream() {
+        assert!(lzma_decompress(&[0x01, 0x00, 0x00, 0x00, 0x00], 1).is_err());
+    }
+}
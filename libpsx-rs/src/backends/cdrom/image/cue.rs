@@ -0,0 +1,494 @@
+use crate::backends::cdrom::image::{
+    ecc::{
+        ecc_generate,
+        edc_compute,
+    },
+    ecm::EcmFile,
+    *,
+};
+use std::{
+    ffi::OsString,
+    fs::File,
+    io::{
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// User data size of a cooked (ISO) sector.
+const COOKED_SECTOR_SIZE: usize = 2048;
+/// Size of a mode 2 sector without the sync pattern and header, as stored by STR / XA stream rips.
+const MODE2_SECTOR_SIZE: usize = 2336;
+/// Subheader submode of a plain data sector.
+const SUBMODE_DATA: u8 = 0x08;
+
+enum BinSource {
+    Raw(File),
+    Ecm(EcmFile),
+    /// User data only (2048 byte sectors); the rest of the sector is rebuilt on read.
+    Cooked(File),
+    /// Mode 2 sectors starting at the subheader (2336 bytes); the sync pattern and header are rebuilt on read.
+    Mode2(File),
+}
+
+struct BinFile {
+    source: BinSource,
+    sectors: usize,
+}
+
+impl BinFile {
+    /// The absolute sector address is needed to rebuild the header of cooked sectors.
+    fn read_sector(&mut self, file_sector: usize, address: usize) -> Result<Vec<u8>, String> {
+        let offset = (file_sector * SECTOR_SIZE) as u64;
+        let mut sector = vec![0; SECTOR_SIZE];
+
+        match self.source {
+            BinSource::Raw(ref mut file) => read_file_at(file, offset, &mut sector, file_sector)?,
+            BinSource::Ecm(ref mut file) => file.read_at(offset, &mut sector)?,
+            BinSource::Cooked(ref mut file) => {
+                let mut data = vec![0; COOKED_SECTOR_SIZE];
+                read_file_at(file, (file_sector * COOKED_SECTOR_SIZE) as u64, &mut data, file_sector)?;
+                return Ok(cooked_to_raw_sector(address, &data));
+            },
+            BinSource::Mode2(ref mut file) => {
+                sector = generate_empty_sector(address, TrackKind::Mode2);
+                read_file_at(file, (file_sector * MODE2_SECTOR_SIZE) as u64, &mut sector[0x10..], file_sector)?;
+            },
+        }
+
+        Ok(sector)
+    }
+}
+
+fn read_file_at(file: &mut File, offset: u64, buffer: &mut [u8], file_sector: usize) -> Result<(), String> {
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Seeking to sector {} failed: {}", file_sector, e))?;
+    file.read_exact(buffer).map_err(|e| format!("Reading sector {} failed: {}", file_sector, e))
+}
+
+/// Rebuilds a raw mode 2 form 1 sector around cooked user data; the subheader isn't stored, so it is a plain data
+/// sector.
+fn cooked_to_raw_sector(address: usize, data: &[u8]) -> Vec<u8> {
+    let mut sector = generate_empty_sector(address, TrackKind::Mode2);
+    sector[0x10..0x18].copy_from_slice(&[0, 0, SUBMODE_DATA, 0, 0, 0, SUBMODE_DATA, 0]);
+    sector[0x18..0x818].copy_from_slice(data);
+
+    let edc = edc_compute(&sector[0x10..0x818]);
+    sector[0x818..0x81C].copy_from_slice(&edc.to_le_bytes());
+    ecc_generate(&mut sector);
+    sector
+}
+
+/// Location of the stored part of a track within the BIN files.
+#[derive(Debug, Clone)]
+struct TrackSource {
+    file_index: usize,
+    /// Absolute sector address of the first sector stored in the file.
+    data_start: usize,
+    /// Sector offset within the file of data_start.
+    file_sector: usize,
+}
+
+/// Track as described in the cue sheet; index positions are relative to the start of the file.
+struct CueTrack {
+    number: usize,
+    kind: TrackKind,
+    file_index: usize,
+    pregap: usize,
+    index0: Option<usize>,
+    index1: Option<usize>,
+}
+
+/// CUE sheet with raw (2352 byte sector) BIN tracks.
+pub(crate) struct CueImage {
+    files: Vec<BinFile>,
+    tracks: Vec<Track>,
+    sources: Vec<TrackSource>,
+}
+
+impl CueImage {
+    pub(crate) fn open(path: &Path) -> Result<CueImage, String> {
+        let sheet = std::fs::read_to_string(path).map_err(|e| format!("Unable to read cue sheet {}: {}", path.display(), e))?;
+        let base_path = path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut files = Vec::new();
+        let mut cue_tracks: Vec<CueTrack> = Vec::new();
+
+        for (line_index, line) in sheet.lines().enumerate() {
+            let tokens = tokenize(line);
+            let error = |message: &str| format!("Cue sheet line {}: {}", line_index + 1, message);
+
+            match tokens.first().map(|t| t.to_uppercase()).as_deref() {
+                Some("FILE") => {
+                    let name = tokens.get(1).ok_or_else(|| error("missing file name"))?;
+                    let file_type = tokens.get(2).map(|t| t.to_uppercase()).unwrap_or_default();
+                    if file_type != "BINARY" {
+                        return Err(error(&format!("unsupported file type {}", file_type)));
+                    }
+                    files.push(open_bin_file(&base_path.join(name))?);
+                },
+                Some("TRACK") => {
+                    if files.is_empty() {
+                        return Err(error("TRACK before FILE"));
+                    }
+
+                    let number = tokens.get(1).and_then(|t| t.parse::<usize>().ok()).ok_or_else(|| error("invalid track number"))?;
+                    let kind = match tokens.get(2).map(|t| t.to_uppercase()).as_deref() {
+                        Some("AUDIO") => TrackKind::Audio,
+                        Some("MODE1/2352") => TrackKind::Mode1,
+                        Some("MODE2/2352") => TrackKind::Mode2,
+                        Some(other) => return Err(error(&format!("unsupported track mode {}", other))),
+                        None => return Err(error("missing track mode")),
+                    };
+
+                    cue_tracks.push(CueTrack {
+                        number,
+                        kind,
+                        file_index: files.len() - 1,
+                        pregap: 0,
+                        index0: None,
+                        index1: None,
+                    });
+                },
+                Some("INDEX") => {
+                    let track = cue_tracks.last_mut().ok_or_else(|| error("INDEX before TRACK"))?;
+                    let number = tokens.get(1).and_then(|t| t.parse::<usize>().ok()).ok_or_else(|| error("invalid index number"))?;
+                    let position = tokens.get(2).and_then(|t| parse_msf(t)).ok_or_else(|| error("invalid index position"))?;
+
+                    match number {
+                        0 => track.index0 = Some(position),
+                        1 => track.index1 = Some(position),
+                        _ => {},
+                    }
+                },
+                Some("PREGAP") => {
+                    let track = cue_tracks.last_mut().ok_or_else(|| error("PREGAP before TRACK"))?;
+                    track.pregap = tokens.get(1).and_then(|t| parse_msf(t)).ok_or_else(|| error("invalid pregap length"))?;
+                },
+                _ => {},
+            }
+        }
+
+        if cue_tracks.is_empty() {
+            return Err(format!("No tracks in cue sheet {}", path.display()));
+        }
+
+        let (tracks, sources) = layout_tracks(&file_sectors(&files), &cue_tracks)?;
+
+        Ok(CueImage {
+            files,
+            tracks,
+            sources,
+        })
+    }
+
+    /// Opens a BIN (or ECM encoded BIN) file without a cue sheet, as a single mode 2 track.
+    pub(crate) fn open_bin(path: &Path) -> Result<CueImage, String> {
+        CueImage::open_single_track(open_bin_file(path)?)
+    }
+
+    /// Opens a cooked ISO (2048 byte sectors) as a single mode 2 track.
+    pub(crate) fn open_iso(path: &Path) -> Result<CueImage, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+        let size = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+
+        if size % COOKED_SECTOR_SIZE != 0 {
+            log::warn!("{} is not a whole number of cooked sectors; ignoring the trailing {} bytes", path.display(), size % COOKED_SECTOR_SIZE);
+        }
+
+        CueImage::open_single_track(BinFile {
+            source: BinSource::Cooked(file),
+            sectors: size / COOKED_SECTOR_SIZE,
+        })
+    }
+
+    /// Opens a ripped STR / XA stream as a single mode 2 track.
+    /// The sectors can be raw (2352 bytes), mode 2 starting at the subheader (2336 bytes), or user data only (2048
+    /// bytes, for video only streams).
+    pub(crate) fn open_stream(path: &Path) -> Result<CueImage, String> {
+        let mut file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+        let size = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+
+        let mut start = [0; 12];
+        file.read_exact(&mut start).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+        if (start == SYNC_PATTERN) && (size % SECTOR_SIZE == 0) {
+            return CueImage::open_bin(path);
+        }
+
+        // Mode 2 sectors start with the subheader, which is stored twice.
+        let (source, sectors) = if (start[0..4] == start[4..8]) && (size % MODE2_SECTOR_SIZE == 0) {
+            (BinSource::Mode2(file), size / MODE2_SECTOR_SIZE)
+        } else if size % COOKED_SECTOR_SIZE == 0 {
+            (BinSource::Cooked(file), size / COOKED_SECTOR_SIZE)
+        } else {
+            return Err(format!("Unrecognised stream layout: {}", path.display()));
+        };
+
+        CueImage::open_single_track(BinFile {
+            source,
+            sectors,
+        })
+    }
+
+    fn open_single_track(file: BinFile) -> Result<CueImage, String> {
+        let files = vec![file];
+        let cue_tracks = [CueTrack {
+            number: 1,
+            kind: TrackKind::Mode2,
+            file_index: 0,
+            pregap: 0,
+            index0: None,
+            index1: Some(0),
+        }];
+
+        let (tracks, sources) = layout_tracks(&file_sectors(&files), &cue_tracks)?;
+
+        Ok(CueImage {
+            files,
+            tracks,
+            sources,
+        })
+    }
+}
+
+impl DiscImage for CueImage {
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn read_raw_sector(&mut self, address: usize) -> Result<Vec<u8>, String> {
+        let track_index =
+            self.tracks.iter().position(|track| (address >= track.pregap_start) && (address < track.end)).ok_or_else(|| format!("Sector {} is outside of the disc", address))?;
+        let track = &self.tracks[track_index];
+        let source = &self.sources[track_index];
+
+        if address < source.data_start {
+            return Ok(generate_empty_sector(address, track.kind));
+        }
+
+        self.files[source.file_index].read_sector(source.file_sector + (address - source.data_start), address)
+    }
+}
+
+/// Opens a BIN file; if it is missing, an ECM encoded copy (name.bin.ecm) is used instead.
+fn open_bin_file(path: &Path) -> Result<BinFile, String> {
+    let ecm_path = {
+        let mut name = OsString::from(path.as_os_str());
+        name.push(".ecm");
+        PathBuf::from(name)
+    };
+
+    let is_ecm = path.extension().map(|e| e.eq_ignore_ascii_case("ecm")).unwrap_or(false);
+    let (source, size, path) = if is_ecm || (!path.exists() && ecm_path.exists()) {
+        let path = if is_ecm {
+            path
+        } else {
+            ecm_path.as_path()
+        };
+        let file = EcmFile::open(path)?;
+        let size = file.len() as usize;
+        (BinSource::Ecm(file), size, path)
+    } else {
+        let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+        let size = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+        (BinSource::Raw(file), size, path)
+    };
+
+    if size % SECTOR_SIZE != 0 {
+        log::warn!("{} is not a whole number of raw sectors; ignoring the trailing {} bytes", path.display(), size % SECTOR_SIZE);
+    }
+
+    Ok(BinFile {
+        source,
+        sectors: size / SECTOR_SIZE,
+    })
+}
+
+fn file_sectors(files: &[BinFile]) -> Vec<usize> {
+    files.iter().map(|file| file.sectors).collect()
+}
+
+/// Places the tracks on the disc; stored pregaps (INDEX 00) come from the file, PREGAP sectors are generated.
+fn layout_tracks(file_sectors: &[usize], cue_tracks: &[CueTrack]) -> Result<(Vec<Track>, Vec<TrackSource>), String> {
+    let mut tracks: Vec<Track> = Vec::new();
+    let mut sources = Vec::new();
+    let mut position = 0;
+
+    for (index, cue_track) in cue_tracks.iter().enumerate() {
+        let index1 = cue_track.index1.ok_or_else(|| format!("Track {} has no INDEX 01", cue_track.number))?;
+        let mut file_start = cue_track.index0.unwrap_or(index1);
+
+        let file_end = match cue_tracks.get(index + 1) {
+            Some(next) if next.file_index == cue_track.file_index => next.index0.or(next.index1).unwrap_or(file_sectors[cue_track.file_index]),
+            _ => file_sectors[cue_track.file_index],
+        };
+
+        if (file_start > index1) || (index1 > file_end) {
+            return Err(format!("Track {} indices are out of order or beyond the end of the file", cue_track.number));
+        }
+
+        let pregap_start = if tracks.is_empty() {
+            // Track 1 INDEX 01 is always at 00:02:00; the lead-in forms its pregap, so a stored pregap overlaps it
+            // (clipped to the lead-in) and a PREGAP is already covered.
+            let stored_pregap = (index1 - file_start).min(LEAD_IN_SECTORS);
+            file_start = index1 - stored_pregap;
+            position = LEAD_IN_SECTORS - stored_pregap;
+            0
+        } else {
+            let pregap_start = position;
+            position += cue_track.pregap;
+            pregap_start
+        };
+
+        sources.push(TrackSource {
+            file_index: cue_track.file_index,
+            data_start: position,
+            file_sector: file_start,
+        });
+
+        tracks.push(Track {
+            number: cue_track.number,
+            kind: cue_track.kind,
+            pregap_start,
+            start: position + (index1 - file_start),
+            end: position + (file_end - file_start),
+        });
+
+        position += file_end - file_start;
+    }
+
+    Ok((tracks, sources))
+}
+
+/// Parses a mm:ss:ff position into a sector count.
+fn parse_msf(value: &str) -> Option<usize> {
+    let parts: Vec<usize> = value.split(':').map(|part| part.parse::<usize>().ok()).collect::<Option<Vec<usize>>>()?;
+    match parts.as_slice() {
+        [minute, second, frame] => Some((minute * SECTORS_PER_MINUTE) + (second * SECTORS_PER_SECOND) + frame),
+        _ => None,
+    }
+}
+
+/// Splits a cue sheet line into tokens; double quoted strings form a single token.
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in line.trim().chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::replace(&mut current, String::new()));
+                }
+            },
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue_track(number: usize, index0: Option<usize>, index1: usize) -> CueTrack {
+        CueTrack {
+            number,
+            kind: TrackKind::Mode2,
+            file_index: 0,
+            pregap: 0,
+            index0,
+            index1: Some(index1),
+        }
+    }
+
+    #[test]
+    fn test_layout_first_track() {
+        let (tracks, sources) = layout_tracks(&[1000], &[cue_track(1, None, 0)]).unwrap();
+
+        assert_eq!(tracks[0].pregap_start, 0);
+        assert_eq!(tracks[0].start, 150);
+        assert_eq!(tracks[0].end, 1150);
+        assert_eq!(sources[0].data_start, 150);
+        assert_eq!(sources[0].file_sector, 0);
+    }
+
+    #[test]
+    fn test_layout_first_track_stored_pregap() {
+        // INDEX 00 00:00:00 / INDEX 01 00:02:00, then a second track with a stored pregap.
+        let (tracks, sources) = layout_tracks(&[1000], &[cue_track(1, Some(0), 150), cue_track(2, Some(350), 500)]).unwrap();
+
+        assert_eq!(tracks[0].pregap_start, 0);
+        assert_eq!(tracks[0].start, 150);
+        assert_eq!(tracks[0].end, 350);
+        assert_eq!(sources[0].data_start, 0);
+        assert_eq!(sources[0].file_sector, 0);
+
+        assert_eq!(tracks[1].pregap_start, 350);
+        assert_eq!(tracks[1].start, 500);
+        assert_eq!(tracks[1].end, 1000);
+        assert_eq!(sources[1].data_start, 350);
+        assert_eq!(sources[1].file_sector, 350);
+    }
+
+    #[test]
+    fn test_layout_first_track_pregap_clipped() {
+        // A stored pregap longer than the lead-in is clipped to it.
+        let (tracks, sources) = layout_tracks(&[1000], &[cue_track(1, Some(0), 225)]).unwrap();
+
+        assert_eq!(tracks[0].start, 150);
+        assert_eq!(tracks[0].end, 925);
+        assert_eq!(sources[0].data_start, 0);
+        assert_eq!(sources[0].file_sector, 75);
+    }
+
+    #[test]
+    fn test_iso_sector() {
+        let path = std::env::temp_dir().join(format!("psx-rs-test-{}.iso", std::process::id()));
+        let data: Vec<u8> = (0..(COOKED_SECTOR_SIZE * 2)).map(|i| (i / COOKED_SECTOR_SIZE) as u8 + 1).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let mut image = CueImage::open_iso(&path).unwrap();
+        let sector = image.read_raw_sector(151).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(image.tracks()[0].end, 152);
+        assert_eq!(sector[12..16], [0x00, 0x02, 0x01, 0x02]);
+        assert_eq!(sector[0x10..0x18], [0, 0, SUBMODE_DATA, 0, 0, 0, SUBMODE_DATA, 0]);
+        assert!(sector[0x18..0x818].iter().all(|&value| value == 2));
+        assert_eq!(sector[0x818..0x81C], edc_compute(&sector[0x10..0x818]).to_le_bytes());
+    }
+
+    #[test]
+    fn test_mode2_stream_sector() {
+        let path = std::env::temp_dir().join(format!("psx-rs-test-{}.str", std::process::id()));
+        let mut data = Vec::new();
+        for index in 0..2u8 {
+            data.extend([1, index, 0x64, 0x04, 1, index, 0x64, 0x04].iter());
+            data.extend(std::iter::repeat(index + 1).take(MODE2_SECTOR_SIZE - 8));
+        }
+        std::fs::write(&path, &data).unwrap();
+
+        let mut image = CueImage::open_stream(&path).unwrap();
+        let sector = image.read_raw_sector(151).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(image.tracks()[0].end, 152);
+        assert_eq!(sector[0..12], SYNC_PATTERN);
+        assert_eq!(sector[12..16], [0x00, 0x02, 0x01, 0x02]);
+        assert_eq!(sector[0x10..0x18], [1, 1, 0x64, 0x04, 1, 1, 0x64, 0x04]);
+        assert!(sector[0x18..].iter().all(|&value| value == 2));
+    }
+}
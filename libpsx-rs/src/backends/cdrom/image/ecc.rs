@@ -0,0 +1,134 @@
+//! CD-ROM sector error detection (EDC) and correction (P/Q parity) generation, for images that strip them.
+
+use lazy_static::lazy_static;
+
+const ECC_P_OFFSET: usize = 0x81C;
+const ECC_P_MAJOR_COUNT: usize = 86;
+const ECC_P_MINOR_COUNT: usize = 24;
+const ECC_Q_OFFSET: usize = 0x8C8;
+const ECC_Q_MAJOR_COUNT: usize = 52;
+const ECC_Q_MINOR_COUNT: usize = 43;
+const HEADER_OFFSET: usize = 12;
+const EDC_POLYNOMIAL: u32 = 0xD801_8001;
+
+lazy_static! {
+    static ref ECC_TABLES: ([u8; 256], [u8; 256]) = ecc_tables();
+    static ref EDC_TABLE: [u32; 256] = edc_table();
+}
+
+/// GF(2^8) multiply by 2, and its inverse for (x ^ 2x).
+fn ecc_tables() -> ([u8; 256], [u8; 256]) {
+    let mut forward = [0u8; 256];
+    let mut backward = [0u8; 256];
+
+    for i in 0..256 {
+        let value = ((i << 1)
+            ^ (if (i & 0x80) > 0 {
+                0x11D
+            } else {
+                0
+            })) as u8;
+        forward[i] = value;
+        backward[i ^ (value as usize)] = i as u8;
+    }
+
+    (forward, backward)
+}
+
+fn ecc_compute_block(sector: &mut [u8], source: &[u8], major_count: usize, minor_count: usize, major_mult: usize, minor_inc: usize, destination: usize) {
+    let (forward, backward) = &*ECC_TABLES;
+    let size = major_count * minor_count;
+
+    for major in 0..major_count {
+        let mut index = ((major >> 1) * major_mult) + (major & 1);
+        let mut ecc_a = 0u8;
+        let mut ecc_b = 0u8;
+
+        for _ in 0..minor_count {
+            let value = source[index];
+            index += minor_inc;
+            if index >= size {
+                index -= size;
+            }
+            ecc_a ^= value;
+            ecc_b ^= value;
+            ecc_a = forward[ecc_a as usize];
+        }
+
+        ecc_a = backward[(forward[ecc_a as usize] ^ ecc_b) as usize];
+        sector[destination + major] = ecc_a;
+        sector[destination + major + major_count] = ecc_a ^ ecc_b;
+    }
+}
+
+/// Generates the P and Q parity of a raw (2352 byte) mode 1 or mode 2 form 1 sector in place.
+/// Mode 2 parity is computed with the header (address and mode) treated as zero.
+pub(crate) fn ecc_generate(sector: &mut [u8]) {
+    let mut source = sector[HEADER_OFFSET..ECC_Q_OFFSET].to_vec();
+    if sector[15] == 2 {
+        source[0..4].copy_from_slice(&[0; 4]);
+    }
+
+    ecc_compute_block(sector, &source, ECC_P_MAJOR_COUNT, ECC_P_MINOR_COUNT, 2, ECC_P_MAJOR_COUNT, ECC_P_OFFSET);
+
+    // Q parity also covers the P parity.
+    source[(ECC_P_OFFSET - HEADER_OFFSET)..].copy_from_slice(&sector[ECC_P_OFFSET..ECC_Q_OFFSET]);
+    ecc_compute_block(sector, &source, ECC_Q_MAJOR_COUNT, ECC_Q_MINOR_COUNT, ECC_P_MAJOR_COUNT, ECC_P_MAJOR_COUNT + 2, ECC_Q_OFFSET);
+}
+
+fn edc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut value = i as u32;
+        for _ in 0..8 {
+            value = (value >> 1)
+                ^ (if (value & 1) > 0 {
+                    EDC_POLYNOMIAL
+                } else {
+                    0
+                });
+        }
+        *entry = value;
+    }
+    table
+}
+
+/// Computes the EDC (a reflected 32 bit CRC) over a block of sector data.
+pub(crate) fn edc_compute(data: &[u8]) -> u32 {
+    let table = &*EDC_TABLE;
+    data.iter().fold(0, |edc, &byte| (edc >> 8) ^ table[((edc ^ (byte as u32)) & 0xFF) as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mode 1 sector at 00:02:00 with user data bytes 0x00, 0x01, ..., 0xFF, 0x00, ...
+    fn mode1_sector() -> Vec<u8> {
+        let mut sector = vec![0; 2352];
+        sector[1..11].copy_from_slice(&[0xFF; 10]);
+        sector[12..16].copy_from_slice(&[0x00, 0x02, 0x00, 0x01]);
+        for (i, byte) in sector[16..0x810].iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        sector
+    }
+
+    #[test]
+    fn test_edc_mode1() {
+        let sector = mode1_sector();
+        assert_eq!(edc_compute(&sector[0..0x810]), 0xE639_9727);
+    }
+
+    #[test]
+    fn test_ecc_mode1() {
+        let mut sector = mode1_sector();
+        let edc = edc_compute(&sector[0..0x810]);
+        sector[0x810..0x814].copy_from_slice(&edc.to_le_bytes());
+        ecc_generate(&mut sector);
+
+        assert_eq!(&sector[ECC_P_OFFSET..(ECC_P_OFFSET + 8)], &[0x42, 0x13, 0xC7, 0x94, 0x21, 0x72, 0xD5, 0x86]);
+        assert_eq!(&sector[ECC_Q_OFFSET..(ECC_Q_OFFSET + 8)], &[0x46, 0xE9, 0x44, 0x86, 0xF1, 0x2A, 0xE2, 0x0B]);
+        assert_eq!(&sector[2344..2352], &[0xD9, 0xD2, 0x2A, 0x7D, 0xD0, 0x7C, 0x65, 0x23]);
+    }
+}
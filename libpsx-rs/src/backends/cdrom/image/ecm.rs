@@ -0,0 +1,321 @@
+//! ECM (error code modeler) files, which strip the sync, EDC and ECC fields from sectors of a raw image.
+//! Stripped fields are regenerated on read, so the decoded data is bit exact with the original image.
+
+use crate::backends::cdrom::image::{
+    ecc::{
+        ecc_generate,
+        edc_compute,
+    },
+    SECTOR_SIZE,
+    SYNC_PATTERN,
+};
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    path::Path,
+};
+
+const ECM_MAGIC: &[u8; 4] = b"ECM\0";
+const END_OF_RECORDS: u64 = 0xFFFF_FFFF;
+const MODE2_OUTPUT_SIZE: usize = 2336;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum RecordKind {
+    /// Bytes stored as is.
+    Raw,
+    /// Mode 1 sectors, stored as address + user data.
+    Mode1,
+    /// Mode 2 form 1 sectors (without sync and header), stored as subheader + user data.
+    Mode2Form1,
+    /// Mode 2 form 2 sectors (without sync and header), stored as subheader + user data.
+    Mode2Form2,
+}
+
+impl RecordKind {
+    /// Size of one unit (byte or sector) in the ECM file.
+    fn input_size(self) -> usize {
+        match self {
+            RecordKind::Raw => 1,
+            RecordKind::Mode1 => 3 + 0x800,
+            RecordKind::Mode2Form1 => 0x804,
+            RecordKind::Mode2Form2 => 0x918,
+        }
+    }
+
+    /// Size of one unit (byte or sector) once decoded.
+    fn output_size(self) -> usize {
+        match self {
+            RecordKind::Raw => 1,
+            RecordKind::Mode1 => SECTOR_SIZE,
+            RecordKind::Mode2Form1 | RecordKind::Mode2Form2 => MODE2_OUTPUT_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Record {
+    kind: RecordKind,
+    count: u64,
+    input_offset: u64,
+    output_offset: u64,
+}
+
+/// Random access reader over the decoded contents of an ECM file.
+pub(crate) struct EcmFile {
+    file: File,
+    records: Vec<Record>,
+    size: u64,
+}
+
+impl EcmFile {
+    pub(crate) fn open(path: &Path) -> Result<EcmFile, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+        let error = |e: std::io::Error| format!("Reading ECM file {} failed: {}", path.display(), e);
+
+        let mut reader = BufReader::new(file);
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic).map_err(error)?;
+        if &magic != ECM_MAGIC {
+            return Err(format!("{} is not an ECM file", path.display()));
+        }
+
+        let mut records = Vec::new();
+        let mut input_offset = ECM_MAGIC.len() as u64;
+        let mut output_offset = 0;
+
+        loop {
+            let (kind, count, header_size) = read_record_header(&mut reader).map_err(error)?;
+            input_offset += header_size;
+
+            let count = match count {
+                END_OF_RECORDS => break,
+                count => count + 1,
+            };
+
+            let input_size = count * kind.input_size() as u64;
+            records.push(Record {
+                kind,
+                count,
+                input_offset,
+                output_offset,
+            });
+
+            reader.seek_relative(input_size as i64).map_err(error)?;
+            input_offset += input_size;
+            output_offset += count * kind.output_size() as u64;
+        }
+
+        Ok(EcmFile {
+            file: reader.into_inner(),
+            records,
+            size: output_offset,
+        })
+    }
+
+    /// Size of the decoded data in bytes.
+    pub(crate) fn len(&self) -> u64 {
+        self.size
+    }
+
+    /// Fills the buffer with decoded data starting at offset.
+    pub(crate) fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<(), String> {
+        if (offset + buffer.len() as u64) > self.size {
+            return Err(format!("Read of {} bytes at offset {} is beyond the end of the ECM data", buffer.len(), offset));
+        }
+
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let mut position = offset;
+        let mut filled = 0;
+        let mut record_index = self.records.partition_point(|record| record.output_offset <= position) - 1;
+
+        while filled < buffer.len() {
+            let record = self.records[record_index].clone();
+            let record_end = record.output_offset + (record.count * record.kind.output_size() as u64);
+            if position >= record_end {
+                record_index += 1;
+                continue;
+            }
+
+            let unit_size = record.kind.output_size() as u64;
+            let unit = (position - record.output_offset) / unit_size;
+            let unit_offset = ((position - record.output_offset) % unit_size) as usize;
+
+            let length = if record.kind == RecordKind::Raw {
+                let length = (buffer.len() - filled).min((record_end - position) as usize);
+                self.read_input(record.input_offset + unit, &mut buffer[filled..(filled + length)])?;
+                length
+            } else {
+                let mut input = vec![0; record.kind.input_size()];
+                self.read_input(record.input_offset + (unit * input.len() as u64), &mut input)?;
+                let sector = decode_sector(record.kind, &input);
+                let length = (buffer.len() - filled).min(sector.len() - unit_offset);
+                buffer[filled..(filled + length)].copy_from_slice(&sector[unit_offset..(unit_offset + length)]);
+                length
+            };
+
+            filled += length;
+            position += length as u64;
+        }
+
+        Ok(())
+    }
+
+    fn read_input(&mut self, offset: u64, buffer: &mut [u8]) -> Result<(), String> {
+        self.file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Seeking in ECM file failed: {}", e))?;
+        self.file.read_exact(buffer).map_err(|e| format!("Reading ECM file failed: {}", e))
+    }
+}
+
+/// Reads a record type and (biased) count; the count is stored 5 bits first, then in 7 bit continuation bytes.
+fn read_record_header<R: Read>(reader: &mut R) -> Result<(RecordKind, u64, u64), std::io::Error> {
+    let mut read_byte = || -> Result<u8, std::io::Error> {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    };
+
+    let mut byte = read_byte()?;
+    let mut header_size = 1;
+
+    let kind = match byte & 3 {
+        0 => RecordKind::Raw,
+        1 => RecordKind::Mode1,
+        2 => RecordKind::Mode2Form1,
+        _ => RecordKind::Mode2Form2,
+    };
+
+    let mut count = ((byte >> 2) & 0x1F) as u64;
+    let mut shift = 5;
+
+    while (byte & 0x80) > 0 {
+        if shift >= 32 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "ECM record count too large"));
+        }
+
+        byte = read_byte()?;
+        header_size += 1;
+        count |= ((byte & 0x7F) as u64) << shift;
+        shift += 7;
+    }
+
+    Ok((kind, count, header_size))
+}
+
+/// Rebuilds a sector from its stored fields, returning the bytes the record covers.
+fn decode_sector(kind: RecordKind, input: &[u8]) -> Vec<u8> {
+    let mut sector = vec![0; SECTOR_SIZE];
+    sector[0..12].copy_from_slice(&SYNC_PATTERN);
+
+    match kind {
+        RecordKind::Raw => unreachable!(),
+        RecordKind::Mode1 => {
+            sector[12..15].copy_from_slice(&input[0..3]);
+            sector[15] = 1;
+            sector[0x10..0x810].copy_from_slice(&input[3..]);
+
+            let edc = edc_compute(&sector[0..0x810]);
+            sector[0x810..0x814].copy_from_slice(&edc.to_le_bytes());
+            ecc_generate(&mut sector);
+            sector
+        },
+        RecordKind::Mode2Form1 => {
+            // The subheader is stored once, and duplicated on decode.
+            sector[15] = 2;
+            sector[0x14..0x818].copy_from_slice(input);
+            sector.copy_within(0x14..0x18, 0x10);
+
+            let edc = edc_compute(&sector[0x10..0x818]);
+            sector[0x818..0x81C].copy_from_slice(&edc.to_le_bytes());
+            ecc_generate(&mut sector);
+            sector[0x10..].to_vec()
+        },
+        RecordKind::Mode2Form2 => {
+            sector[15] = 2;
+            sector[0x14..0x92C].copy_from_slice(input);
+            sector.copy_within(0x14..0x18, 0x10);
+
+            let edc = edc_compute(&sector[0x10..0x92C]);
+            sector[0x92C..0x930].copy_from_slice(&edc.to_le_bytes());
+            sector[0x10..].to_vec()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raw sector at 00:02:frame with a complete EDC / ECC; the data bytes count up from the frame number.
+    fn raw_sector(frame: u8, mode: u8, submode: u8) -> Vec<u8> {
+        let mut sector = vec![0; SECTOR_SIZE];
+        sector[0..12].copy_from_slice(&SYNC_PATTERN);
+        sector[12..16].copy_from_slice(&[0x00, 0x02, frame, mode]);
+
+        let (data_start, edc_start, edc_end) = match (mode, submode & 0x20) {
+            (1, _) => (0x10, 0x00, 0x810),
+            (_, 0) => (0x18, 0x10, 0x818),
+            _ => (0x18, 0x10, 0x92C),
+        };
+        if mode == 2 {
+            sector[0x10..0x18].copy_from_slice(&[0, 0, submode, 0, 0, 0, submode, 0]);
+        }
+        for (i, byte) in sector[data_start..edc_end].iter_mut().enumerate() {
+            *byte = frame.wrapping_add(i as u8);
+        }
+
+        let edc = edc_compute(&sector[edc_start..edc_end]);
+        sector[edc_end..(edc_end + 4)].copy_from_slice(&edc.to_le_bytes());
+        if edc_end != 0x92C {
+            ecc_generate(&mut sector);
+        }
+        sector
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mode1 = raw_sector(0, 1, 0);
+        let form1 = raw_sector(1, 2, 0x08);
+        let form2 = raw_sector(2, 2, 0x20);
+
+        // Each record holds one sector (count 0); mode 2 sectors are preceded by a raw record of the sync and header.
+        let mut ecm = ECM_MAGIC.to_vec();
+        ecm.push(0x01);
+        ecm.extend_from_slice(&mode1[12..15]);
+        ecm.extend_from_slice(&mode1[0x10..0x810]);
+        ecm.push(0x3C);
+        ecm.extend_from_slice(&form1[0..0x10]);
+        ecm.push(0x02);
+        ecm.extend_from_slice(&form1[0x14..0x818]);
+        ecm.push(0x3C);
+        ecm.extend_from_slice(&form2[0..0x10]);
+        ecm.push(0x03);
+        ecm.extend_from_slice(&form2[0x14..0x92C]);
+        ecm.extend_from_slice(&[0xFC, 0xFF, 0xFF, 0xFF, 0x3F]);
+
+        let path = std::env::temp_dir().join(format!("psx-rs-test-{}.ecm", std::process::id()));
+        std::fs::write(&path, &ecm).unwrap();
+        let file = EcmFile::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        let mut file = file.unwrap();
+
+        let expected = [mode1, form1, form2].concat();
+        assert_eq!(file.len(), expected.len() as u64);
+
+        let mut buffer = vec![0; expected.len()];
+        file.read_at(0, &mut buffer).unwrap();
+        assert_eq!(buffer, expected);
+
+        // Unaligned read spanning the end of the mode 1 sector and the raw record.
+        let mut buffer = vec![0; 100];
+        file.read_at(2300, &mut buffer).unwrap();
+        assert_eq!(&buffer[..], &expected[2300..2400]);
+    }
+}
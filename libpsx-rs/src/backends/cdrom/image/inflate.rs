@@ -0,0 +1,269 @@
+//! Raw deflate (RFC 1951) decoder, as used by the CHD zlib codecs and PBP blocks (no zlib header).
+
+const LENGTH_BASE: [usize; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [usize; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [usize; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA: [usize; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+const MAX_CODE_LENGTH: usize = 15;
+
+/// LSB first bit reader.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read_bit(&mut self) -> Result<usize, String> {
+        let byte = *self.data.get(self.position / 8).ok_or_else(|| "Unexpected end of deflate stream".to_owned())?;
+        let bit = (byte >> (self.position % 8)) & 1;
+        self.position += 1;
+        Ok(bit as usize)
+    }
+
+    fn read_bits(&mut self, count: usize) -> Result<usize, String> {
+        let mut value = 0;
+        for i in 0..count {
+            value |= self.read_bit()? << i;
+        }
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        self.position = (self.position + 7) & !7;
+    }
+}
+
+/// Canonical Huffman code, decoded bit by bit (as in zlib's puff).
+struct Huffman {
+    counts: [usize; MAX_CODE_LENGTH + 1],
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[usize]) -> Result<Huffman, String> {
+        let mut counts = [0; MAX_CODE_LENGTH + 1];
+        for &length in lengths.iter() {
+            counts[length] += 1;
+        }
+
+        let mut offsets = [0; MAX_CODE_LENGTH + 2];
+        for length in 1..=MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length]] = symbol;
+                offsets[length] += 1;
+            }
+        }
+
+        Ok(Huffman {
+            counts,
+            symbols,
+        })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<usize, String> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+
+        for length in 1..=MAX_CODE_LENGTH {
+            code |= reader.read_bit()?;
+            let count = self.counts[length];
+            if code < first + count {
+                return Ok(self.symbols[index + (code - first)]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err("Invalid deflate Huffman code".into())
+    }
+}
+
+/// Decompresses a raw deflate stream, producing exactly output_size bytes.
+pub(crate) fn inflate(data: &[u8], output_size: usize) -> Result<Vec<u8>, String> {
+    let output = inflate_limited(data, output_size)?;
+
+    if output.len() != output_size {
+        return Err(format!("Deflate stream size mismatch: {} bytes (expected {})", output.len(), output_size));
+    }
+
+    Ok(output)
+}
+
+/// Decompresses a raw deflate stream of at most max_size bytes.
+pub(crate) fn inflate_limited(data: &[u8], max_size: usize) -> Result<Vec<u8>, String> {
+    let mut reader = BitReader {
+        data,
+        position: 0,
+    };
+    let mut output = Vec::with_capacity(max_size);
+
+    loop {
+        let last = reader.read_bit()? > 0;
+
+        match reader.read_bits(2)? {
+            0 => inflate_stored(&mut reader, &mut output)?,
+            1 => {
+                let (literal, distance) = fixed_codes()?;
+                inflate_codes(&mut reader, &mut output, &literal, &distance)?;
+            },
+            2 => {
+                let (literal, distance) = dynamic_codes(&mut reader)?;
+                inflate_codes(&mut reader, &mut output, &literal, &distance)?;
+            },
+            _ => return Err("Invalid deflate block type".into()),
+        }
+
+        if last || (output.len() >= max_size) {
+            break;
+        }
+    }
+
+    if output.len() > max_size {
+        return Err(format!("Deflate stream too large: {} bytes (at most {})", output.len(), max_size));
+    }
+
+    Ok(output)
+}
+
+fn inflate_stored(reader: &mut BitReader, output: &mut Vec<u8>) -> Result<(), String> {
+    reader.align_to_byte();
+    let start = reader.position / 8;
+    let header = data_slice(reader.data, start, 4)?;
+    let length = u16::from_le_bytes([header[0], header[1]]);
+    let length_complement = u16::from_le_bytes([header[2], header[3]]);
+    if length != !length_complement {
+        return Err("Invalid deflate stored block length".into());
+    }
+
+    output.extend_from_slice(data_slice(reader.data, start + 4, length as usize)?);
+    reader.position = (start + 4 + (length as usize)) * 8;
+    Ok(())
+}
+
+fn data_slice(data: &[u8], start: usize, length: usize) -> Result<&[u8], String> {
+    data.get(start..(start + length)).ok_or_else(|| "Unexpected end of deflate stream".to_owned())
+}
+
+fn fixed_codes() -> Result<(Huffman, Huffman), String> {
+    let mut lengths = [0; 288];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = reader.read_bits(5)? + 257;
+    let distance_count = reader.read_bits(5)? + 1;
+    let code_length_count = reader.read_bits(4)? + 4;
+
+    let mut code_lengths = [0; 19];
+    for &index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_lengths[index] = reader.read_bits(3)?;
+    }
+    let code_length_huffman = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < (literal_count + distance_count) {
+        let symbol = code_length_huffman.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol, 1),
+            16 => (*lengths.last().ok_or_else(|| "Deflate code length repeat with no previous length".to_owned())?, 3 + reader.read_bits(2)?),
+            17 => (0, 3 + reader.read_bits(3)?),
+            _ => (0, 11 + reader.read_bits(7)?),
+        };
+        lengths.extend(std::iter::repeat(value).take(repeat));
+    }
+
+    if lengths.len() != (literal_count + distance_count) {
+        return Err("Deflate code lengths overflow".into());
+    }
+
+    Ok((Huffman::new(&lengths[0..literal_count])?, Huffman::new(&lengths[literal_count..])?))
+}
+
+fn inflate_codes(reader: &mut BitReader, output: &mut Vec<u8>, literal: &Huffman, distance: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = literal.decode(reader)?;
+
+        if symbol < 256 {
+            output.push(symbol as u8);
+        } else if symbol == 256 {
+            return Ok(());
+        } else {
+            let index = symbol - 257;
+            if index >= LENGTH_BASE.len() {
+                return Err("Invalid deflate length symbol".into());
+            }
+            let length = LENGTH_BASE[index] + reader.read_bits(LENGTH_EXTRA[index])?;
+
+            let index = distance.decode(reader)?;
+            if index >= DISTANCE_BASE.len() {
+                return Err("Invalid deflate distance symbol".into());
+            }
+            let distance = DISTANCE_BASE[index] + reader.read_bits(DISTANCE_EXTRA[index])?;
+            if distance > output.len() {
+                return Err("Deflate distance too far back".into());
+            }
+
+            let start = output.len() - distance;
+            for i in 0..length {
+                let value = output[start + i];
+                output.push(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_block() {
+        let data = [0x01, 0x05, 0x00, 0xFA, 0xFF, b'h', b'e', b'l', b'l', b'o'];
+        assert_eq!(inflate(&data, 5).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_fixed_block() {
+        let data = [0x4B, 0x4C, 0x4A, 0x4E, 0x44, 0x42, 0x8A, 0x00];
+        assert_eq!(inflate(&data, 16).unwrap(), b"abcabcabcabcabc!");
+    }
+
+    #[test]
+    fn test_dynamic_block() {
+        let data =
+            [0xED, 0xC3, 0x31, 0x0D, 0x00, 0x00, 0x08, 0x03, 0x30, 0xAD, 0x03, 0xFC, 0x6B, 0xE0, 0x42, 0x03, 0x4F, 0x9B, 0x34, 0x01, 0x80, 0x02, 0x9A, 0x6F, 0xA9, 0x9E, 0xBB];
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&[b'a'; 1550]);
+        expected.extend_from_slice(&[b'b'; 775]);
+        expected.extend_from_slice(&[b'c'; 387]);
+        expected.extend_from_slice(b"abcdabcdabcd");
+
+        // Block type 2 (dynamic Huffman codes).
+        assert_eq!(data[0] & 0x06, 0x04);
+        assert_eq!(inflate(&data, expected.len()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_size_mismatch() {
+        let data = [0x01, 0x05, 0x00, 0xFA, 0xFF, b'h', b'e', b'l', b'l', b'o'];
+        assert!(inflate(&data, 6).is_err());
+        assert!(inflate_limited(&data, 4).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! PSP EBOOT (PBP) images, as created by popstation; the disc images are stored in DATA.PSAR as deflated blocks of 16
+//! sectors. Multi-disc EBOOTs hold up to 5 discs, selected with select_disc.
+
+use crate::{
+    backends::cdrom::image::{
+        inflate::inflate_limited,
+        *,
+    },
+    utilities::bcd_to_binary,
+};
+use std::{
+    fs::File,
+    io::{
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    path::Path,
+};
+
+const PBP_MAGIC: &[u8; 4] = b"\0PBP";
+/// Offset of the DATA.PSAR offset within the PBP header (the last of 8 section offsets).
+const PBP_PSAR_OFFSET: u64 = 0x24;
+const PSAR_SINGLE_DISC_MAGIC: &[u8] = b"PSISOIMG0000";
+const PSAR_MULTI_DISC_MAGIC: &[u8] = b"PSTITLEIMG000000";
+const PSAR_DISC_TABLE_OFFSET: u64 = 0x200;
+const MAX_DISCS: usize = 5;
+
+const ISO_TOC_OFFSET: u64 = 0x800;
+const ISO_TOC_ENTRY_SIZE: usize = 10;
+const ISO_TOC_MAX_ENTRIES: usize = 102;
+const ISO_BLOCK_TABLE_OFFSET: u64 = 0x4000;
+/// End of the block table; block offsets are relative to this.
+const ISO_BLOCK_TABLE_END: u64 = 0x10_0000;
+const ISO_BLOCK_ENTRY_SIZE: usize = 32;
+const SECTORS_PER_BLOCK: usize = 16;
+const BLOCK_SIZE: usize = SECTORS_PER_BLOCK * SECTOR_SIZE;
+/// Pregap assumed for tracks after the first, as the TOC only records INDEX 01.
+const TRACK_PREGAP_SECTORS: usize = 150;
+
+struct Block {
+    /// Absolute file offset.
+    offset: u64,
+    size: usize,
+}
+
+struct PbpDisc {
+    tracks: Vec<Track>,
+    blocks: Vec<Block>,
+}
+
+pub(crate) struct PbpImage {
+    file: File,
+    discs: Vec<PbpDisc>,
+    disc_index: usize,
+    /// Most recently decompressed block (disc index, block index, data).
+    cached_block: Option<(usize, usize, Vec<u8>)>,
+}
+
+impl PbpImage {
+    pub(crate) fn open(path: &Path) -> Result<PbpImage, String> {
+        let mut file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+
+        let header = read_at(&mut file, 0, 0x28)?;
+        if &header[0..4] != PBP_MAGIC {
+            return Err(format!("{} is not a PBP file", path.display()));
+        }
+
+        let psar_offset = read_u32(&header, PBP_PSAR_OFFSET as usize) as u64;
+        let psar_header = read_at(&mut file, psar_offset, 0x10)?;
+
+        let iso_offsets = if psar_header.starts_with(PSAR_MULTI_DISC_MAGIC) {
+            let table = read_at(&mut file, psar_offset + PSAR_DISC_TABLE_OFFSET, MAX_DISCS * 4)?;
+            (0..MAX_DISCS).map(|index| read_u32(&table, index * 4) as u64).take_while(|&offset| offset != 0).map(|offset| psar_offset + offset).collect()
+        } else if psar_header.starts_with(PSAR_SINGLE_DISC_MAGIC) {
+            vec![psar_offset]
+        } else {
+            return Err(format!("{} does not contain a PSX disc image (unsupported or encrypted DATA.PSAR)", path.display()));
+        };
+
+        let mut discs = Vec::new();
+        for (index, &iso_offset) in iso_offsets.iter().enumerate() {
+            let disc = read_disc(&mut file, iso_offset).map_err(|e| format!("{}: disc {}: {}", path.display(), index + 1, e))?;
+            discs.push(disc);
+        }
+
+        if discs.is_empty() {
+            return Err(format!("{} does not contain any discs", path.display()));
+        }
+
+        log::info!("PBP image contains {} disc(s)", discs.len());
+
+        Ok(PbpImage {
+            file,
+            discs,
+            disc_index: 0,
+            cached_block: None,
+        })
+    }
+
+    fn read_block(&mut self, block_index: usize) -> Result<&[u8], String> {
+        let cached = match self.cached_block {
+            Some((disc_index, cached_index, _)) => (disc_index == self.disc_index) && (cached_index == block_index),
+            None => false,
+        };
+
+        if !cached {
+            let block = &self.discs[self.disc_index].blocks[block_index];
+            let data = read_at(&mut self.file, block.offset, block.size)?;
+
+            let mut data = if block.size == BLOCK_SIZE {
+                data
+            } else {
+                inflate_limited(&data, BLOCK_SIZE).map_err(|e| format!("Decompressing block {} failed: {}", block_index, e))?
+            };
+            data.resize(BLOCK_SIZE, 0);
+
+            self.cached_block = Some((self.disc_index, block_index, data));
+        }
+
+        Ok(&self.cached_block.as_ref().unwrap().2)
+    }
+}
+
+impl DiscImage for PbpImage {
+    fn tracks(&self) -> &[Track] {
+        &self.discs[self.disc_index].tracks
+    }
+
+    fn read_raw_sector(&mut self, address: usize) -> Result<Vec<u8>, String> {
+        let track = find_track(self.tracks(), address).ok_or_else(|| format!("Sector {} is outside of the disc", address))?;
+        let kind = track.kind;
+
+        if address < LEAD_IN_SECTORS {
+            return Ok(generate_empty_sector(address, kind));
+        }
+
+        let sector_index = address - LEAD_IN_SECTORS;
+        let block_index = sector_index / SECTORS_PER_BLOCK;
+        if block_index >= self.discs[self.disc_index].blocks.len() {
+            return Ok(generate_empty_sector(address, kind));
+        }
+
+        let offset = (sector_index % SECTORS_PER_BLOCK) * SECTOR_SIZE;
+        Ok(self.read_block(block_index)?[offset..(offset + SECTOR_SIZE)].to_vec())
+    }
+
+    fn disc_count(&self) -> usize {
+        self.discs.len()
+    }
+
+    fn select_disc(&mut self, index: usize) -> Result<(), String> {
+        if index >= self.discs.len() {
+            return Err(format!("Disc {} does not exist (the image contains {} disc(s))", index + 1, self.discs.len()));
+        }
+
+        self.disc_index = index;
+        Ok(())
+    }
+}
+
+fn read_disc(file: &mut File, iso_offset: u64) -> Result<PbpDisc, String> {
+    let iso_header = read_at(file, iso_offset, PSAR_SINGLE_DISC_MAGIC.len())?;
+    if iso_header != PSAR_SINGLE_DISC_MAGIC {
+        return Err("invalid disc image header".into());
+    }
+
+    let toc = read_at(file, iso_offset + ISO_TOC_OFFSET, ISO_TOC_ENTRY_SIZE * ISO_TOC_MAX_ENTRIES)?;
+    let tracks = parse_toc(&toc)?;
+
+    let table = read_at(file, iso_offset + ISO_BLOCK_TABLE_OFFSET, (ISO_BLOCK_TABLE_END - ISO_BLOCK_TABLE_OFFSET) as usize)?;
+    let blocks = table
+        .chunks_exact(ISO_BLOCK_ENTRY_SIZE)
+        .map(|entry| {
+            Block {
+                offset: iso_offset + ISO_BLOCK_TABLE_END + (read_u32(entry, 0) as u64),
+                size: u16::from_le_bytes([entry[4], entry[5]]) as usize,
+            }
+        })
+        .take_while(|block| block.size != 0)
+        .collect::<Vec<_>>();
+
+    if blocks.iter().any(|block| block.size > BLOCK_SIZE) {
+        return Err("invalid block table (the image may be encrypted)".into());
+    }
+
+    Ok(PbpDisc {
+        tracks,
+        blocks,
+    })
+}
+
+/// Parses the disc TOC: point A0 / A1 hold the first / last track numbers, A2 the lead-out, followed by one entry per
+/// track.
+fn parse_toc(toc: &[u8]) -> Result<Vec<Track>, String> {
+    let position =
+        |entry: &[u8]| (bcd_to_binary(entry[7]) as usize * SECTORS_PER_MINUTE) + (bcd_to_binary(entry[8]) as usize * SECTORS_PER_SECOND) + bcd_to_binary(entry[9]) as usize;
+
+    let entries = toc.chunks_exact(ISO_TOC_ENTRY_SIZE).collect::<Vec<_>>();
+    let (first, last, lead_out) = match entries[0..3] {
+        [first, last, lead_out] if (first[2] == 0xA0) && (last[2] == 0xA1) && (lead_out[2] == 0xA2) => {
+            (bcd_to_binary(first[7]) as usize, bcd_to_binary(last[7]) as usize, position(lead_out))
+        },
+        _ => return Err("invalid TOC (the image may be encrypted)".into()),
+    };
+
+    if (first == 0) || (last < first) || ((last - first + 1) > (entries.len() - 3)) {
+        return Err(format!("invalid TOC track range {} - {}", first, last));
+    }
+
+    let mut tracks: Vec<Track> = Vec::new();
+    for entry in entries[3..].iter().take(last - first + 1) {
+        let start = position(entry);
+        let kind = if (entry[0] & 0x40) > 0 {
+            TrackKind::Mode2
+        } else {
+            TrackKind::Audio
+        };
+
+        let pregap_start = match tracks.last_mut() {
+            Some(previous) => {
+                let pregap_start = if start > (previous.start + TRACK_PREGAP_SECTORS) {
+                    start - TRACK_PREGAP_SECTORS
+                } else {
+                    start
+                };
+                previous.end = pregap_start;
+                pregap_start
+            },
+            None => 0,
+        };
+
+        tracks.push(Track {
+            number: bcd_to_binary(entry[2]) as usize,
+            kind,
+            pregap_start,
+            start,
+            end: lead_out,
+        });
+    }
+
+    if tracks.iter().any(|track| track.start > lead_out) {
+        return Err("TOC tracks are beyond the lead-out".into());
+    }
+
+    Ok(tracks)
+}
+
+fn read_at(file: &mut File, offset: u64, length: usize) -> Result<Vec<u8>, String> {
+    let mut buffer = vec![0; length];
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Seeking to offset {} failed: {}", offset, e))?;
+    file.read_exact(&mut buffer).map_err(|e| format!("Reading {} bytes at offset {} failed: {}", length, offset, e))?;
+    Ok(buffer)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Disc image with a single data track of one block; the first sector is filled with the fill byte and the rest
+    /// are zero. The block is either stored uncompressed, or deflated (as a stored deflate block of the first sector
+    /// only, the remainder of the block being zero filled).
+    fn disc_image(fill: u8, deflated: bool) -> Vec<u8> {
+        let mut image = vec![0; ISO_BLOCK_TABLE_END as usize];
+        image[0..PSAR_SINGLE_DISC_MAGIC.len()].copy_from_slice(PSAR_SINGLE_DISC_MAGIC);
+
+        // A0 (first track 1), A1 (last track 1), A2 (lead-out at 00:02:16), track 1 at 00:02:00.
+        let toc = ISO_TOC_OFFSET as usize;
+        image[toc..(toc + 10)].copy_from_slice(&[0x41, 0, 0xA0, 0, 0, 0, 0, 0x01, 0x20, 0]);
+        image[(toc + 10)..(toc + 20)].copy_from_slice(&[0x41, 0, 0xA1, 0, 0, 0, 0, 0x01, 0, 0]);
+        image[(toc + 20)..(toc + 30)].copy_from_slice(&[0x41, 0, 0xA2, 0, 0, 0, 0, 0x00, 0x02, 0x16]);
+        image[(toc + 30)..(toc + 40)].copy_from_slice(&[0x41, 0, 0x01, 0, 0, 0, 0, 0x00, 0x02, 0x00]);
+
+        let block = if deflated {
+            let mut block = vec![0x01];
+            block.extend_from_slice(&(SECTOR_SIZE as u16).to_le_bytes());
+            block.extend_from_slice(&(!(SECTOR_SIZE as u16)).to_le_bytes());
+            block.extend_from_slice(&[fill; SECTOR_SIZE]);
+            block
+        } else {
+            let mut block = vec![0; BLOCK_SIZE];
+            block[0..SECTOR_SIZE].copy_from_slice(&[fill; SECTOR_SIZE]);
+            block
+        };
+
+        let table = ISO_BLOCK_TABLE_OFFSET as usize;
+        image[(table + 4)..(table + 6)].copy_from_slice(&(block.len() as u16).to_le_bytes());
+        image.extend_from_slice(&block);
+        image
+    }
+
+    #[test]
+    fn test_multi_disc() {
+        let psar_offset = 0x28;
+        let disc_offsets = [0x400, 0x400 + 0x20_0000];
+
+        let mut pbp = vec![0; psar_offset];
+        pbp[0..4].copy_from_slice(PBP_MAGIC);
+        pbp[(PBP_PSAR_OFFSET as usize)..(PBP_PSAR_OFFSET as usize + 4)].copy_from_slice(&(psar_offset as u32).to_le_bytes());
+
+        let mut psar = vec![0; disc_offsets[0]];
+        psar[0..PSAR_MULTI_DISC_MAGIC.len()].copy_from_slice(PSAR_MULTI_DISC_MAGIC);
+        for (index, &offset) in disc_offsets.iter().enumerate() {
+            let entry = PSAR_DISC_TABLE_OFFSET as usize + (index * 4);
+            psar[entry..(entry + 4)].copy_from_slice(&(offset as u32).to_le_bytes());
+        }
+        for (index, &offset) in disc_offsets.iter().enumerate() {
+            psar.resize(offset, 0);
+            psar.extend_from_slice(&disc_image(0x11 * (index as u8 + 1), index == 1));
+        }
+        pbp.extend_from_slice(&psar);
+
+        let path = std::env::temp_dir().join(format!("psx-rs-test-{}.pbp", std::process::id()));
+        std::fs::write(&path, &pbp).unwrap();
+        let image = PbpImage::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        let mut image = image.unwrap();
+
+        assert_eq!(image.disc_count(), 2);
+        assert_eq!(image.tracks().len(), 1);
+        assert_eq!(image.tracks()[0].start, LEAD_IN_SECTORS);
+        assert_eq!(image.tracks()[0].end, LEAD_IN_SECTORS + 16);
+
+        assert_eq!(image.read_raw_sector(LEAD_IN_SECTORS).unwrap(), vec![0x11; SECTOR_SIZE]);
+        assert_eq!(image.read_raw_sector(LEAD_IN_SECTORS + 15).unwrap(), vec![0; SECTOR_SIZE]);
+
+        image.select_disc(1).unwrap();
+        assert_eq!(image.read_raw_sector(LEAD_IN_SECTORS).unwrap(), vec![0x22; SECTOR_SIZE]);
+        assert_eq!(image.read_raw_sector(LEAD_IN_SECTORS + 15).unwrap(), vec![0; SECTOR_SIZE]);
+        assert!(image.select_disc(2).is_err());
+    }
+}
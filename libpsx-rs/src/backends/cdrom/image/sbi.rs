@@ -0,0 +1,192 @@
+//! Subchannel Q patches from .sbi / .lsd sidecar files, listing the sectors with modified Q data (such as LibCrypt
+//! protected discs). Disc images rarely store subchannel data, so these are the usual way of providing it.
+
+use crate::{
+    backends::cdrom::image::{
+        SECTORS_PER_MINUTE,
+        SECTORS_PER_SECOND,
+        SUBCHANNEL_Q_SIZE,
+    },
+    utilities::bcd_to_binary,
+};
+use std::{
+    collections::HashMap,
+    path::Path,
+};
+
+const SBI_MAGIC: &[u8; 4] = b"SBI\0";
+const SBI_TYPE_DATA: u8 = 1;
+const SBI_TYPE_RELATIVE: u8 = 2;
+const SBI_TYPE_ABSOLUTE: u8 = 3;
+const LSD_ENTRY_SIZE: usize = 3 + SUBCHANNEL_Q_SIZE;
+
+#[derive(Debug, Copy, Clone)]
+enum QPatch {
+    /// Whole Q data including the CRC (.lsd).
+    Full([u8; SUBCHANNEL_Q_SIZE]),
+    /// Q data without the CRC, which is left as is (.sbi).
+    Data([u8; 10]),
+    /// Relative MSF only.
+    Relative([u8; 3]),
+    /// Absolute MSF only.
+    Absolute([u8; 3]),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SubchannelPatches {
+    patches: HashMap<usize, QPatch>,
+}
+
+impl SubchannelPatches {
+    pub(crate) fn new() -> SubchannelPatches {
+        SubchannelPatches {
+            patches: HashMap::new(),
+        }
+    }
+
+    /// Loads the .sbi or .lsd file with the same name as the disc image, if there is one.
+    pub(crate) fn load_sidecar(image_path: &Path) -> Result<SubchannelPatches, String> {
+        for &extension in ["sbi", "lsd"].iter() {
+            let path = image_path.with_extension(extension);
+            if !path.is_file() {
+                continue;
+            }
+
+            let data = std::fs::read(&path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+            let patches = match extension {
+                "sbi" => parse_sbi(&data),
+                _ => parse_lsd(&data),
+            }
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+            log::info!("Loaded {} subchannel Q patches from {}", patches.len(), path.display());
+            return Ok(SubchannelPatches {
+                patches,
+            });
+        }
+
+        Ok(SubchannelPatches::new())
+    }
+
+    /// Applies the patch for a sector (if any) to its Q data.
+    /// Patches without a CRC keep the original one, so the patched data fails the CRC check as on the original disc.
+    pub(crate) fn apply(&self, address: usize, q: [u8; SUBCHANNEL_Q_SIZE]) -> [u8; SUBCHANNEL_Q_SIZE] {
+        let mut q = q;
+
+        match self.patches.get(&address) {
+            Some(QPatch::Full(data)) => q = *data,
+            Some(QPatch::Data(data)) => q[0..10].copy_from_slice(data),
+            Some(QPatch::Relative(msf)) => q[3..6].copy_from_slice(msf),
+            Some(QPatch::Absolute(msf)) => q[7..10].copy_from_slice(msf),
+            None => {},
+        }
+
+        q
+    }
+}
+
+fn msf_to_address(msf: &[u8]) -> usize {
+    (bcd_to_binary(msf[0]) as usize * SECTORS_PER_MINUTE) + (bcd_to_binary(msf[1]) as usize * SECTORS_PER_SECOND) + bcd_to_binary(msf[2]) as usize
+}
+
+/// SBI: magic, then entries of an MSF address, a type byte and the patch data (10 bytes of Q data, or 3 bytes of MSF).
+fn parse_sbi(data: &[u8]) -> Result<HashMap<usize, QPatch>, String> {
+    if !data.starts_with(SBI_MAGIC) {
+        return Err("not an SBI file".into());
+    }
+
+    let mut patches = HashMap::new();
+    let mut offset = SBI_MAGIC.len();
+
+    while offset < data.len() {
+        let header = data.get(offset..(offset + 4)).ok_or_else(|| "truncated entry".to_owned())?;
+        let address = msf_to_address(&header[0..3]);
+        let patch_type = header[3];
+        offset += 4;
+
+        let length = match patch_type {
+            SBI_TYPE_DATA => 10,
+            SBI_TYPE_RELATIVE | SBI_TYPE_ABSOLUTE => 3,
+            _ => return Err(format!("unknown entry type {}", patch_type)),
+        };
+
+        let patch_data = data.get(offset..(offset + length)).ok_or_else(|| "truncated entry".to_owned())?;
+        offset += length;
+
+        let patch = match patch_type {
+            SBI_TYPE_DATA => {
+                let mut q = [0; 10];
+                q.copy_from_slice(patch_data);
+                QPatch::Data(q)
+            },
+            SBI_TYPE_RELATIVE => QPatch::Relative([patch_data[0], patch_data[1], patch_data[2]]),
+            _ => QPatch::Absolute([patch_data[0], patch_data[1], patch_data[2]]),
+        };
+
+        patches.insert(address, patch);
+    }
+
+    Ok(patches)
+}
+
+/// LSD: entries of an MSF address followed by the whole Q data (including the CRC).
+fn parse_lsd(data: &[u8]) -> Result<HashMap<usize, QPatch>, String> {
+    if (data.len() % LSD_ENTRY_SIZE) != 0 {
+        return Err(format!("size is not a multiple of {} bytes", LSD_ENTRY_SIZE));
+    }
+
+    Ok(data
+        .chunks_exact(LSD_ENTRY_SIZE)
+        .map(|entry| {
+            let mut q = [0; SUBCHANNEL_Q_SIZE];
+            q.copy_from_slice(&entry[3..]);
+            (msf_to_address(&entry[0..3]), QPatch::Full(q))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sbi() {
+        let mut data = SBI_MAGIC.to_vec();
+        // 03:08:05, Q data (type 1).
+        data.extend_from_slice(&[0x03, 0x08, 0x05, 0x01, 0x41, 0x01, 0x01, 0x03, 0x06, 0x05, 0x00, 0x03, 0x08, 0x05]);
+        // 03:08:10, absolute MSF (type 3).
+        data.extend_from_slice(&[0x03, 0x08, 0x10, 0x03, 0x03, 0x0A, 0x10]);
+
+        let patches = SubchannelPatches {
+            patches: parse_sbi(&data).unwrap(),
+        };
+        assert_eq!(patches.patches.len(), 2);
+
+        let q = [0xFF; SUBCHANNEL_Q_SIZE];
+        assert_eq!(patches.apply(14105, q), [0x41, 0x01, 0x01, 0x03, 0x06, 0x05, 0x00, 0x03, 0x08, 0x05, 0xFF, 0xFF]);
+        assert_eq!(patches.apply(14110, q), [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x03, 0x0A, 0x10, 0xFF, 0xFF]);
+        assert_eq!(patches.apply(14106, q), q);
+
+        assert!(parse_sbi(&data[0..(data.len() - 1)]).is_err());
+        assert!(parse_sbi(b"SBX\0").is_err());
+    }
+
+    #[test]
+    fn test_parse_lsd() {
+        let data = [
+            0x03, 0x08, 0x05, 0x41, 0x01, 0x01, 0x03, 0x06, 0x05, 0x00, 0x03, 0x08, 0x05, 0x12, 0x34, 0x03, 0x08, 0x10, 0x41, 0x01, 0x01, 0x03, 0x06, 0x0A, 0x00, 0x03, 0x0A, 0x10,
+            0x56, 0x78,
+        ];
+
+        let patches = SubchannelPatches {
+            patches: parse_lsd(&data).unwrap(),
+        };
+        assert_eq!(patches.patches.len(), 2);
+
+        let q = [0xFF; SUBCHANNEL_Q_SIZE];
+        assert_eq!(patches.apply(14105, q), [0x41, 0x01, 0x01, 0x03, 0x06, 0x05, 0x00, 0x03, 0x08, 0x05, 0x12, 0x34]);
+        assert_eq!(patches.apply(14110, q), [0x41, 0x01, 0x01, 0x03, 0x06, 0x0A, 0x00, 0x03, 0x0A, 0x10, 0x56, 0x78]);
+
+        assert!(parse_lsd(&data[0..(data.len() - 1)]).is_err());
+    }
+}
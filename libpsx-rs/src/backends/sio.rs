@@ -0,0 +1,114 @@
+pub mod pipe;
+pub mod socket;
+
+use parking_lot::Mutex;
+
+/// State of the handshaking lines driven by one end of a link cable.
+/// The local DTR/RTS outputs are seen as the DSR/CTS inputs at the remote end.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LinkLines {
+    pub dtr: bool,
+    pub rts: bool,
+}
+
+/// Link cable connection to a remote serial device (usually another emulator instance).
+/// Methods are called from the SIO1 controller and must not block.
+pub trait LinkBackend: Send {
+    /// Sends a byte to the remote end.
+    fn transmit(&mut self, value: u8) -> Result<(), String>;
+
+    /// Returns the next byte sent by the remote end, if one is available.
+    fn receive(&mut self) -> Result<Option<u8>, String>;
+
+    /// Sets the local output lines.
+    fn set_output_lines(&mut self, lines: LinkLines) -> Result<(), String>;
+
+    /// Returns the output lines last set by the remote end (inactive if disconnected).
+    fn input_lines(&mut self) -> Result<LinkLines, String>;
+}
+
+pub enum SioBackend<'a> {
+    None,
+    Link(Mutex<Box<dyn LinkBackend + 'a>>),
+}
+
+impl<'a> SioBackend<'a> {
+    pub fn link<B: LinkBackend + 'a>(backend: B) -> SioBackend<'a> {
+        SioBackend::Link(Mutex::new(Box::new(backend)))
+    }
+}
+
+/// Messages exchanged between the two ends of a link cable.
+/// Line changes are sent in-band so they stay ordered with respect to the data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum LinkMessage {
+    Data(u8),
+    Lines(LinkLines),
+}
+
+impl LinkMessage {
+    const TAG_DATA: u8 = 0;
+    const TAG_LINES: u8 = 1;
+
+    pub(crate) fn encode(self) -> [u8; 2] {
+        match self {
+            LinkMessage::Data(value) => [LinkMessage::TAG_DATA, value],
+            LinkMessage::Lines(lines) => [LinkMessage::TAG_LINES, (lines.dtr as u8) | ((lines.rts as u8) << 1)],
+        }
+    }
+
+    pub(crate) fn decode(bytes: [u8; 2]) -> Result<LinkMessage, String> {
+        match bytes[0] {
+            LinkMessage::TAG_DATA => Ok(LinkMessage::Data(bytes[1])),
+            LinkMessage::TAG_LINES => {
+                Ok(LinkMessage::Lines(LinkLines {
+                    dtr: (bytes[1] & 1) > 0,
+                    rts: (bytes[1] & 2) > 0,
+                }))
+            },
+            tag => Err(format!("Invalid link message tag 0x{:02X}", tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_message_encoding() {
+        let cases = [
+            (LinkMessage::Data(0x00), [0x00, 0x00]),
+            (LinkMessage::Data(0xA5), [0x00, 0xA5]),
+            (LinkMessage::Lines(LinkLines::default()), [0x01, 0x00]),
+            (
+                LinkMessage::Lines(LinkLines {
+                    dtr: true,
+                    rts: false,
+                }),
+                [0x01, 0x01],
+            ),
+            (
+                LinkMessage::Lines(LinkLines {
+                    dtr: false,
+                    rts: true,
+                }),
+                [0x01, 0x02],
+            ),
+            (
+                LinkMessage::Lines(LinkLines {
+                    dtr: true,
+                    rts: true,
+                }),
+                [0x01, 0x03],
+            ),
+        ];
+
+        for &(message, bytes) in cases.iter() {
+            assert_eq!(message.encode(), bytes);
+            assert_eq!(LinkMessage::decode(bytes).unwrap(), message);
+        }
+
+        assert!(LinkMessage::decode([0x02, 0x00]).is_err());
+    }
+}
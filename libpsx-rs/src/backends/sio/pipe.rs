@@ -0,0 +1,69 @@
+use crate::backends::sio::{
+    LinkBackend,
+    LinkLines,
+    LinkMessage,
+};
+use std::sync::mpsc::{
+    channel,
+    Receiver,
+    Sender,
+    TryRecvError,
+};
+
+/// In-process link cable end; see `create`.
+pub struct PipeLink {
+    sender: Sender<LinkMessage>,
+    receiver: Receiver<LinkMessage>,
+    remote_lines: LinkLines,
+}
+
+/// Creates both ends of an in-process link cable, for connecting two cores within the same process.
+pub fn create() -> (PipeLink, PipeLink) {
+    let (sender_a, receiver_b) = channel();
+    let (sender_b, receiver_a) = channel();
+
+    let end_a = PipeLink {
+        sender: sender_a,
+        receiver: receiver_a,
+        remote_lines: LinkLines::default(),
+    };
+
+    let end_b = PipeLink {
+        sender: sender_b,
+        receiver: receiver_b,
+        remote_lines: LinkLines::default(),
+    };
+
+    (end_a, end_b)
+}
+
+impl LinkBackend for PipeLink {
+    fn transmit(&mut self, value: u8) -> Result<(), String> {
+        // A disconnected remote end behaves like an unplugged cable; the data is lost.
+        let _ = self.sender.send(LinkMessage::Data(value));
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Option<u8>, String> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(LinkMessage::Data(value)) => return Ok(Some(value)),
+                Ok(LinkMessage::Lines(lines)) => self.remote_lines = lines,
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Disconnected) => {
+                    self.remote_lines = LinkLines::default();
+                    return Ok(None);
+                },
+            }
+        }
+    }
+
+    fn set_output_lines(&mut self, lines: LinkLines) -> Result<(), String> {
+        let _ = self.sender.send(LinkMessage::Lines(lines));
+        Ok(())
+    }
+
+    fn input_lines(&mut self) -> Result<LinkLines, String> {
+        Ok(self.remote_lines)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::backends::sio::{
+    LinkBackend,
+    LinkLines,
+    LinkMessage,
+};
+use std::{
+    collections::VecDeque,
+    io::{
+        ErrorKind as IoErrorKind,
+        Read,
+        Result as IoResult,
+        Write,
+    },
+    net::{
+        TcpListener,
+        TcpStream,
+        ToSocketAddrs,
+    },
+};
+
+/// Link cable end connected to another process over a TCP socket (usually on localhost).
+pub struct SocketLink {
+    stream: Option<TcpStream>,
+    buffer: VecDeque<u8>,
+    remote_lines: LinkLines,
+}
+
+impl SocketLink {
+    /// Waits for the remote end to connect; blocks until a connection is accepted.
+    pub fn listen<A: ToSocketAddrs>(address: A) -> IoResult<SocketLink> {
+        let listener = TcpListener::bind(address)?;
+        log::info!("Waiting for a link cable connection on {}", listener.local_addr()?);
+        let (stream, remote_address) = listener.accept()?;
+        log::info!("Link cable connected to {}", remote_address);
+        SocketLink::from_stream(stream)
+    }
+
+    /// Connects to a remote end that is listening.
+    pub fn connect<A: ToSocketAddrs>(address: A) -> IoResult<SocketLink> {
+        let stream = TcpStream::connect(address)?;
+        log::info!("Link cable connected to {}", stream.peer_addr()?);
+        SocketLink::from_stream(stream)
+    }
+
+    fn from_stream(stream: TcpStream) -> IoResult<SocketLink> {
+        stream.set_nodelay(true)?;
+        stream.set_nonblocking(true)?;
+
+        Ok(SocketLink {
+            stream: Some(stream),
+            buffer: VecDeque::new(),
+            remote_lines: LinkLines::default(),
+        })
+    }
+
+    fn disconnect(&mut self) {
+        log::warn!("Link cable disconnected");
+        self.stream = None;
+        self.buffer.clear();
+        self.remote_lines = LinkLines::default();
+    }
+
+    fn send(&mut self, message: LinkMessage) -> Result<(), String> {
+        let stream = match self.stream {
+            Some(ref mut stream) => stream,
+            None => return Ok(()),
+        };
+
+        let bytes = message.encode();
+        let mut written = 0;
+
+        while written < bytes.len() {
+            match stream.write(&bytes[written..]) {
+                Ok(0) => {
+                    self.disconnect();
+                    return Ok(());
+                },
+                Ok(count) => written += count,
+                Err(ref e) if e.kind() == IoErrorKind::WouldBlock || e.kind() == IoErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.disconnect();
+                    return Err(format!("Error writing to the link cable socket: {}", e));
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn poll(&mut self) -> Result<(), String> {
+        let stream = match self.stream {
+            Some(ref mut stream) => stream,
+            None => return Ok(()),
+        };
+
+        let mut data = [0; 64];
+
+        loop {
+            match stream.read(&mut data) {
+                Ok(0) => {
+                    self.disconnect();
+                    return Ok(());
+                },
+                Ok(count) => self.buffer.extend(&data[..count]),
+                Err(ref e) if e.kind() == IoErrorKind::WouldBlock => return Ok(()),
+                Err(ref e) if e.kind() == IoErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.disconnect();
+                    return Err(format!("Error reading from the link cable socket: {}", e));
+                },
+            }
+        }
+    }
+}
+
+impl LinkBackend for SocketLink {
+    fn transmit(&mut self, value: u8) -> Result<(), String> {
+        self.send(LinkMessage::Data(value))
+    }
+
+    fn receive(&mut self) -> Result<Option<u8>, String> {
+        self.poll()?;
+
+        while self.buffer.len() >= 2 {
+            let bytes = [self.buffer.pop_front().unwrap(), self.buffer.pop_front().unwrap()];
+
+            match LinkMessage::decode(bytes)? {
+                LinkMessage::Data(value) => return Ok(Some(value)),
+                LinkMessage::Lines(lines) => self.remote_lines = lines,
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn set_output_lines(&mut self, lines: LinkLines) -> Result<(), String> {
+        self.send(LinkMessage::Lines(lines))
+    }
+
+    fn input_lines(&mut self) -> Result<LinkLines, String> {
+        Ok(self.remote_lines)
+    }
+}
@@ -7,8 +7,10 @@ use crate::{
             crtc::controllers::run as run_gpu_crtc,
         },
         intc::controllers::run as run_intc,
+        mdec::controllers::run as run_mdec,
         padmc::controllers::run as run_padmc,
         r3000::controllers::run as run_r3000,
+        sio1::controllers::run as run_sio1,
         spu::controllers::run as run_spu,
         timers::controllers::run as run_timers,
         types::{
@@ -48,9 +50,10 @@ use std::{
 };
 use thread_priority::*;
 
-const CONTROLLER_COUNT: usize = 9;
-const CONTROLLER_HANDLERS: [ControllerHandler; CONTROLLER_COUNT] = [run_r3000, run_intc, run_dmac, run_gpu, run_spu, run_timers, run_cdrom, run_padmc, run_gpu_crtc];
-const CONTROLLER_NAMES: [&'static str; CONTROLLER_COUNT] = ["r3000", "intc", "dmac", "gpu", "spu", "timers", "cdrom", "padmc", "gpu_crtc"];
+const CONTROLLER_COUNT: usize = 11;
+const CONTROLLER_HANDLERS: [ControllerHandler; CONTROLLER_COUNT] =
+    [run_r3000, run_intc, run_dmac, run_gpu, run_spu, run_timers, run_cdrom, run_padmc, run_gpu_crtc, run_sio1, run_mdec];
+const CONTROLLER_NAMES: [&'static str; CONTROLLER_COUNT] = ["r3000", "intc", "dmac", "gpu", "spu", "timers", "cdrom", "padmc", "gpu_crtc", "sio1", "mdec"];
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum TaskStatus {
@@ -325,6 +328,8 @@ impl Executor {
             config.cdrom_bias,
             config.padmc_bias,
             config.gpu_crtc_bias,
+            config.sio1_bias,
+            config.mdec_bias,
         ];
 
         let time_delta = config.time_delta * config.global_bias;
@@ -0,0 +1,276 @@
+//! Input API for feeding peripheral (controller port) state into the core.
+
+use crate::{
+    system::padmc::{
+        constants::*,
+        types::{
+            ControllerDeviceState,
+            JoypadState,
+        },
+    },
+    types::bitfield::Bitfield,
+    Core,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Port {
+    Port1,
+    Port2,
+}
+
+/// Multitap slot; only slot A is used when there is no multitap connected.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Slot {
+    A,
+    B,
+    C,
+    D,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ControllerKind {
+    None,
+    Digital,
+    DualShock,
+    Mouse,
+    /// Lightgun; light is only detected for one GunCon per port.
+    GunCon,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PortConfig {
+    /// Connect a multitap adapter to the port, making slots B-D available.
+    pub multitap: bool,
+    /// Controller connected to each slot.
+    pub controllers: [ControllerKind; 4],
+    /// Memory card inserted into each slot.
+    pub memory_cards: [bool; 4],
+}
+
+impl PortConfig {
+    /// A single DualShock and memory card, with no multitap.
+    pub fn new() -> PortConfig {
+        PortConfig {
+            multitap: false,
+            controllers: [ControllerKind::DualShock, ControllerKind::None, ControllerKind::None, ControllerKind::None],
+            memory_cards: [true, false, false, false],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Button {
+    Select,
+    L3,
+    R3,
+    Start,
+    Up,
+    Right,
+    Down,
+    Left,
+    L2,
+    R2,
+    L1,
+    R1,
+    Triangle,
+    Circle,
+    Cross,
+    Square,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Axis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+}
+
+/// Buttons of pointer devices (mouse / GunCon).
+/// For the GunCon, left is the trigger, right is the A button and middle is the B button.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PointerButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Called when the rumble motor values of a joypad change; arguments are the port, slot, small motor (0x00 or 0xFF) and
+/// large motor (0x00 to 0xFF) values.
+pub type RumbleCallback = Box<dyn FnMut(Port, Slot, u8, u8)>;
+
+pub(crate) fn set_button(core: &mut Core, port: Port, slot: Slot, button: Button, pressed: bool) {
+    if let Some(joypad_state) = joypad_state(core, port, slot) {
+        // Buttons are active-low.
+        let value = if pressed {
+            0
+        } else {
+            1
+        };
+        joypad_state.buttons = button_bitfield(button).insert_into(joypad_state.buttons, value);
+    }
+}
+
+pub(crate) fn set_axis(core: &mut Core, port: Port, slot: Slot, axis: Axis, value: u8) {
+    if let Some(joypad_state) = joypad_state(core, port, slot) {
+        match axis {
+            Axis::LeftX => joypad_state.left_x = value,
+            Axis::LeftY => joypad_state.left_y = value,
+            Axis::RightX => joypad_state.right_x = value,
+            Axis::RightY => joypad_state.right_y = value,
+        }
+    }
+}
+
+/// Emulates pressing the analog button, which toggles between digital and analog modes unless locked by the software.
+pub(crate) fn toggle_analog_mode(core: &mut Core, port: Port, slot: Slot) -> bool {
+    match joypad_state(core, port, slot) {
+        Some(joypad_state) => {
+            if joypad_state.dualshock && !joypad_state.analog_mode_locked {
+                joypad_state.analog_mode = !joypad_state.analog_mode;
+            }
+
+            joypad_state.analog_mode
+        },
+        None => false,
+    }
+}
+
+pub(crate) fn set_pointer_button(core: &mut Core, port: Port, slot: Slot, button: PointerButton, pressed: bool) {
+    let controller_state = core.state.padmc.controller_state.get_mut();
+    // Buttons are active-low.
+    let value = if pressed {
+        0
+    } else {
+        1
+    };
+
+    match controller_state.port_states[port_index(port)].controller_states[slot_index(slot)] {
+        ControllerDeviceState::Mouse(ref mut mouse_state) => {
+            let bitfield = match button {
+                PointerButton::Left => MOUSE_BUTTON_LEFT,
+                PointerButton::Right => MOUSE_BUTTON_RIGHT,
+                PointerButton::Middle => return,
+            };
+
+            mouse_state.buttons = bitfield.insert_into(mouse_state.buttons, value);
+        },
+        ControllerDeviceState::GunCon(ref mut guncon_state) => {
+            let bitfield = match button {
+                PointerButton::Left => GUNCON_BUTTON_TRIGGER,
+                PointerButton::Right => GUNCON_BUTTON_A,
+                PointerButton::Middle => GUNCON_BUTTON_B,
+            };
+
+            guncon_state.buttons = bitfield.insert_into(guncon_state.buttons, value);
+        },
+        _ => {},
+    }
+}
+
+/// Relative pointer motion, used by the mouse.
+pub(crate) fn move_pointer(core: &mut Core, port: Port, slot: Slot, delta_x: i32, delta_y: i32) {
+    let controller_state = core.state.padmc.controller_state.get_mut();
+
+    if let ControllerDeviceState::Mouse(ref mut mouse_state) = controller_state.port_states[port_index(port)].controller_states[slot_index(slot)] {
+        mouse_state.delta_x = mouse_state.delta_x.saturating_add(delta_x);
+        mouse_state.delta_y = mouse_state.delta_y.saturating_add(delta_y);
+    }
+}
+
+/// Absolute pointer position, used by the GunCon.
+/// The position is normalized to the visible display area (0.0 to 1.0); None means pointing off-screen.
+pub(crate) fn set_pointer_position(core: &mut Core, port: Port, slot: Slot, position: Option<(f32, f32)>) {
+    let is_guncon = match core.state.padmc.controller_state.get_mut().port_states[port_index(port)].controller_states[slot_index(slot)] {
+        ControllerDeviceState::GunCon(_) => true,
+        _ => false,
+    };
+
+    if is_guncon {
+        let position = position.filter(|&(x, y)| (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y));
+        core.state.padmc.lightgun_state.get_mut().pointers[port_index(port)] = position;
+    }
+}
+
+pub(crate) fn handle_rumble(core: &mut Core) {
+    let controller_state = core.state.padmc.controller_state.get_mut();
+
+    for (port_index, port_state) in controller_state.port_states.iter_mut().enumerate() {
+        for (slot_index, controller_device_state) in port_state.controller_states.iter_mut().enumerate() {
+            let joypad_state = match controller_device_state {
+                ControllerDeviceState::Joypad(joypad_state) if joypad_state.motors_changed => joypad_state,
+                _ => continue,
+            };
+
+            joypad_state.motors_changed = false;
+
+            if let Some(ref mut rumble_callback) = core.rumble_callback {
+                rumble_callback(index_port(port_index), index_slot(slot_index), joypad_state.small_motor, joypad_state.large_motor);
+            }
+        }
+    }
+}
+
+fn joypad_state<'a>(core: &'a mut Core, port: Port, slot: Slot) -> Option<&'a mut JoypadState> {
+    let controller_state = core.state.padmc.controller_state.get_mut();
+
+    match controller_state.port_states[port_index(port)].controller_states[slot_index(slot)] {
+        ControllerDeviceState::Joypad(ref mut joypad_state) => Some(joypad_state),
+        _ => None,
+    }
+}
+
+fn port_index(port: Port) -> usize {
+    match port {
+        Port::Port1 => 0,
+        Port::Port2 => 1,
+    }
+}
+
+fn slot_index(slot: Slot) -> usize {
+    match slot {
+        Slot::A => 0,
+        Slot::B => 1,
+        Slot::C => 2,
+        Slot::D => 3,
+    }
+}
+
+fn index_slot(index: usize) -> Slot {
+    match index {
+        0 => Slot::A,
+        1 => Slot::B,
+        2 => Slot::C,
+        3 => Slot::D,
+        _ => unreachable!(),
+    }
+}
+
+fn index_port(index: usize) -> Port {
+    match index {
+        0 => Port::Port1,
+        1 => Port::Port2,
+        _ => unreachable!(),
+    }
+}
+
+fn button_bitfield(button: Button) -> Bitfield {
+    match button {
+        Button::Select => BUTTON_SELECT,
+        Button::L3 => BUTTON_L3,
+        Button::R3 => BUTTON_R3,
+        Button::Start => BUTTON_START,
+        Button::Up => BUTTON_UP,
+        Button::Right => BUTTON_RIGHT,
+        Button::Down => BUTTON_DOWN,
+        Button::Left => BUTTON_LEFT,
+        Button::L2 => BUTTON_L2,
+        Button::R2 => BUTTON_R2,
+        Button::L1 => BUTTON_L1,
+        Button::R1 => BUTTON_R1,
+        Button::Triangle => BUTTON_TRIANGLE,
+        Button::Circle => BUTTON_CIRCLE,
+        Button::Cross => BUTTON_CROSS,
+        Button::Square => BUTTON_SQUARE,
+    }
+}
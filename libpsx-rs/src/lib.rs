@@ -4,6 +4,8 @@
 pub mod backends;
 pub(crate) mod debug;
 pub(crate) mod executor;
+pub mod input;
+pub mod media;
 #[cfg(feature = "serialization")]
 pub(crate) mod serialization;
 pub(crate) mod system;
@@ -20,6 +22,7 @@ use crate::{
             self,
             CdromBackend,
         },
+        sio::SioBackend,
         video::{
             self,
             VideoBackend,
@@ -29,6 +32,15 @@ use crate::{
 };
 use executor::Executor;
 pub use executor::ThreadingKind;
+use input::{
+    Axis,
+    Button,
+    PointerButton,
+    Port,
+    PortConfig,
+    RumbleCallback,
+    Slot,
+};
 use std::{
     io::Result as IoResult,
     path::{
@@ -44,6 +56,7 @@ pub struct Config<'a> {
     pub video_backend: VideoBackend<'a>,
     pub audio_backend: AudioBackend<'a>,
     pub cdrom_backend: CdromBackend<'a>,
+    pub sio_backend: SioBackend<'a>,
     pub time_delta: f32,
     pub threading: ThreadingKind,
     pub internal_scale_factor: usize,
@@ -56,12 +69,48 @@ pub struct Config<'a> {
     pub timers_bias: f32,
     pub cdrom_bias: f32,
     pub padmc_bias: f32,
+    pub sio1_bias: f32,
+    pub mdec_bias: f32,
     pub intc_bias: f32,
+    pub port1: PortConfig,
+    pub port2: PortConfig,
+    pub cdrom: CdromConfig,
+}
+
+/// Console region (SKU); the drive only accepts discs licensed for the same region.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConsoleRegion {
+    NtscU,
+    NtscJ,
+    Pal,
+}
+
+/// CDROM drive settings.
+#[derive(Debug, Copy, Clone)]
+pub struct CdromConfig {
+    /// Shortens seek, spin-up and data sector read times; streamed audio still plays in real time, and command
+    /// responses keep their order.
+    pub fast_loading: bool,
+    pub region: ConsoleRegion,
+    /// Accepts unlicensed and other region discs, as a modchip does.
+    pub modchip: bool,
+}
+
+impl CdromConfig {
+    /// Hardware accurate timing, NTSC-U console without a modchip.
+    pub fn new() -> CdromConfig {
+        CdromConfig {
+            fast_loading: false,
+            region: ConsoleRegion::NtscU,
+            modchip: false,
+        }
+    }
 }
 
 pub struct Core<'a: 'b, 'b> {
     pub(crate) state: Box<State>,
     pub(crate) config: &'b Config<'a>,
+    pub(crate) rumble_callback: Option<RumbleCallback>,
     executor: Executor,
 }
 
@@ -69,7 +118,7 @@ impl<'a: 'b, 'b> Core<'a, 'b> {
     pub fn new(config: &'b Config<'a>) -> IoResult<Core<'a, 'b>> {
         log::info!("Initializing core");
 
-        let state = State::with_bios(&config.workspace_path, &config.bios_filename)?;
+        let state = create_state(config)?;
         let executor = Executor::new(config.threading);
 
         video::setup(config);
@@ -79,13 +128,14 @@ impl<'a: 'b, 'b> Core<'a, 'b> {
         Ok(Core {
             state,
             config,
+            rumble_callback: None,
             executor,
         })
     }
 
     pub fn reset(&mut self, hard_reset: bool) -> IoResult<()> {
         if hard_reset {
-            self.state = State::with_bios(&self.config.workspace_path, &self.config.bios_filename)?;
+            self.state = create_state(self.config)?;
         } else {
             State::initialize(&mut self.state);
         }
@@ -99,15 +149,76 @@ impl<'a: 'b, 'b> Core<'a, 'b> {
             video_backend: &self.config.video_backend,
             audio_backend: &self.config.audio_backend,
             cdrom_backend: &self.config.cdrom_backend,
+            sio_backend: &self.config.sio_backend,
         };
 
-        self.executor.run(iterations, &self.config, &context)
+        self.executor.run(iterations, &self.config, &context)?;
+
+        input::handle_rumble(self);
+
+        let elapsed = self.config.time_delta * self.config.global_bias * iterations as f32;
+        State::flush_memory_cards(&mut self.state, &self.config.workspace_path.join(r"memcards/"), Some(elapsed)).map_err(|e| vec![format!("Error flushing memory cards: {}", e)])
     }
 
+    /// Swaps the disc in the backend; open the lid beforehand (and close it afterwards) for the game to notice the
+    /// change.
     pub fn change_disc(&mut self, path: &Path) -> Result<(), String> {
         backends::cdrom::change_disc(&self.config, path)
     }
 
+    /// Number of discs in the loaded disc image; multi-disc images (PBP) can hold more than one.
+    pub fn disc_count(&self) -> usize {
+        backends::cdrom::disc_count(&self.config)
+    }
+
+    /// Switches to another disc (0 based) of a multi-disc image.
+    pub fn select_disc(&mut self, index: usize) -> Result<(), String> {
+        backends::cdrom::select_disc(&self.config, index)
+    }
+
+    /// Opens the disc tray lid; the drive stops, and reports the shell as open until the lid is closed again.
+    pub fn open_lid(&mut self) {
+        State::set_lid_open(&mut self.state, true)
+    }
+
+    /// Closes the disc tray lid; the drive spins up and re-reads the TOC before the disc can be accessed.
+    pub fn close_lid(&mut self) {
+        State::set_lid_open(&mut self.state, false)
+    }
+
+    pub fn lid_open(&self) -> bool {
+        State::lid_open(&self.state)
+    }
+
+    pub fn set_button(&mut self, port: Port, slot: Slot, button: Button, pressed: bool) {
+        input::set_button(self, port, slot, button, pressed)
+    }
+
+    pub fn set_axis(&mut self, port: Port, slot: Slot, axis: Axis, value: u8) {
+        input::set_axis(self, port, slot, axis, value)
+    }
+
+    /// Returns true if the joypad is in analog mode after the toggle.
+    pub fn toggle_analog_mode(&mut self, port: Port, slot: Slot) -> bool {
+        input::toggle_analog_mode(self, port, slot)
+    }
+
+    pub fn set_pointer_button(&mut self, port: Port, slot: Slot, button: PointerButton, pressed: bool) {
+        input::set_pointer_button(self, port, slot, button, pressed)
+    }
+
+    pub fn move_pointer(&mut self, port: Port, slot: Slot, delta_x: i32, delta_y: i32) {
+        input::move_pointer(self, port, slot, delta_x, delta_y)
+    }
+
+    pub fn set_pointer_position(&mut self, port: Port, slot: Slot, position: Option<(f32, f32)>) {
+        input::set_pointer_position(self, port, slot, position)
+    }
+
+    pub fn set_rumble_callback(&mut self, callback: Option<RumbleCallback>) {
+        self.rumble_callback = callback;
+    }
+
     pub fn analyze(&mut self) -> IoResult<()> {
         debug::analysis(self)
     }
@@ -125,8 +236,20 @@ impl<'a: 'b, 'b> Core<'a, 'b> {
 
 impl<'a: 'b, 'b> Drop for Core<'a, 'b> {
     fn drop(&mut self) {
+        if let Err(e) = State::flush_memory_cards(&mut self.state, &self.config.workspace_path.join(r"memcards/"), None) {
+            log::error!("Error flushing memory cards: {}", e);
+        }
+
         video::teardown(&self.config);
         audio::teardown(&self.config);
         cdrom::teardown(&self.config);
     }
 }
+
+fn create_state(config: &Config) -> IoResult<Box<State>> {
+    let mut state = State::with_bios(&config.workspace_path, &config.bios_filename)?;
+    State::setup_ports(&mut state, &config.port1, &config.port2);
+    State::setup_cdrom(&mut state, &config.cdrom);
+    State::load_memory_cards(&mut state, &config.workspace_path.join(r"memcards/"))?;
+    Ok(state)
+}
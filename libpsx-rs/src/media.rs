@@ -0,0 +1,281 @@
+//! Host access to the emulated CDROM drive, DMA controller and MDEC, standing in for the CPU without running the full
+//! system. Used to play STR movies and XA audio streams through the emulated hardware, such as for testing.
+
+use crate::{
+    backends::{
+        audio::AudioBackend,
+        cdrom::{
+            image,
+            CdromBackend,
+        },
+        sio::SioBackend,
+        video::VideoBackend,
+    },
+    system::{
+        bus::types::*,
+        cdrom::{
+            self,
+            constants::{
+                INTERRUPT_FLAGS,
+                REQUEST_BFRD,
+            },
+            memory::*,
+        },
+        dmac::{
+            self,
+            constants::*,
+            memory::*,
+        },
+        mdec::{
+            self,
+            constants::{
+                CLOCK_SPEED_PERIOD,
+                STATUS_COMMAND_BUSY,
+            },
+            memory::{
+                control_read_u32 as mdec_control_read_u32,
+                control_write_u32 as mdec_control_write_u32,
+            },
+        },
+        memory::constants::MAIN_MEMORY_SIZE,
+        types::{
+            ControllerContext,
+            Event,
+            State,
+        },
+    },
+};
+use std::path::Path;
+
+/// Bytes of 24-bit RGB output per 16x16 macroblock.
+pub const MACROBLOCK_RGB_SIZE: usize = 16 * 16 * 3;
+
+const SLICE_DURATION: f32 = 256.0 * CLOCK_SPEED_PERIOD;
+/// Emulated time to wait for an interrupt or a DMA transfer before giving up.
+const TIMEOUT_DURATION: f32 = 10.0;
+
+/// Main memory used for the DMA transfers.
+const SECTOR_BUFFER_ADDRESS: u32 = 0x1_0000;
+const MDEC_IN_ADDRESS: u32 = 0x2_0000;
+const MDEC_OUT_ADDRESS: u32 = 0x10_0000;
+
+const CHANNEL_MDEC_IN: usize = 0;
+const CHANNEL_MDEC_OUT: usize = 1;
+const CHANNEL_CDROM: usize = 3;
+const DMA_BLOCK_SIZE: usize = 32;
+
+/// An interrupt raised by the CDROM drive (INT1-INT5), along with its response bytes.
+pub struct CdromInterrupt {
+    pub index: u8,
+    pub response: Vec<u8>,
+}
+
+/// The emulated CDROM drive (with the image backend), DMA controller and MDEC.
+/// Registers are accessed as the CPU would, and the CD audio sent to the SPU CD input is collected as it is produced.
+pub struct MediaSystem {
+    state: Box<State>,
+    cdrom_backend: CdromBackend<'static>,
+    cd_audio: Vec<i16>,
+}
+
+impl MediaSystem {
+    /// Loads a disc image or STR / XA stream file through the image CDROM backend.
+    pub fn open(path: &Path) -> Result<MediaSystem, String> {
+        let backend_params = image::BackendParams::new();
+        image::load_disc(&backend_params, path)?;
+
+        let mut system = MediaSystem {
+            state: State::new(),
+            cdrom_backend: CdromBackend::Image(backend_params),
+            cd_audio: Vec::new(),
+        };
+
+        let channels = [DPCR_MDECIN_ENABLE, DPCR_MDECOUT_ENABLE, DPCR_CDROM_ENABLE].iter().fold(0, |value, enable| enable.insert_into(value, 1));
+        system.write(|state| dpcr_write_u32(state, 0, channels))?;
+        system.write_cdrom(1, 2, INTERRUPT_FLAGS.insert_into(0, 0x1F))?;
+        Ok(system)
+    }
+
+    /// Sends a command, once the parameters have been written to the parameter FIFO.
+    pub fn send_command(&mut self, command: u8, parameters: &[u8]) -> Result<(), String> {
+        for &parameter in parameters.iter() {
+            self.write_cdrom(0, 2, parameter)?;
+        }
+
+        self.write_cdrom(0, 1, command)
+    }
+
+    /// Waits for the next CDROM interrupt; the response FIFO is emptied and the interrupt acknowledged.
+    pub fn wait_interrupt(&mut self) -> Result<CdromInterrupt, String> {
+        let mut elapsed = 0.0;
+
+        let index = loop {
+            status_write_u8(&self.state, 0, 1).unwrap();
+            if let Ok(value) = cdrom1803_read_u8(&self.state, 0) {
+                let index = INTERRUPT_FLAGS.extract_from(value);
+                if index > 0 {
+                    break index;
+                }
+            }
+
+            self.step(&mut elapsed, "CDROM interrupt")?;
+        };
+
+        let mut response = Vec::new();
+        while let Ok(value) = cdrom1801_read_u8(&self.state, 0) {
+            response.push(value);
+        }
+
+        self.write_cdrom(1, 3, INTERRUPT_FLAGS.insert_into(0, 0x1F))?;
+
+        Ok(CdromInterrupt {
+            index,
+            response,
+        })
+    }
+
+    /// Loads the sector that raised the last data ready interrupt into the data FIFO, and transfers it to memory
+    /// through DMA3. The size (in bytes) depends on the Setmode sector size.
+    pub fn read_sector(&mut self, size: usize) -> Result<Vec<u8>, String> {
+        if size % 4 != 0 {
+            return Err(format!("Sector size {} is not a whole number of words", size));
+        }
+
+        self.write_cdrom(0, 3, REQUEST_BFRD.insert_into(0, 1))?;
+        self.start_dma(CHANNEL_CDROM, SECTOR_BUFFER_ADDRESS, (size / 4) as u32, CHCR_STARTTRIGGER.insert_into(dma_chcr(0, false), 1))?;
+        self.wait_dma(CHANNEL_CDROM)?;
+
+        Ok(self.state.memory.main_memory.read_raw(SECTOR_BUFFER_ADDRESS)[0..size].to_vec())
+    }
+
+    /// Writes the MDEC control register (reset / DMA enables).
+    pub fn write_mdec_control(&mut self, value: u32) -> Result<(), String> {
+        self.write(|state| mdec_control_write_u32(state, 0, value))
+    }
+
+    /// Sends an MDEC command and its parameters through DMA0, collecting output_words words of output through DMA1.
+    /// Returns once the MDEC has finished the command.
+    pub fn mdec_transfer(&mut self, input: &[u32], output_words: usize) -> Result<Vec<u32>, String> {
+        if input.is_empty() || (input.len() > 0x1_0000) || (MDEC_IN_ADDRESS as usize + (input.len() * 4) > MDEC_OUT_ADDRESS as usize) {
+            return Err(format!("Invalid MDEC input size ({} words)", input.len()));
+        }
+
+        if (output_words % DMA_BLOCK_SIZE != 0) || (MDEC_OUT_ADDRESS as usize + (output_words * 4) > MAIN_MEMORY_SIZE) {
+            return Err(format!("Invalid MDEC output size ({} words)", output_words));
+        }
+
+        for (index, &value) in input.iter().enumerate() {
+            self.state.memory.main_memory.write_u32(MDEC_IN_ADDRESS + (index as u32 * 4), value);
+        }
+
+        if output_words > 0 {
+            let bcr = (((output_words / DMA_BLOCK_SIZE) as u32) << 16) | DMA_BLOCK_SIZE as u32;
+            self.start_dma(CHANNEL_MDEC_OUT, MDEC_OUT_ADDRESS, bcr, dma_chcr(1, false))?;
+        }
+
+        let bcr = (1 << 16) | (input.len() as u32 & 0xFFFF);
+        self.start_dma(CHANNEL_MDEC_IN, MDEC_IN_ADDRESS, bcr, dma_chcr(1, true))?;
+        self.wait_dma(CHANNEL_MDEC_IN)?;
+
+        if output_words > 0 {
+            self.wait_dma(CHANNEL_MDEC_OUT)?;
+        }
+
+        let mut elapsed = 0.0;
+        while STATUS_COMMAND_BUSY.extract_from(mdec_control_read_u32(&self.state, 0).unwrap()) > 0 {
+            self.step(&mut elapsed, "MDEC command")?;
+        }
+
+        Ok((0..output_words).map(|index| self.state.memory.main_memory.read_u32(MDEC_OUT_ADDRESS + (index as u32 * 4))).collect())
+    }
+
+    /// Takes the CD audio (44.1 kHz stereo, interleaved left/right) sent to the SPU CD input so far.
+    pub fn take_cd_audio(&mut self) -> Vec<i16> {
+        std::mem::replace(&mut self.cd_audio, Vec::new())
+    }
+
+    fn start_dma(&mut self, channel_id: usize, address: u32, bcr: u32, chcr: u32) -> Result<(), String> {
+        self.write(|state| madr_write_u32(state, 0, address, channel_id))?;
+        self.write(|state| bcr_write_u32(state, 0, bcr, channel_id))?;
+        self.write(|state| chcr_write_u32(state, 0, chcr, channel_id))
+    }
+
+    fn wait_dma(&mut self, channel_id: usize) -> Result<(), String> {
+        let mut elapsed = 0.0;
+
+        loop {
+            // The control register reads as not ready until the DMAC has taken the start request.
+            if let Ok(value) = chcr_read_u32(&self.state, 0, channel_id) {
+                if CHCR_STARTBUSY.extract_from(value) == 0 {
+                    return Ok(());
+                }
+            }
+
+            self.step(&mut elapsed, "DMA transfer")?;
+        }
+    }
+
+    /// Selects the CDROM register bank (index) and writes one of the ports at 0x1F80_1801-3.
+    fn write_cdrom(&mut self, index: u8, port: usize, value: u8) -> Result<(), String> {
+        let write = match port {
+            1 => cdrom1801_write_u8,
+            2 => cdrom1802_write_u8,
+            3 => cdrom1803_write_u8,
+            _ => return Err(format!("Invalid CDROM port {}", port)),
+        };
+
+        self.write(|state| {
+            status_write_u8(state, 0, index)?;
+            write(state, 0, value)
+        })
+    }
+
+    /// Performs a register write, letting the hardware run until the register is ready to accept it.
+    fn write<F: Fn(&State) -> WriteResult>(&mut self, write: F) -> Result<(), String> {
+        let mut elapsed = 0.0;
+
+        loop {
+            match write(&self.state) {
+                Ok(()) => return Ok(()),
+                Err(WriteErrorKind::NotReady) => self.step(&mut elapsed, "register write")?,
+                Err(WriteErrorKind::Full) => return Err("Register write failed: FIFO full".into()),
+            }
+        }
+    }
+
+    /// Runs the hardware for a time slice, collecting the CD audio output.
+    fn step(&mut self, elapsed: &mut f32, description: &str) -> Result<(), String> {
+        if *elapsed >= TIMEOUT_DURATION {
+            return Err(format!("Timed out waiting for {}", description));
+        }
+        *elapsed += SLICE_DURATION;
+
+        let video_backend = VideoBackend::None;
+        let audio_backend = AudioBackend::None;
+        let sio_backend = SioBackend::None;
+        let context = ControllerContext {
+            state: &self.state,
+            video_backend: &video_backend,
+            audio_backend: &audio_backend,
+            cdrom_backend: &self.cdrom_backend,
+            sio_backend: &sio_backend,
+        };
+
+        cdrom::controllers::run(&context, Event::Time(SLICE_DURATION))?;
+        dmac::controllers::run(&context, Event::Time(SLICE_DURATION))?;
+        mdec::controllers::run(&context, Event::Time(SLICE_DURATION))?;
+
+        while let Ok(frame) = self.state.cdrom.audio.read_one() {
+            self.cd_audio.push(frame.left);
+            self.cd_audio.push(frame.right);
+        }
+
+        Ok(())
+    }
+}
+
+/// Channel control value starting a transfer (sync mode 0 = continuous, 1 = blocks), to or from the channel.
+fn dma_chcr(sync_mode: u32, to_channel: bool) -> u32 {
+    let value = CHCR_STARTBUSY.insert_into(CHCR_SYNCMODE.insert_into(0, sync_mode), 1);
+    CHCR_TRANSFER_DIRECTION.insert_into(value, to_channel as u32)
+}
@@ -3,9 +3,11 @@ pub(crate) mod cdrom;
 pub(crate) mod dmac;
 pub(crate) mod gpu;
 pub(crate) mod intc;
+pub(crate) mod mdec;
 pub(crate) mod memory;
 pub(crate) mod padmc;
 pub(crate) mod r3000;
+pub(crate) mod sio1;
 pub(crate) mod spu;
 pub(crate) mod timers;
 pub(crate) mod types;
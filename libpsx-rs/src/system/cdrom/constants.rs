@@ -17,7 +17,90 @@ pub(crate) const CLOCK_SPEED: f32 = 33.8688 * 1e6; // Unknown; 33.8688 MHz
 pub(crate) const CLOCK_SPEED_PERIOD: f32 = 1.0 / CLOCK_SPEED;
 pub(crate) const VERSION: [u8; 4] = [0x94, 0x09, 0x19, 0x19];
 pub(crate) const SECTOR_DELAY_CYCLES_SINGLE_SPEED: usize = 0x6E1CD;
+/// Seek time: a fixed settling time, plus the head travel time (about 1 second end to end); long seeks take extra time
+/// to find the track.
+pub(crate) const SEEK_MIN_CYCLES: usize = 20_000;
+pub(crate) const SEEK_CYCLES_PER_SECTOR: usize = 100;
+pub(crate) const SEEK_LONG_SECTORS: usize = 32;
+pub(crate) const SEEK_LONG_CYCLES: usize = (CLOCK_SPEED / 10.0) as usize;
+pub(crate) const SEEK_MAX_CYCLES: usize = CLOCK_SPEED as usize;
+/// Motor spin-up time from stopped.
+pub(crate) const SPIN_UP_CYCLES: usize = CLOCK_SPEED as usize;
+/// Seek, spin-up and data sector read time divisor with fast loading enabled.
+pub(crate) const FAST_LOADING_FACTOR: usize = 8;
+/// Large enough to hold a whole sector (0x924 bytes).
+pub(crate) const DATA_FIFO_SIZE: usize = 0x1000;
+
+/// Large enough to hold the resampled audio of a few XA sectors.
+pub(crate) const AUDIO_FIFO_SIZE: usize = 0x4000;
+
+pub(crate) const MODE_CDDA: Bitfield = Bitfield::new(0, 1);
+pub(crate) const MODE_AUTO_PAUSE: Bitfield = Bitfield::new(1, 1);
+pub(crate) const MODE_REPORT: Bitfield = Bitfield::new(2, 1);
+pub(crate) const MODE_XA_FILTER: Bitfield = Bitfield::new(3, 1);
+pub(crate) const MODE_SECTOR_SIZE: Bitfield = Bitfield::new(5, 1);
+pub(crate) const MODE_XA_ADPCM: Bitfield = Bitfield::new(6, 1);
+pub(crate) const MODE_DOUBLE_SPEED: Bitfield = Bitfield::new(7, 1);
+
+pub(crate) const AUDIO_APPLY_MUTE_ADPCM: Bitfield = Bitfield::new(0, 1);
+pub(crate) const AUDIO_APPLY_CHANGES: Bitfield = Bitfield::new(5, 1);
+/// Volume matrix value for 100%.
+pub(crate) const AUDIO_VOLUME_UNITY: i32 = 0x80;
+
+/// Raw sector layout: sync (12), header (4), subheader (2 x 4), user data.
+pub(crate) const SECTOR_RAW_SIZE: usize = 2352;
+pub(crate) const SECTOR_SYNC_PATTERN: [u8; 12] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+pub(crate) const SECTOR_HEADER_OFFSET: usize = 12;
+pub(crate) const SECTOR_MODE_OFFSET: usize = 15;
+pub(crate) const SECTOR_SUBHEADER_OFFSET: usize = 16;
+pub(crate) const SECTOR_MODE1_DATA_OFFSET: usize = 16;
+pub(crate) const SECTOR_MODE2_DATA_OFFSET: usize = 24;
+pub(crate) const SECTORS_PER_SECOND: usize = 75;
+pub(crate) const SECTORS_PER_MINUTE: usize = 60 * SECTORS_PER_SECOND;
+pub(crate) const SECTOR_DATA_SIZE: usize = 0x800;
+pub(crate) const SECTOR_WHOLE_SIZE: usize = 0x924;
+
+/// CD-DA sectors hold 588 stereo frames of 16-bit little endian samples (44.1 kHz).
+pub(crate) const AUDIO_FRAMES_PER_SECTOR: usize = SECTOR_RAW_SIZE / 4;
+/// Sectors skipped after each sector played while fast forwarding / rewinding.
+pub(crate) const PLAY_SCAN_SKIP_SECTORS: usize = 8;
+/// Report mode sends a position report every 10 sectors, alternating between absolute and relative positions.
+pub(crate) const REPORT_INTERVAL_SECTORS: usize = 10;
+
+/// Subheader submode bits identifying real-time XA-ADPCM sectors.
+pub(crate) const SUBMODE_AUDIO: Bitfield = Bitfield::new(2, 1);
+pub(crate) const SUBMODE_FORM2: Bitfield = Bitfield::new(5, 1);
+pub(crate) const SUBMODE_REAL_TIME: Bitfield = Bitfield::new(6, 1);
+
+pub(crate) const STAT_ERROR: Bitfield = Bitfield::new(0, 1);
+pub(crate) const STAT_ID_ERROR: Bitfield = Bitfield::new(3, 1);
+/// Error codes sent after the stat byte in INT5 responses.
+pub(crate) const ERROR_INVALID_PARAMETER: u8 = 0x10;
+pub(crate) const ERROR_INVALID_COMMAND: u8 = 0x40;
+pub(crate) const ERROR_DOOR_OPENED: u8 = 0x08;
+pub(crate) const ERROR_NOT_READY: u8 = 0x80;
 
 pub(crate) const COMMAND_FIRST_DEFAULT_WAIT_CYCLES: usize = 0xC4E1;
+pub(crate) const COMMAND_FIRST_MOTOR_OFF_WAIT_CYCLES: usize = 0x5CF4;
 pub(crate) const COMMAND_FIRST_INIT_WAIT_CYCLES: usize = 0x13CCE;
 pub(crate) const COMMAND_SECOND_DEFAULT_WAIT_CYCLES: usize = 0x20000;
+pub(crate) const COMMAND_SECOND_GET_ID_WAIT_CYCLES: usize = 0x4A00;
+/// Pause / Stop second response times at single / double speed, and when already paused / stopped.
+pub(crate) const COMMAND_SECOND_PAUSE_WAIT_CYCLES: [usize; 2] = [0x21181C, 0x10BD93];
+pub(crate) const COMMAND_SECOND_PAUSE_IDLE_WAIT_CYCLES: usize = 0x1DF2;
+pub(crate) const COMMAND_SECOND_STOP_WAIT_CYCLES: [usize; 2] = [0xD38ACA, 0x18A6076];
+pub(crate) const COMMAND_SECOND_STOP_IDLE_WAIT_CYCLES: usize = 0x1D7B;
+/// ReadTOC takes about a second to complete.
+pub(crate) const COMMAND_SECOND_READ_TOC_WAIT_CYCLES: usize = CLOCK_SPEED as usize;
+
+pub(crate) const XA_DATA_SIZE: usize = 2324;
+pub(crate) const XA_OUTPUT_SAMPLE_RATE: usize = 44100;
+pub(crate) const XA_SOUND_GROUP_COUNT: usize = 18;
+pub(crate) const XA_SOUND_GROUP_SIZE: usize = 128;
+pub(crate) const XA_SAMPLES_PER_UNIT: usize = 28;
+/// ADPCM filter coefficients (/64), indexed by the filter number in the sound unit header.
+pub(crate) const XA_FILTER_POSITIVE: [i32; 4] = [0, 60, 115, 98];
+pub(crate) const XA_FILTER_NEGATIVE: [i32; 4] = [0, 0, -52, -55];
+pub(crate) const XA_CODING_STEREO: Bitfield = Bitfield::new(0, 2);
+pub(crate) const XA_CODING_SAMPLE_RATE: Bitfield = Bitfield::new(2, 2);
+pub(crate) const XA_CODING_BITS_PER_SAMPLE: Bitfield = Bitfield::new(4, 2);
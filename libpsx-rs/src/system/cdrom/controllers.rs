@@ -1,10 +1,17 @@
+pub(crate) mod audio;
 pub(crate) mod backend_dispatch;
 pub(crate) mod command;
 pub(crate) mod command_impl;
+pub(crate) mod disc_id;
 pub(crate) mod interrupt;
+pub(crate) mod play;
 pub(crate) mod read;
 pub(crate) mod register;
 pub(crate) mod state;
+pub(crate) mod subchannel;
+pub(crate) mod timing;
+pub(crate) mod tray;
+pub(crate) mod xa_adpcm;
 
 use crate::{
     backends::cdrom::CdromBackend,
@@ -13,6 +20,7 @@ use crate::{
             constants::*,
             controllers::{
                 command::*,
+                play::*,
                 read::*,
                 register::{
                     handle_audio_apply,
@@ -20,6 +28,7 @@ use crate::{
                     handle_interrupt_flag,
                     handle_request,
                 },
+                tray::*,
             },
             types::ControllerState,
         },
@@ -55,11 +64,16 @@ fn tick(state: &State, controller_state: &mut ControllerState, cdrom_backend: &C
     handle_request(state, controller_state)?;
     handle_interrupt_flag(state, controller_state)?;
     handle_audio_apply(state, controller_state)?;
+    handle_tray(state, controller_state)?;
 
     if controller_state.interrupt_index == 0 {
         handle_read(state, controller_state, cdrom_backend)?;
     }
 
+    if controller_state.interrupt_index == 0 {
+        handle_play(state, controller_state, cdrom_backend)?;
+    }
+
     if controller_state.interrupt_index == 0 {
         handle_command(state, controller_state, cdrom_backend)?;
     }
@@ -0,0 +1,39 @@
+use crate::{
+    system::{
+        cdrom::{
+            constants::*,
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    types::stereo::Stereo,
+};
+
+/// Sends CD audio to the SPU through the volume matrix, unless muted.
+pub(crate) fn push_audio(state: &State, controller_state: &ControllerState, frames: &[Stereo]) -> ControllerResult<()> {
+    if controller_state.muted {
+        return Ok(());
+    }
+
+    let volume = controller_state.audio_volume;
+
+    for frame in frames.iter() {
+        let left = ((frame.left as i32 * volume.left_to_left as i32) + (frame.right as i32 * volume.right_to_left as i32)) / AUDIO_VOLUME_UNITY;
+        let right = ((frame.left as i32 * volume.left_to_right as i32) + (frame.right as i32 * volume.right_to_right as i32)) / AUDIO_VOLUME_UNITY;
+        let frame = Stereo::new(clamp_sample(left), clamp_sample(right));
+
+        if state.cdrom.audio.write_one(frame).is_err() {
+            log::warn!("CD audio FIFO full, dropping {} samples", frames.len());
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn clamp_sample(value: i32) -> i16 {
+    value.max(std::i16::MIN as i32).min(std::i16::MAX as i32) as i16
+}
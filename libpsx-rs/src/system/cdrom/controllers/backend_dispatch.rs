@@ -1,18 +1,26 @@
 #![allow(unused_variables)]
 
+mod image;
 #[cfg(libcdio)]
 mod libcdio;
 #[cfg(libmirage)]
 mod libmirage;
 
 use crate::{
-    backends::cdrom::CdromBackend,
-    system::types::ControllerResult,
+    backends::cdrom::{
+        image::SUBCHANNEL_Q_SIZE,
+        CdromBackend,
+    },
+    system::{
+        cdrom::types::TocTrack,
+        types::ControllerResult,
+    },
 };
 
 pub(crate) fn disc_loaded(cdrom_backend: &CdromBackend) -> ControllerResult<Result<bool, ()>> {
     match cdrom_backend {
         CdromBackend::None => Ok(Err(())),
+        CdromBackend::Image(ref params) => Ok(Ok(image::disc_loaded(params)?)),
         #[cfg(libmirage)]
         CdromBackend::Libmirage(ref params) => Ok(Ok(libmirage::disc_loaded(params)?)),
         #[cfg(libcdio)]
@@ -24,6 +32,7 @@ pub(crate) fn disc_loaded(cdrom_backend: &CdromBackend) -> ControllerResult<Resu
 pub(crate) fn disc_mode(cdrom_backend: &CdromBackend) -> ControllerResult<Result<usize, ()>> {
     match cdrom_backend {
         CdromBackend::None => Ok(Err(())),
+        CdromBackend::Image(ref params) => Ok(Ok(image::disc_mode(params)?)),
         #[cfg(libmirage)]
         CdromBackend::Libmirage(ref params) => Ok(Ok(libmirage::disc_mode(params)?)),
         #[cfg(libcdio)]
@@ -35,6 +44,7 @@ pub(crate) fn disc_mode(cdrom_backend: &CdromBackend) -> ControllerResult<Result
 pub(crate) fn read_sector(cdrom_backend: &CdromBackend, msf_address_base: (u8, u8, u8), msf_address_offset: usize) -> ControllerResult<Result<Vec<u8>, ()>> {
     match cdrom_backend {
         CdromBackend::None => Ok(Err(())),
+        CdromBackend::Image(ref params) => Ok(Ok(image::read_sector(params, msf_address_base, msf_address_offset)?)),
         #[cfg(libmirage)]
         CdromBackend::Libmirage(ref params) => Ok(Ok(libmirage::read_sector(params, msf_address_base, msf_address_offset)?)),
         #[cfg(libcdio)]
@@ -42,3 +52,55 @@ pub(crate) fn read_sector(cdrom_backend: &CdromBackend, msf_address_base: (u8, u
         _ => unimplemented!(),
     }
 }
+
+pub(crate) fn toc(cdrom_backend: &CdromBackend) -> ControllerResult<Result<Vec<TocTrack>, ()>> {
+    match cdrom_backend {
+        CdromBackend::None => Ok(Err(())),
+        CdromBackend::Image(ref params) => Ok(Ok(image::toc(params)?)),
+        #[cfg(libmirage)]
+        CdromBackend::Libmirage(ref params) => Ok(Ok(libmirage::toc(params)?)),
+        #[cfg(libcdio)]
+        CdromBackend::Libcdio(ref params) => Ok(Ok(libcdio::toc(params)?)),
+        _ => unimplemented!(),
+    }
+}
+
+/// Subchannel Q data of a sector at an absolute address; generated from the TOC when the backend doesn't provide it.
+pub(crate) fn subchannel_q(cdrom_backend: &CdromBackend, address: usize) -> ControllerResult<Result<[u8; SUBCHANNEL_Q_SIZE], ()>> {
+    match cdrom_backend {
+        CdromBackend::None => Ok(Err(())),
+        CdromBackend::Image(ref params) => Ok(Ok(image::subchannel_q(params, address)?)),
+        #[cfg(libmirage)]
+        CdromBackend::Libmirage(ref params) => Ok(Ok(libmirage::subchannel_q(params, address)?)),
+        #[cfg(libcdio)]
+        CdromBackend::Libcdio(ref params) => Ok(Ok(libcdio::subchannel_q(params, address)?)),
+        _ => unimplemented!(),
+    }
+}
+
+/// First and last track numbers on the disc.
+pub(crate) fn track_range(cdrom_backend: &CdromBackend) -> ControllerResult<Result<(usize, usize), ()>> {
+    let toc = match toc(cdrom_backend)? {
+        Ok(toc) => toc,
+        Err(()) => return Ok(Err(())),
+    };
+
+    match (toc.first(), toc.last()) {
+        (Some(first), Some(last)) => Ok(Ok((first.number, last.number))),
+        _ => Err("Disc TOC has no tracks".into()),
+    }
+}
+
+/// Absolute sector address of the start (index 1) of a track, or of the lead-out for track 0; None if the track doesn't
+/// exist.
+pub(crate) fn track_start(cdrom_backend: &CdromBackend, track_number: usize) -> ControllerResult<Result<Option<usize>, ()>> {
+    let toc = match toc(cdrom_backend)? {
+        Ok(toc) => toc,
+        Err(()) => return Ok(Err(())),
+    };
+
+    match track_number {
+        0 => Ok(Ok(toc.last().map(|track| track.end))),
+        _ => Ok(Ok(toc.iter().find(|track| track.number == track_number).map(|track| track.start))),
+    }
+}
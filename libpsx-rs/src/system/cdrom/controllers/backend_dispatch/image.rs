@@ -0,0 +1,75 @@
+use crate::{
+    backends::cdrom::image::*,
+    system::{
+        cdrom::{
+            controllers::subchannel::generate_subchannel_q,
+            types::TocTrack,
+        },
+        types::ControllerResult,
+    },
+    utilities::bcd_to_binary,
+};
+
+pub(crate) fn disc_loaded(backend_params: &BackendParams) -> ControllerResult<bool> {
+    Ok(backend_params.image.lock().is_some())
+}
+
+pub(crate) fn disc_mode(backend_params: &BackendParams) -> ControllerResult<usize> {
+    let image = backend_params.image.lock();
+    let image = image.as_ref().ok_or_else(|| "No disc loaded".to_owned())?;
+
+    // Mode of the first track.
+    match image.tracks().first().map(|track| track.kind) {
+        Some(TrackKind::Mode1) => Ok(1),
+        Some(TrackKind::Mode2) => Ok(2),
+        Some(kind) => Err(format!("Disc mode not implemented: {:?}", kind)),
+        None => Err("No track on disc".into()),
+    }
+}
+
+pub(crate) fn read_sector(backend_params: &BackendParams, msf_address_base: (u8, u8, u8), msf_address_offset: usize) -> ControllerResult<Vec<u8>> {
+    let mut image = backend_params.image.lock();
+    let image = image.as_mut().ok_or_else(|| "No disc loaded".to_owned())?;
+
+    let minute = bcd_to_binary(msf_address_base.0) as usize;
+    let second = bcd_to_binary(msf_address_base.1) as usize;
+    let frame = bcd_to_binary(msf_address_base.2) as usize;
+    let address = (minute * SECTORS_PER_MINUTE) + (second * SECTORS_PER_SECOND) + frame + msf_address_offset;
+
+    image.read_raw_sector(address)
+}
+
+pub(crate) fn toc(backend_params: &BackendParams) -> ControllerResult<Vec<TocTrack>> {
+    let image = backend_params.image.lock();
+    let image = image.as_ref().ok_or_else(|| "No disc loaded".to_owned())?;
+
+    Ok(image
+        .tracks()
+        .iter()
+        .map(|track| {
+            TocTrack {
+                number: track.number,
+                audio: track.kind == TrackKind::Audio,
+                pregap_start: track.pregap_start,
+                start: track.start,
+                end: track.end,
+            }
+        })
+        .collect())
+}
+
+/// Stored Q data if the image has it (otherwise generated from the TOC), with the SBI / LSD patches applied.
+pub(crate) fn subchannel_q(backend_params: &BackendParams, address: usize) -> ControllerResult<[u8; SUBCHANNEL_Q_SIZE]> {
+    let stored_q = {
+        let mut image = backend_params.image.lock();
+        let image = image.as_mut().ok_or_else(|| "No disc loaded".to_owned())?;
+        image.read_subchannel_q(address)?
+    };
+
+    let q = match stored_q {
+        Some(q) => q,
+        None => generate_subchannel_q(&toc(backend_params)?, address),
+    };
+
+    Ok(backend_params.subchannel_patches.lock().apply(address, q))
+}
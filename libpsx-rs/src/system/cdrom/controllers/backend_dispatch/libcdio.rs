@@ -1,8 +1,27 @@
 #![allow(non_upper_case_globals)]
 
 use crate::{
-    backends::cdrom::libcdio::*,
-    system::types::ControllerResult,
+    backends::cdrom::{
+        image::{
+            ecc::{
+                ecc_generate,
+                edc_compute,
+            },
+            generate_empty_sector,
+            TrackKind,
+            LEAD_IN_SECTORS,
+            SECTOR_SIZE,
+            SUBCHANNEL_Q_SIZE,
+        },
+        libcdio::*,
+    },
+    system::{
+        cdrom::{
+            controllers::subchannel::generate_subchannel_q,
+            types::TocTrack,
+        },
+        types::ControllerResult,
+    },
 };
 use libcdio_sys::*;
 
@@ -45,14 +64,87 @@ pub(crate) fn read_sector(backend_params: &BackendParams, msf_address_base: (u8,
         let mut lsn = cdio_msf_to_lsn(&msf);
         lsn += msf_address_offset as i32;
 
-        let read_mode = cdio_read_mode_t_CDIO_READ_MODE_M2F1;
-        let mut buffer = vec![0; 2048];
+        // Audio reads return the raw sector, so are only used for audio tracks; data sectors are read without the sync
+        // and header (or as cooked user data for mode 1), which are then regenerated.
+        let address = (lsn + LEAD_IN_SECTORS as lsn_t) as usize;
+        let track = cdio_get_track(DISC, lsn);
+        if track == CDIO_INVALID_TRACK as track_t {
+            return Err(format!("Sector {} is outside of the disc", address));
+        }
+
+        let (result, buffer) = match cdio_get_track_format(DISC, track) {
+            track_format_t_TRACK_FORMAT_AUDIO => {
+                let mut buffer = vec![0; SECTOR_SIZE];
+                let result = cdio_read_audio_sector(DISC, buffer.as_mut_ptr() as *mut std::ffi::c_void, lsn);
+                (result, buffer)
+            },
+            track_format_t_TRACK_FORMAT_DATA => {
+                let mut buffer = generate_empty_sector(address, TrackKind::Mode1);
+                let result = cdio_read_mode1_sector(DISC, buffer[0x10..].as_mut_ptr() as *mut std::ffi::c_void, lsn, false);
+                let edc = edc_compute(&buffer[0..0x810]);
+                buffer[0x810..0x814].copy_from_slice(&edc.to_le_bytes());
+                ecc_generate(&mut buffer);
+                (result, buffer)
+            },
+            _ => {
+                let mut buffer = generate_empty_sector(address, TrackKind::Mode2);
+                let result = cdio_read_mode2_sector(DISC, buffer[0x10..].as_mut_ptr() as *mut std::ffi::c_void, lsn, true);
+                (result, buffer)
+            },
+        };
 
-        let result = cdio_read_sector(DISC, buffer.as_mut_ptr() as *mut std::ffi::c_void, lsn, read_mode);
-        if result > 0 {
+        if result != 0 {
             return Err(format!("Error reading disc sector; return code: {}", result));
         }
 
         Ok(buffer)
     }
 }
+
+pub(crate) fn toc(backend_params: &BackendParams) -> ControllerResult<Vec<TocTrack>> {
+    let (_context_guard, _context) = backend_params.context.guard();
+
+    unsafe {
+        if DISC.is_null() {
+            return Err("No disc loaded".into());
+        }
+
+        let first_track = cdio_get_first_track_num(DISC);
+        let track_count = cdio_get_num_tracks(DISC);
+        if (first_track == CDIO_INVALID_TRACK as track_t) || (track_count == CDIO_INVALID_TRACK as track_t) {
+            return Err("Reading the disc TOC failed".into());
+        }
+
+        // LSNs start after the 2 second lead-in.
+        let to_address = |lsn: lsn_t| (lsn + 150) as usize;
+
+        let mut tracks = Vec::new();
+        for number in first_track..(first_track + track_count) {
+            let start = cdio_get_track_lsn(DISC, number);
+            let last = cdio_get_track_last_lsn(DISC, number);
+            if (start < 0) || (last < start) {
+                return Err(format!("Reading the TOC entry of track {} failed", number));
+            }
+
+            let pregap_start = match cdio_get_track_pregap_lsn(DISC, number) {
+                pregap_start if (pregap_start >= 0) && (pregap_start <= start) => pregap_start,
+                _ => start,
+            };
+
+            tracks.push(TocTrack {
+                number: number as usize,
+                audio: cdio_get_track_format(DISC, number) == track_format_t_TRACK_FORMAT_AUDIO,
+                pregap_start: to_address(pregap_start),
+                start: to_address(start),
+                end: to_address(last) + 1,
+            });
+        }
+
+        Ok(tracks)
+    }
+}
+
+/// The drive doesn't expose subchannel data through the backend; generated from the TOC instead.
+pub(crate) fn subchannel_q(backend_params: &BackendParams, address: usize) -> ControllerResult<[u8; SUBCHANNEL_Q_SIZE]> {
+    Ok(generate_subchannel_q(&toc(backend_params)?, address))
+}
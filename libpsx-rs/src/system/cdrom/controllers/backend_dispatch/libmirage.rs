@@ -1,8 +1,17 @@
 #![allow(non_upper_case_globals)]
 
 use crate::{
-    backends::cdrom::libmirage::*,
-    system::types::ControllerResult,
+    backends::cdrom::{
+        image::SUBCHANNEL_Q_SIZE,
+        libmirage::*,
+    },
+    system::{
+        cdrom::{
+            controllers::subchannel::generate_subchannel_q,
+            types::TocTrack,
+        },
+        types::ControllerResult,
+    },
 };
 use libmirage_sys::*;
 
@@ -61,16 +70,29 @@ pub(crate) fn read_sector(backend_params: &BackendParams, msf_address_base: (u8,
             return Err("Seeking sector from disc failed".into());
         }
 
-        let mut buffer_raw_ptr: *const guint8 = std::ptr::null_mut();
-        let mut buffer_raw_size: gint = 0;
-        let result = mirage_sector_get_data(sector, &mut buffer_raw_ptr as *mut *const guint8, &mut buffer_raw_size as *mut gint, &mut error as *mut *mut GError);
-        if result == 0 {
-            return Err("Reading sector from disc failed".into());
+        // Assemble the raw sector from its parts; parts not present in the sector type (e.g. audio) fail and are skipped.
+        type PartFn = unsafe extern "C" fn(*mut MirageSector, *mut *const guint8, *mut gint, *mut *mut GError) -> gboolean;
+        let parts: [PartFn; 5] = [mirage_sector_get_sync, mirage_sector_get_header, mirage_sector_get_subheader, mirage_sector_get_data, mirage_sector_get_edc_ecc];
+
+        let mut buffer = Vec::with_capacity(2352);
+        for part_fn in parts.iter() {
+            let mut buffer_raw_ptr: *const guint8 = std::ptr::null_mut();
+            let mut buffer_raw_size: gint = 0;
+            let mut part_error: *mut GError = std::ptr::null_mut();
+            let result = part_fn(sector, &mut buffer_raw_ptr as *mut *const guint8, &mut buffer_raw_size as *mut gint, &mut part_error as *mut *mut GError);
+            if result == 0 {
+                g_clear_error(&mut part_error as *mut *mut GError);
+                continue;
+            }
+
+            for offset in 0..(buffer_raw_size as usize) {
+                buffer.push(*buffer_raw_ptr.add(offset));
+            }
         }
 
-        let mut buffer = Vec::with_capacity(buffer_raw_size as usize);
-        for offset in 0..(buffer_raw_size as usize) {
-            buffer.push(*buffer_raw_ptr.add(offset));
+        if buffer.len() != 2352 {
+            g_clear_object((&mut sector as *mut *mut MirageSector) as *mut *mut GObject);
+            return Err(format!("Reading raw sector from disc failed (got {} bytes)", buffer.len()));
         }
 
         g_clear_object((&mut sector as *mut *mut MirageSector) as *mut *mut GObject);
@@ -79,3 +101,47 @@ pub(crate) fn read_sector(backend_params: &BackendParams, msf_address_base: (u8,
         Ok(buffer)
     }
 }
+
+pub(crate) fn toc(backend_params: &BackendParams) -> ControllerResult<Vec<TocTrack>> {
+    let (_context_guard, _context) = backend_params.context.guard();
+
+    unsafe {
+        if DISC.is_null() {
+            return Err("No disc loaded".into());
+        }
+
+        // Layout sector addresses are LBAs, starting at -150 for the track 1 pregap.
+        let to_address = |lba: gint| (lba + 150) as usize;
+
+        let mut tracks = Vec::new();
+        for index in 0..mirage_disc_get_number_of_tracks(DISC) {
+            let mut error: *mut GError = std::ptr::null_mut();
+            let mut track = mirage_disc_get_track_by_index(DISC, index, &mut error as *mut *mut GError);
+            if track.is_null() {
+                g_clear_error(&mut error as *mut *mut GError);
+                return Err(format!("Reading the TOC entry of track index {} failed", index));
+            }
+
+            let pregap_start = mirage_track_layout_get_start_sector(track);
+            let start = pregap_start + mirage_track_get_track_start(track);
+            let end = pregap_start + mirage_track_layout_get_length(track);
+
+            tracks.push(TocTrack {
+                number: mirage_track_layout_get_track_number(track) as usize,
+                audio: mirage_track_get_sector_type(track) == _MirageSectorType_MIRAGE_SECTOR_AUDIO,
+                pregap_start: to_address(pregap_start),
+                start: to_address(start),
+                end: to_address(end),
+            });
+
+            g_clear_object((&mut track as *mut *mut MirageTrack) as *mut *mut GObject);
+        }
+
+        Ok(tracks)
+    }
+}
+
+/// The drive doesn't expose subchannel data through the backend; generated from the TOC instead.
+pub(crate) fn subchannel_q(backend_params: &BackendParams, address: usize) -> ControllerResult<[u8; SUBCHANNEL_Q_SIZE]> {
+    Ok(generate_subchannel_q(&toc(backend_params)?, address))
+}
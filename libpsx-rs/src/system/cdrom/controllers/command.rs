@@ -2,7 +2,12 @@ use crate::{
     backends::cdrom::CdromBackend,
     system::{
         cdrom::{
-            controllers::command_impl,
+            constants::*,
+            controllers::{
+                command_impl,
+                interrupt::handle_error_response,
+                timing,
+            },
             types::*,
         },
         types::{
@@ -23,7 +28,7 @@ pub(crate) fn handle_command(state: &State, controller_state: &mut ControllerSta
 
     match controller_state.command_wait_cycles {
         WaitCyclesMode::Ready => {
-            let cycles = (handler.0)(command_iteration)?;
+            let cycles = (handler.0)(controller_state, command_iteration)?;
             controller_state.command_wait_cycles = WaitCyclesMode::Waiting(cycles);
             return Ok(());
         },
@@ -47,7 +52,14 @@ pub(crate) fn handle_command(state: &State, controller_state: &mut ControllerSta
         return Err("CDROM response FIFO still had bytes when a new command was run!".into());
     }
 
-    let finished = (handler.2)(state, controller_state, cdrom_backend, command_iteration)?;
+    let finished = if (command_iteration == 0) && (controller_state.tray_state != TrayState::Closed) && command_requires_disc(command_index) {
+        // The disc can't be accessed while the lid is open, or until the TOC has been read after closing it.
+        state.cdrom.parameter.clear();
+        handle_error_response(state, controller_state, ERROR_NOT_READY)?;
+        true
+    } else {
+        (handler.2)(state, controller_state, cdrom_backend, command_iteration)?
+    };
 
     if finished {
         controller_state.command_index = None;
@@ -65,19 +77,43 @@ pub(crate) fn handle_command(state: &State, controller_state: &mut ControllerSta
     Ok(())
 }
 
+/// Commands that access the disc, which fail with the lid open.
+pub(crate) fn command_requires_disc(command_index: u8) -> bool {
+    match command_index {
+        0x03 | 0x04 | 0x05 | 0x06 | 0x07 | 0x10 | 0x11 | 0x12 | 0x13 | 0x14 | 0x15 | 0x16 | 0x1A | 0x1B | 0x1D | 0x1E => true,
+        _ => false,
+    }
+}
+
 fn get_handler_fn(command_index: u8) -> ControllerResult<(WaitCyclesFn, LengthFn, HandlerFn)> {
     match command_index {
-        0x01 => Ok((command_impl::default_wait_cycles, command_impl::command_01_length, command_impl::command_01_handler)),
-        0x02 => Ok((command_impl::default_wait_cycles, command_impl::command_02_length, command_impl::command_02_handler)),
-        0x06 => Ok((command_impl::default_wait_cycles, command_impl::command_06_length, command_impl::command_06_handler)),
-        0x08 => Ok((command_impl::default_wait_cycles, command_impl::command_08_length, command_impl::command_08_handler)),
-        0x09 => Ok((command_impl::default_wait_cycles, command_impl::command_09_length, command_impl::command_09_handler)),
-        0x0A => Ok((command_impl::command_0a_wait_cycles, command_impl::command_0a_length, command_impl::command_0a_handler)),
-        0x0C => Ok((command_impl::default_wait_cycles, command_impl::command_0c_length, command_impl::command_0c_handler)),
-        0x0E => Ok((command_impl::default_wait_cycles, command_impl::command_0e_length, command_impl::command_0e_handler)),
-        0x15 => Ok((command_impl::default_wait_cycles, command_impl::command_15_length, command_impl::command_15_handler)),
-        0x19 => Ok((command_impl::default_wait_cycles, command_impl::command_19_length, command_impl::command_19_handler)),
-        0x1A => Ok((command_impl::default_wait_cycles, command_impl::command_1a_length, command_impl::command_1a_handler)),
-        _ => Err(format!("Command not implemented: 0x{:0X}", command_index)),
+        0x01 => Ok((timing::default_wait_cycles, command_impl::command_01_length, command_impl::command_01_handler)),
+        0x02 => Ok((timing::default_wait_cycles, command_impl::command_02_length, command_impl::command_02_handler)),
+        0x03 => Ok((timing::default_wait_cycles, command_impl::command_03_length, command_impl::command_03_handler)),
+        0x04 => Ok((timing::default_wait_cycles, command_impl::command_04_length, command_impl::command_04_handler)),
+        0x05 => Ok((timing::default_wait_cycles, command_impl::command_05_length, command_impl::command_05_handler)),
+        0x06 => Ok((timing::default_wait_cycles, command_impl::command_06_length, command_impl::command_06_handler)),
+        0x07 => Ok((timing::motor_on_wait_cycles, command_impl::command_07_length, command_impl::command_07_handler)),
+        0x08 => Ok((timing::stop_wait_cycles, command_impl::command_08_length, command_impl::command_08_handler)),
+        0x09 => Ok((timing::pause_wait_cycles, command_impl::command_09_length, command_impl::command_09_handler)),
+        0x0A => Ok((timing::init_wait_cycles, command_impl::command_0a_length, command_impl::command_0a_handler)),
+        0x0B => Ok((timing::default_wait_cycles, command_impl::command_0b_length, command_impl::command_0b_handler)),
+        0x0C => Ok((timing::default_wait_cycles, command_impl::command_0c_length, command_impl::command_0c_handler)),
+        0x0D => Ok((timing::default_wait_cycles, command_impl::command_0d_length, command_impl::command_0d_handler)),
+        0x0E => Ok((timing::default_wait_cycles, command_impl::command_0e_length, command_impl::command_0e_handler)),
+        0x10 => Ok((timing::default_wait_cycles, command_impl::command_10_length, command_impl::command_10_handler)),
+        0x11 => Ok((timing::default_wait_cycles, command_impl::command_11_length, command_impl::command_11_handler)),
+        0x12 => Ok((timing::default_wait_cycles, command_impl::command_12_length, command_impl::command_12_handler)),
+        0x13 => Ok((timing::default_wait_cycles, command_impl::command_13_length, command_impl::command_13_handler)),
+        0x14 => Ok((timing::default_wait_cycles, command_impl::command_14_length, command_impl::command_14_handler)),
+        0x15 => Ok((timing::seek_wait_cycles, command_impl::command_15_length, command_impl::command_15_handler)),
+        0x16 => Ok((timing::seek_wait_cycles, command_impl::command_16_length, command_impl::command_16_handler)),
+        0x19 => Ok((timing::default_wait_cycles, command_impl::command_19_length, command_impl::command_19_handler)),
+        0x1A => Ok((timing::get_id_wait_cycles, command_impl::command_1a_length, command_impl::command_1a_handler)),
+        0x1B => Ok((timing::default_wait_cycles, command_impl::command_1b_length, command_impl::command_1b_handler)),
+        0x1C => Ok((timing::default_wait_cycles, command_impl::command_1c_length, command_impl::command_1c_handler)),
+        0x1D => Ok((timing::default_wait_cycles, command_impl::command_1d_length, command_impl::command_1d_handler)),
+        0x1E => Ok((timing::read_toc_wait_cycles, command_impl::command_1e_length, command_impl::command_1e_handler)),
+        _ => Ok((timing::default_wait_cycles, command_impl::command_invalid_length, command_impl::command_invalid_handler)),
     }
 }
@@ -5,27 +5,32 @@ use crate::{
             constants::*,
             controllers::{
                 backend_dispatch,
+                disc_id::*,
                 interrupt::*,
+                play::*,
                 state::*,
+                subchannel::*,
+                timing::*,
+            },
+            types::{
+                ControllerState,
+                PlayScan,
+                TrayState,
+                XaAdpcmState,
+                XaResamplerState,
             },
-            types::ControllerState,
         },
         types::{
             ControllerResult,
             State,
         },
     },
-    types::bitfield::Bitfield,
+    utilities::{
+        bcd_to_binary,
+        binary_to_bcd,
+    },
 };
 
-pub(crate) fn default_wait_cycles(command_iteration: usize) -> ControllerResult<usize> {
-    match command_iteration {
-        0 => Ok(COMMAND_FIRST_DEFAULT_WAIT_CYCLES),
-        1 => Ok(COMMAND_SECOND_DEFAULT_WAIT_CYCLES),
-        _ => Err(format!("Default wait cycles for command iteration {} not implemented", command_iteration)),
-    }
-}
-
 pub(crate) fn command_01_length(_command_iteration: usize) -> usize {
     0
 }
@@ -38,6 +43,12 @@ pub(crate) fn command_01_handler(state: &State, controller_state: &mut Controlle
 
     let stat_value = calculate_stat_value(controller_state);
     state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+
+    // The shell open bit stays set until it has been read with the lid closed.
+    if controller_state.tray_state != TrayState::Open {
+        controller_state.shell_open = false;
+    }
+
     handle_irq_raise(state, controller_state, 3)?;
     Ok(true)
 }
@@ -70,6 +81,74 @@ pub(crate) fn command_02_handler(state: &State, controller_state: &mut Controlle
     Ok(true)
 }
 
+pub(crate) fn command_03_length(_command_iteration: usize) -> usize {
+    // The track parameter is optional.
+    0
+}
+
+pub(crate) fn command_03_handler(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // Play
+    if command_iteration > 0 {
+        return Err("Play: command iteration was above 0".into());
+    }
+
+    let track_number = match state.cdrom.parameter.read_available() {
+        0 => 0,
+        _ => bcd_to_binary(state.cdrom.parameter.read_one().unwrap()) as usize,
+    };
+
+    start_play(controller_state, cdrom_backend, track_number)?;
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_04_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_04_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // Forward
+    if command_iteration > 0 {
+        return Err("Forward: command iteration was above 0".into());
+    }
+
+    if controller_state.playing {
+        controller_state.play_scan = PlayScan::Forward;
+    } else {
+        log::warn!("Forward: not playing, ignoring");
+    }
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_05_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_05_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // Backward
+    if command_iteration > 0 {
+        return Err("Backward: command iteration was above 0".into());
+    }
+
+    if controller_state.playing {
+        controller_state.play_scan = PlayScan::Backward;
+    } else {
+        log::warn!("Backward: not playing, ignoring");
+    }
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
 pub(crate) fn command_06_length(_command_iteration: usize) -> usize {
     0
 }
@@ -80,8 +159,9 @@ pub(crate) fn command_06_handler(state: &State, controller_state: &mut Controlle
         return Err("ReadN: command iteration was above 0".into());
     }
 
+    controller_state.playing = false;
     controller_state.reading = true;
-    controller_state.sector_delay_counter = SECTOR_DELAY_CYCLES_SINGLE_SPEED;
+    controller_state.sector_delay_counter = start_seek(controller_state) + sector_cycles(controller_state);
 
     let stat_value = calculate_stat_value(controller_state);
     state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
@@ -89,6 +169,27 @@ pub(crate) fn command_06_handler(state: &State, controller_state: &mut Controlle
     Ok(true)
 }
 
+pub(crate) fn command_07_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_07_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // MotorOn
+    let (finished, interrupt_index) = match command_iteration {
+        0 => (false, 3),
+        1 => {
+            controller_state.motor_on = true;
+            (true, 2)
+        },
+        _ => return Err(format!("MotorOn: command iteration invalid: {}", command_iteration)),
+    };
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, interrupt_index)?;
+    Ok(finished)
+}
+
 pub(crate) fn command_08_length(_command_iteration: usize) -> usize {
     0
 }
@@ -99,9 +200,13 @@ pub(crate) fn command_08_handler(state: &State, controller_state: &mut Controlle
         0 => {
             controller_state.reading = false;
             controller_state.seeking = false;
+            controller_state.playing = false;
             (false, 3)
         },
-        1 => (true, 2),
+        1 => {
+            controller_state.motor_on = false;
+            (true, 2)
+        },
         _ => return Err(format!("Stop: command iteration invalid: {}", command_iteration)),
     };
 
@@ -119,7 +224,9 @@ pub(crate) fn command_09_handler(state: &State, controller_state: &mut Controlle
     // Pause
     let (finished, interrupt_index) = match command_iteration {
         0 => {
+            controller_state.already_paused = !(controller_state.reading || controller_state.playing);
             controller_state.reading = false;
+            controller_state.playing = false;
             (false, 3)
         },
         1 => (true, 2),
@@ -132,14 +239,6 @@ pub(crate) fn command_09_handler(state: &State, controller_state: &mut Controlle
     Ok(finished)
 }
 
-pub(crate) fn command_0a_wait_cycles(command_iteration: usize) -> ControllerResult<usize> {
-    match command_iteration {
-        0 => Ok(COMMAND_FIRST_INIT_WAIT_CYCLES),
-        1 => Ok(COMMAND_SECOND_DEFAULT_WAIT_CYCLES),
-        _ => Err(format!("Init: Invalid command iteration {}", command_iteration)),
-    }
-}
-
 pub(crate) fn command_0a_length(_command_iteration: usize) -> usize {
     0
 }
@@ -150,9 +249,13 @@ pub(crate) fn command_0a_handler(state: &State, controller_state: &mut Controlle
         0 => {
             controller_state.reading = false;
             controller_state.seeking = false;
+            controller_state.playing = false;
             (false, 3)
         },
-        1 => (true, 2),
+        1 => {
+            controller_state.motor_on = true;
+            (true, 2)
+        },
         _ => return Err(format!("Init: command iteration invalid: {}", command_iteration)),
     };
 
@@ -162,6 +265,24 @@ pub(crate) fn command_0a_handler(state: &State, controller_state: &mut Controlle
     Ok(finished)
 }
 
+pub(crate) fn command_0b_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_0b_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // Mute
+    if command_iteration > 0 {
+        return Err("Mute: command iteration was above 0".into());
+    }
+
+    controller_state.muted = true;
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
 pub(crate) fn command_0c_length(_command_iteration: usize) -> usize {
     0
 }
@@ -172,7 +293,29 @@ pub(crate) fn command_0c_handler(state: &State, controller_state: &mut Controlle
         return Err("Demute: command iteration was above 0".into());
     }
 
-    log::warn!("Demute not implemented");
+    controller_state.muted = false;
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_0d_length(_command_iteration: usize) -> usize {
+    2
+}
+
+pub(crate) fn command_0d_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // SetFilter
+    if command_iteration > 0 {
+        return Err("SetFilter: command iteration was above 0".into());
+    }
+
+    controller_state.filter_file = state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?;
+    controller_state.filter_channel = state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?;
+
+    // A new stream is starting; don't carry over the previous decoder history.
+    controller_state.xa_adpcm_state = XaAdpcmState::new();
 
     let stat_value = calculate_stat_value(controller_state);
     state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
@@ -190,14 +333,161 @@ pub(crate) fn command_0e_handler(state: &State, controller_state: &mut Controlle
         return Err("Setmode: command iteration was above 0".into());
     }
 
-    let mode = state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?;
+    controller_state.mode = state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?;
 
-    if Bitfield::new(5, 1).extract_from(mode) > 0 {
-        return Err("Setmode: unhandled sector size (not data only)".into());
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_10_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_10_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // GetlocL
+    if command_iteration > 0 {
+        return Err("GetlocL: command iteration was above 0".into());
+    }
+
+    let header = match controller_state.last_sector_header {
+        Some(header) => header,
+        None => {
+            handle_error_response(state, controller_state, ERROR_NOT_READY)?;
+            return Ok(true);
+        },
+    };
+
+    for &data in header.iter() {
+        state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    }
+
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_11_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_11_handler(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // GetlocP
+    if command_iteration > 0 {
+        return Err("GetlocP: command iteration was above 0".into());
+    }
+
+    if !backend_dispatch::disc_loaded(cdrom_backend)?.unwrap_or(false) {
+        handle_error_response(state, controller_state, ERROR_NOT_READY)?;
+        return Ok(true);
+    }
+
+    // Track, index, relative MSF, absolute MSF.
+    let q = current_subchannel_q(controller_state, cdrom_backend)?;
+    let response = [q[1], q[2], q[3], q[4], q[5], q[7], q[8], q[9]];
+    for &data in response.iter() {
+        state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    }
+
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_12_length(command_iteration: usize) -> usize {
+    match command_iteration {
+        0 => 1,
+        _ => 0,
     }
+}
+
+pub(crate) fn command_12_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // SetSession
+    let (finished, interrupt_index) = match command_iteration {
+        0 => {
+            let session = bcd_to_binary(state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?);
+
+            // Multi-session discs aren't supported by the backends; only the first session exists.
+            if session != 1 {
+                log::warn!("SetSession: session {} not available", session);
+                handle_error_response(state, controller_state, ERROR_INVALID_PARAMETER)?;
+                return Ok(true);
+            }
+
+            controller_state.reading = false;
+            controller_state.playing = false;
+            (false, 3)
+        },
+        1 => (true, 2),
+        _ => return Err(format!("SetSession: command iteration invalid: {}", command_iteration)),
+    };
 
     let stat_value = calculate_stat_value(controller_state);
     state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, interrupt_index)?;
+    Ok(finished)
+}
+
+pub(crate) fn command_13_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_13_handler(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // GetTN
+    if command_iteration > 0 {
+        return Err("GetTN: command iteration was above 0".into());
+    }
+
+    let (first_track, last_track) = match backend_dispatch::track_range(cdrom_backend)? {
+        Ok(range) => range,
+        Err(()) => {
+            handle_error_response(state, controller_state, ERROR_NOT_READY)?;
+            return Ok(true);
+        },
+    };
+
+    let stat_value = calculate_stat_value(controller_state);
+    let response = [stat_value, binary_to_bcd(first_track as u8), binary_to_bcd(last_track as u8)];
+    for &data in response.iter() {
+        state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    }
+
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_14_length(_command_iteration: usize) -> usize {
+    1
+}
+
+pub(crate) fn command_14_handler(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // GetTD
+    if command_iteration > 0 {
+        return Err("GetTD: command iteration was above 0".into());
+    }
+
+    let track_number = bcd_to_binary(state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?) as usize;
+
+    let address = match backend_dispatch::track_start(cdrom_backend, track_number)? {
+        Ok(Some(address)) => address,
+        Ok(None) => {
+            handle_error_response(state, controller_state, ERROR_INVALID_PARAMETER)?;
+            return Ok(true);
+        },
+        Err(()) => {
+            handle_error_response(state, controller_state, ERROR_NOT_READY)?;
+            return Ok(true);
+        },
+    };
+
+    // Only the minute and second are returned.
+    let (minute, second, _) = address_to_msf(address);
+
+    let stat_value = calculate_stat_value(controller_state);
+    let response = [stat_value, minute, second];
+    for &data in response.iter() {
+        state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    }
+
     handle_irq_raise(state, controller_state, 3)?;
     Ok(true)
 }
@@ -210,11 +500,14 @@ pub(crate) fn command_15_handler(state: &State, controller_state: &mut Controlle
     // SeekL
     let (finished, interrupt_index) = match command_iteration {
         0 => {
+            controller_state.playing = false;
             controller_state.seeking = true;
             (false, 3)
         },
         1 => {
             controller_state.seeking = false;
+            controller_state.head_address = current_address(controller_state);
+            controller_state.motor_on = true;
             (true, 2)
         },
         _ => return Err(format!("SeekL: command iteration invalid: {}", command_iteration)),
@@ -226,6 +519,33 @@ pub(crate) fn command_15_handler(state: &State, controller_state: &mut Controlle
     Ok(finished)
 }
 
+pub(crate) fn command_16_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_16_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // SeekP
+    let (finished, interrupt_index) = match command_iteration {
+        0 => {
+            controller_state.playing = false;
+            controller_state.seeking = true;
+            (false, 3)
+        },
+        1 => {
+            controller_state.seeking = false;
+            controller_state.head_address = current_address(controller_state);
+            controller_state.motor_on = true;
+            (true, 2)
+        },
+        _ => return Err(format!("SeekP: command iteration invalid: {}", command_iteration)),
+    };
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, interrupt_index)?;
+    Ok(finished)
+}
+
 pub(crate) fn command_19_length(_command_iteration: usize) -> usize {
     1
 }
@@ -258,9 +578,6 @@ pub(crate) fn command_1a_length(_command_iteration: usize) -> usize {
 
 pub(crate) fn command_1a_handler(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
     // GetID
-    const NO_DISC_DATA: [u8; 8] = [0x08, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
-    const DISC_LOADED_DATA: [u8; 8] = [0x02, 0x00, 0x20, 0x00, 0x53, 0x43, 0x45, 0x41]; // SCEx: ASCII A = 0x41, E = 0x45, I = 0x49
-
     match command_iteration {
         0 => {
             let stat_value = calculate_stat_value(controller_state);
@@ -269,38 +586,172 @@ pub(crate) fn command_1a_handler(state: &State, controller_state: &mut Controlle
             Ok(false)
         },
         1 => {
-            let response = &state.cdrom.response;
+            let (interrupt_index, response) = disc_id_response(controller_state, cdrom_backend)?;
+            for &data in response.iter() {
+                state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+            }
+
+            handle_irq_raise(state, controller_state, interrupt_index)?;
+            Ok(true)
+        },
+        _ => return Err(format!("GetID: command iteration invalid: {}", command_iteration)),
+    }
+}
+
+pub(crate) fn command_1b_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_1b_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // ReadS
+    // Identical to ReadN, as read errors are never emulated.
+    if command_iteration > 0 {
+        return Err("ReadS: command iteration was above 0".into());
+    }
+
+    controller_state.playing = false;
+    controller_state.reading = true;
+    controller_state.sector_delay_counter = start_seek(controller_state) + sector_cycles(controller_state);
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_1c_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_1c_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // Reset
+    if command_iteration > 0 {
+        return Err("Reset: command iteration was above 0".into());
+    }
+
+    controller_state.reading = false;
+    controller_state.seeking = false;
+    controller_state.playing = false;
+    controller_state.play_scan = PlayScan::Normal;
+    controller_state.mode = 0;
+    controller_state.muted = false;
+    controller_state.filter_file = 0;
+    controller_state.filter_channel = 0;
+    controller_state.xa_adpcm_state = XaAdpcmState::new();
+    controller_state.xa_resampler_state = XaResamplerState::new();
+    controller_state.sector_buffer.clear();
+    controller_state.load_data_flag = false;
+    controller_state.loading_data = false;
 
-            let disc_loaded = match backend_dispatch::disc_loaded(cdrom_backend)? {
-                Ok(disc_loaded) => disc_loaded,
-                Err(()) => false,
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 3)?;
+    Ok(true)
+}
+
+pub(crate) fn command_1d_length(command_iteration: usize) -> usize {
+    match command_iteration {
+        0 => 2,
+        _ => 0,
+    }
+}
+
+pub(crate) fn command_1d_handler(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // GetQ
+    match command_iteration {
+        0 => {
+            let adr = state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?;
+            let point = state.cdrom.parameter.read_one().map_err(|_| "Couldn't read from the parameter FIFO".to_owned())?;
+
+            let toc = match backend_dispatch::toc(cdrom_backend)? {
+                Ok(toc) => toc,
+                Err(()) => {
+                    handle_error_response(state, controller_state, ERROR_NOT_READY)?;
+                    return Ok(true);
+                },
             };
 
-            let interrupt_index;
+            if adr != 1 {
+                log::warn!("GetQ: ADR {} not supported, using ADR 1", adr);
+            }
 
-            if disc_loaded {
-                let mode = backend_dispatch::disc_mode(cdrom_backend)?.unwrap();
-                match mode {
-                    2 => {
-                        for data in DISC_LOADED_DATA.iter() {
-                            response.write_one(*data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
-                        }
-                    },
-                    _ => return Err(format!("Disc mode {} not handled", mode)),
-                }
+            // Lead-in TOC entry: A0 / A1 hold the first / last track number as the minute, A2 the lead-out position.
+            let entry = match point {
+                0xA0 => toc.first().map(|track| (track.audio, (binary_to_bcd(track.number as u8), 0, 0))),
+                0xA1 => toc.last().map(|track| (track.audio, (binary_to_bcd(track.number as u8), 0, 0))),
+                0xA2 => toc.last().map(|track| (track.audio, address_to_msf(track.end))),
+                _ => toc.iter().find(|track| track.number == bcd_to_binary(point) as usize).map(|track| (track.audio, address_to_msf(track.start))),
+            };
 
-                interrupt_index = 2;
-            } else {
-                for data in NO_DISC_DATA.iter() {
-                    response.write_one(*data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
-                }
+            let (audio, (minute, second, frame)) = match entry {
+                Some(entry) => entry,
+                None => {
+                    handle_error_response(state, controller_state, ERROR_INVALID_PARAMETER)?;
+                    return Ok(true);
+                },
+            };
 
-                interrupt_index = 5;
+            let control = if audio {
+                0x00
+            } else {
+                0x40
             };
+            controller_state.get_q_response = Some([control | 0x01, 0x00, point, 0x00, 0x00, 0x00, 0x00, minute, second, frame]);
 
-            handle_irq_raise(state, controller_state, interrupt_index)?;
+            let stat_value = calculate_stat_value(controller_state);
+            state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+            handle_irq_raise(state, controller_state, 3)?;
+            Ok(false)
+        },
+        1 => {
+            let response = controller_state.get_q_response.take().ok_or_else(|| "GetQ: no response prepared".to_owned())?;
+            for &data in response.iter() {
+                state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+            }
+
+            handle_irq_raise(state, controller_state, 2)?;
             Ok(true)
         },
-        _ => return Err(format!("GetID: command iteration invalid: {}", command_iteration)),
+        _ => Err(format!("GetQ: command iteration invalid: {}", command_iteration)),
     }
 }
+
+pub(crate) fn command_1e_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_1e_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, command_iteration: usize) -> ControllerResult<bool> {
+    // ReadTOC
+    // The TOC is always available from the backend, so there is nothing to re-read.
+    let (finished, interrupt_index) = match command_iteration {
+        0 => {
+            controller_state.reading = false;
+            controller_state.playing = false;
+            (false, 3)
+        },
+        1 => {
+            controller_state.motor_on = true;
+            (true, 2)
+        },
+        _ => return Err(format!("ReadTOC: command iteration invalid: {}", command_iteration)),
+    };
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, interrupt_index)?;
+    Ok(finished)
+}
+
+pub(crate) fn command_invalid_length(_command_iteration: usize) -> usize {
+    0
+}
+
+pub(crate) fn command_invalid_handler(state: &State, controller_state: &mut ControllerState, _cdrom_backend: &CdromBackend, _command_iteration: usize) -> ControllerResult<bool> {
+    // Unknown / unsupported commands (such as the test mode and secret unlock commands) are rejected.
+    log::warn!("Invalid command: 0x{:0X}", controller_state.command_index.unwrap());
+
+    while state.cdrom.parameter.read_one().is_ok() {}
+
+    handle_error_response(state, controller_state, ERROR_INVALID_COMMAND)?;
+    Ok(true)
+}
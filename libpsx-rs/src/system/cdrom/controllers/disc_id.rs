@@ -0,0 +1,206 @@
+//! GetID: disc type and licence check.
+//! The SCEx licence string is recorded in the wobble of the lead-in, which images don't store; the region is derived
+//! from the licence text in sector 4 instead.
+
+use crate::{
+    backends::cdrom::CdromBackend,
+    system::{
+        cdrom::{
+            constants::*,
+            controllers::{
+                backend_dispatch,
+                state::*,
+            },
+            types::ControllerState,
+        },
+        types::ControllerResult,
+    },
+};
+
+/// 00:02:04 (sector 4).
+const LICENCE_SECTOR_MSF: (u8, u8, u8) = (0x00, 0x02, 0x04);
+const LICENCE_TEXT: &[u8] = b"Sony Computer Entertainment";
+
+const DISC_FLAG_UNLICENSED: u8 = 0x80;
+const DISC_FLAG_NO_DISC: u8 = 0x40;
+const DISC_FLAG_AUDIO: u8 = 0x10;
+const DISC_TYPE_MODE2: u8 = 0x20;
+
+/// Returns the interrupt index and the 8 byte response: stat, flags, disc type, 0, SCEx string.
+pub(crate) fn disc_id_response(controller_state: &ControllerState, cdrom_backend: &CdromBackend) -> ControllerResult<(usize, [u8; 8])> {
+    if !backend_dispatch::disc_loaded(cdrom_backend)?.unwrap_or(false) {
+        // The motor doesn't spin without a disc.
+        return Ok((5, [STAT_ID_ERROR.insert_into(0, 1), DISC_FLAG_NO_DISC, 0, 0, 0, 0, 0, 0]));
+    }
+
+    let toc = backend_dispatch::toc(cdrom_backend)?.map_err(|_| "No backend available for reading the TOC".to_owned())?;
+    if toc.first().map_or(true, |track| track.audio) {
+        // Audio discs are always reported as such, modchip or not; the BIOS then starts the CD player.
+        let stat_value = STAT_ID_ERROR.insert_into(calculate_stat_value(controller_state), 1);
+        return Ok((5, [stat_value, DISC_FLAG_UNLICENSED | DISC_FLAG_AUDIO, 0, 0, 0, 0, 0, 0]));
+    }
+
+    let has_audio = toc.iter().any(|track| track.audio);
+    let disc_type = match backend_dispatch::disc_mode(cdrom_backend)?.map_err(|_| "No backend available for the disc mode".to_owned())? {
+        2 => DISC_TYPE_MODE2,
+        _ => 0,
+    };
+    let licence_code = licence_region_code(cdrom_backend)?;
+
+    let licensed = licence_code == Some(controller_state.region_code);
+    if licensed || controller_state.modchip {
+        let stat_value = calculate_stat_value(controller_state);
+        return Ok((2, [stat_value, 0, disc_type, 0, b'S', b'C', b'E', controller_state.region_code]));
+    }
+
+    match licence_code {
+        Some(code) => log::warn!("GetID: disc is licensed for region SCE{}, the console is SCE{}", code as char, controller_state.region_code as char),
+        None => log::warn!("GetID: disc is unlicensed"),
+    }
+
+    let flags = if has_audio {
+        DISC_FLAG_UNLICENSED | DISC_FLAG_AUDIO
+    } else {
+        DISC_FLAG_UNLICENSED
+    };
+
+    let stat_value = STAT_ID_ERROR.insert_into(calculate_stat_value(controller_state), 1);
+    Ok((5, [stat_value, flags, disc_type, 0, 0, 0, 0, 0]))
+}
+
+/// Region letter (A, E or I) from the licence text ("Sony Computer Entertainment Amer  ica" etc.), or None if there
+/// isn't one.
+fn licence_region_code(cdrom_backend: &CdromBackend) -> ControllerResult<Option<u8>> {
+    let raw_sector = backend_dispatch::read_sector(cdrom_backend, LICENCE_SECTOR_MSF, 0)?.map_err(|_| "No backend available for reading sector".to_owned())?;
+    let data = &raw_sector[SECTOR_MODE2_DATA_OFFSET.min(raw_sector.len())..];
+
+    let position = match data.windows(LICENCE_TEXT.len()).position(|window| window == LICENCE_TEXT) {
+        Some(position) => position + LICENCE_TEXT.len(),
+        None => return Ok(None),
+    };
+
+    // The spacing within the region name varies between discs.
+    let region = data[position..].iter().filter(|c| !c.is_ascii_whitespace()).take(4).cloned().collect::<Vec<_>>();
+    match region.as_slice() {
+        b"Amer" => Ok(Some(b'A')),
+        b"Euro" => Ok(Some(b'E')),
+        [b'I', b'n', b'c', ..] => Ok(Some(b'I')),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backends::cdrom::image,
+        system::types::State,
+        CdromConfig,
+        ConsoleRegion,
+    };
+    use std::path::PathBuf;
+
+    const COOKED_SECTOR_SIZE: usize = 2048;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("psx-rs-test-{}-{}", std::process::id(), name))
+    }
+
+    /// Data disc (ISO) with the given licence text at the start of sector 4.
+    fn data_disc(name: &str, licence: &[u8]) -> CdromBackend<'static> {
+        let path = temp_path(&format!("{}.iso", name));
+        let mut data = vec![0; COOKED_SECTOR_SIZE * 16];
+        data[(COOKED_SECTOR_SIZE * 4)..((COOKED_SECTOR_SIZE * 4) + licence.len())].copy_from_slice(licence);
+        std::fs::write(&path, &data).unwrap();
+
+        let backend_params = image::BackendParams::new();
+        image::load_disc(&backend_params, &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        CdromBackend::Image(backend_params)
+    }
+
+    fn audio_disc() -> CdromBackend<'static> {
+        let bin_path = temp_path("audio.bin");
+        let cue_path = temp_path("audio.cue");
+        std::fs::write(&bin_path, vec![0; image::SECTOR_SIZE * 16]).unwrap();
+        std::fs::write(&cue_path, format!("FILE \"{}\" BINARY\n  TRACK 01 AUDIO\n    INDEX 01 00:00:00\n", bin_path.display())).unwrap();
+
+        let backend_params = image::BackendParams::new();
+        image::load_disc(&backend_params, &cue_path).unwrap();
+        std::fs::remove_file(&cue_path).unwrap();
+        std::fs::remove_file(&bin_path).unwrap();
+        CdromBackend::Image(backend_params)
+    }
+
+    fn controller_state(region: ConsoleRegion, modchip: bool) -> ControllerState {
+        let mut state = State::new();
+        State::setup_cdrom(
+            &mut state,
+            &CdromConfig {
+                fast_loading: false,
+                region,
+                modchip,
+            },
+        );
+        state.cdrom.controller_state.get_mut().clone()
+    }
+
+    #[test]
+    fn test_licence_region_code() {
+        let cases: [(&str, &[u8], Option<u8>); 4] = [
+            ("america", b"          Licensed  by          Sony Computer Entertainment Amer  ica ", Some(b'A')),
+            ("europe", b"          Licensed  by          Sony Computer Entertainment Euro pe   ", Some(b'E')),
+            ("japan", b"          Licensed  by          Sony Computer Entertainment Inc.", Some(b'I')),
+            ("none", b"Homebrew", None),
+        ];
+
+        for &(name, licence, code) in cases.iter() {
+            let cdrom_backend = data_disc(&format!("licence-{}", name), licence);
+            assert_eq!(licence_region_code(&cdrom_backend).unwrap(), code, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_disc_id_response() {
+        let regions = [
+            (ConsoleRegion::NtscU, b'A', &b"Sony Computer Entertainment Amer  ica"[..], &b"Sony Computer Entertainment Euro pe"[..]),
+            (ConsoleRegion::NtscJ, b'I', &b"Sony Computer Entertainment Inc."[..], &b"Sony Computer Entertainment Amer  ica"[..]),
+            (ConsoleRegion::Pal, b'E', &b"Sony Computer Entertainment Euro pe"[..], &b"Sony Computer Entertainment Inc."[..]),
+        ];
+
+        for (index, &(region, code, matching, mismatched)) in regions.iter().enumerate() {
+            let matching_disc = data_disc(&format!("matching-{}", index), matching);
+            let mismatched_disc = data_disc(&format!("mismatched-{}", index), mismatched);
+
+            for &modchip in [false, true].iter() {
+                let controller_state = controller_state(region, modchip);
+                let stat_value = calculate_stat_value(&controller_state);
+                let licensed = (2, [stat_value, 0, DISC_TYPE_MODE2, 0, b'S', b'C', b'E', code]);
+
+                assert_eq!(disc_id_response(&controller_state, &matching_disc).unwrap(), licensed, "{:?} modchip {}", region, modchip);
+
+                let expected = if modchip {
+                    licensed
+                } else {
+                    (5, [STAT_ID_ERROR.insert_into(stat_value, 1), DISC_FLAG_UNLICENSED, DISC_TYPE_MODE2, 0, 0, 0, 0, 0])
+                };
+                assert_eq!(disc_id_response(&controller_state, &mismatched_disc).unwrap(), expected, "{:?} modchip {}", region, modchip);
+            }
+        }
+    }
+
+    #[test]
+    fn test_disc_id_response_audio() {
+        let cdrom_backend = audio_disc();
+
+        for &region in [ConsoleRegion::NtscU, ConsoleRegion::NtscJ, ConsoleRegion::Pal].iter() {
+            for &modchip in [false, true].iter() {
+                let controller_state = controller_state(region, modchip);
+                let stat_value = STAT_ID_ERROR.insert_into(calculate_stat_value(&controller_state), 1);
+
+                let response = disc_id_response(&controller_state, &cdrom_backend).unwrap();
+                assert_eq!(response, (5, [stat_value, DISC_FLAG_UNLICENSED | DISC_FLAG_AUDIO, 0, 0, 0, 0, 0, 0]), "{:?} modchip {}", region, modchip);
+            }
+        }
+    }
+}
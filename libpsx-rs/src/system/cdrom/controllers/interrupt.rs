@@ -1,6 +1,7 @@
 use crate::system::{
     cdrom::{
         constants::*,
+        controllers::state::calculate_stat_value,
         types::*,
     },
     intc::types::Line,
@@ -33,3 +34,11 @@ pub(crate) fn handle_irq_raise(state: &State, controller_state: &mut ControllerS
 
     Ok(())
 }
+
+/// Responds with an error (INT5): the stat value with the error bit set, followed by the error code.
+pub(crate) fn handle_error_response(state: &State, controller_state: &mut ControllerState, error_code: u8) -> ControllerResult<()> {
+    let stat_value = STAT_ERROR.insert_into(calculate_stat_value(controller_state), 1);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    state.cdrom.response.write_one(error_code).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 5)
+}
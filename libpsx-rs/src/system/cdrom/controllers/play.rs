@@ -0,0 +1,145 @@
+use crate::{
+    backends::cdrom::CdromBackend,
+    system::{
+        cdrom::{
+            constants::*,
+            controllers::{
+                audio::push_audio,
+                backend_dispatch,
+                interrupt::*,
+                state::*,
+                subchannel::*,
+                timing::*,
+            },
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    types::stereo::Stereo,
+};
+
+pub(crate) fn handle_play(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend) -> ControllerResult<()> {
+    if !controller_state.playing {
+        return Ok(());
+    }
+
+    if controller_state.sector_delay_counter > 0 {
+        controller_state.sector_delay_counter -= 1;
+        return Ok(());
+    }
+
+    controller_state.sector_delay_counter = sector_cycles(controller_state);
+
+    let toc = backend_dispatch::toc(cdrom_backend)?.map_err(|_| "No backend available for reading the TOC".to_owned())?;
+    let address = current_address(controller_state);
+
+    let track = match find_toc_track(&toc, address) {
+        Some(track) => *track,
+        None => {
+            log::debug!("CD-DA playback reached the end of the disc");
+            return end_play(state, controller_state);
+        },
+    };
+
+    if track.number != controller_state.play_track {
+        if (controller_state.play_scan != PlayScan::Backward) && (MODE_AUTO_PAUSE.extract_from(controller_state.mode) > 0) {
+            return end_play(state, controller_state);
+        }
+
+        controller_state.play_track = track.number;
+    }
+
+    let raw_sector = backend_dispatch::read_sector(cdrom_backend, controller_state.msf_address_base, controller_state.msf_address_offset)?
+        .map_err(|_| "No backend available for reading sector".to_owned())?;
+    if raw_sector.len() != SECTOR_RAW_SIZE {
+        return Err(format!("Backend returned a sector of {} bytes (expected a raw sector)", raw_sector.len()));
+    }
+
+    let frames = raw_sector.chunks_exact(4).map(|frame| Stereo::new(i16::from_le_bytes([frame[0], frame[1]]), i16::from_le_bytes([frame[2], frame[3]]))).collect::<Vec<_>>();
+    assert_eq!(frames.len(), AUDIO_FRAMES_PER_SECTOR);
+    push_audio(state, controller_state, &frames)?;
+    update_subchannel_q(controller_state, cdrom_backend, address)?;
+
+    match controller_state.play_scan {
+        PlayScan::Normal => controller_state.msf_address_offset += 1,
+        PlayScan::Forward => set_address(controller_state, address + 1 + PLAY_SCAN_SKIP_SECTORS),
+        PlayScan::Backward => {
+            // Rewinding stops at the start of the first track, continuing playback from there.
+            let first_start = toc.first().map(|track| track.start).unwrap_or(0);
+            let next_address = address.saturating_sub(PLAY_SCAN_SKIP_SECTORS).max(first_start);
+            if next_address == first_start {
+                controller_state.play_scan = PlayScan::Normal;
+            }
+            set_address(controller_state, next_address);
+        },
+    }
+
+    controller_state.head_address = current_address(controller_state);
+
+    let frame = address % SECTORS_PER_SECOND;
+    if (MODE_REPORT.extract_from(controller_state.mode) > 0) && ((frame % REPORT_INTERVAL_SECTORS) == 0) {
+        let relative = (frame % (REPORT_INTERVAL_SECTORS * 2)) != 0;
+        send_report(state, controller_state, cdrom_backend, relative, &frames)?;
+    }
+
+    Ok(())
+}
+
+/// Starts playback at the current position, or at the start of a track (if not 0).
+pub(crate) fn start_play(controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, track_number: usize) -> ControllerResult<()> {
+    let toc = backend_dispatch::toc(cdrom_backend)?.map_err(|_| "No backend available for reading the TOC".to_owned())?;
+
+    if track_number > 0 {
+        match toc.iter().find(|track| track.number == track_number) {
+            Some(track) => set_address(controller_state, track.start),
+            None => log::warn!("Play: track {} not found, playing from the current position", track_number),
+        }
+    }
+
+    let address = current_address(controller_state);
+    controller_state.play_track = find_toc_track(&toc, address).map(|track| track.number).unwrap_or(0);
+    controller_state.playing = true;
+    controller_state.play_scan = PlayScan::Normal;
+    controller_state.reading = false;
+    controller_state.sector_delay_counter = start_seek(controller_state) + sector_cycles(controller_state);
+    Ok(())
+}
+
+pub(crate) fn find_toc_track(toc: &[TocTrack], address: usize) -> Option<&TocTrack> {
+    toc.iter().find(|track| (address >= track.pregap_start) && (address < track.end))
+}
+
+/// Stops playback at the end of a track (auto pause) or the disc, raising a data end interrupt.
+fn end_play(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    controller_state.playing = false;
+    controller_state.play_scan = PlayScan::Normal;
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 4)
+}
+
+/// Sends a report mode INT1 from the subchannel Q data: stat, track, index, position (relative positions have bit 7 of
+/// the seconds set), peak level.
+fn send_report(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, relative: bool, frames: &[Stereo]) -> ControllerResult<()> {
+    let q = current_subchannel_q(controller_state, cdrom_backend)?;
+    let (track_number, index) = (q[1], q[2]);
+    let (minute, second, frame) = if relative {
+        (q[3], q[4] | 0x80, q[5])
+    } else {
+        (q[7], q[8], q[9])
+    };
+
+    let peak = frames.iter().map(|frame| (frame.left as i32).abs().max((frame.right as i32).abs())).max().unwrap_or(0).min(std::i16::MAX as i32) as u16;
+
+    let stat_value = calculate_stat_value(controller_state);
+    let response = [stat_value, track_number, index, minute, second, frame, peak as u8, (peak >> 8) as u8];
+    for &data in response.iter() {
+        state.cdrom.response.write_one(data).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    }
+
+    handle_irq_raise(state, controller_state, 1)
+}
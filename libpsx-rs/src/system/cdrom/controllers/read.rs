@@ -6,7 +6,11 @@ use crate::{
             controllers::{
                 backend_dispatch,
                 interrupt::*,
+                play::find_toc_track,
                 state::*,
+                subchannel::*,
+                timing::*,
+                xa_adpcm::*,
             },
             types::ControllerState,
         },
@@ -47,16 +51,34 @@ pub(crate) fn handle_read(state: &State, controller_state: &mut ControllerState,
             return Ok(());
         }
 
-        read_sector(controller_state, cdrom_backend)?;
-        controller_state.sector_delay_counter = SECTOR_DELAY_CYCLES_SINGLE_SPEED;
-        let stat_value = calculate_stat_value(controller_state);
-        state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
-        handle_irq_raise(state, controller_state, 1)?;
+        let toc = backend_dispatch::toc(cdrom_backend)?.map_err(|_| "No backend available for reading the TOC".to_owned())?;
+        if find_toc_track(&toc, current_address(controller_state)).is_none() {
+            log::debug!("Read reached the end of the disc");
+            return end_read(state, controller_state);
+        }
+
+        let data_ready = read_sector(state, controller_state, cdrom_backend)?;
+        controller_state.sector_delay_counter = sector_cycles(controller_state);
+
+        if data_ready {
+            let stat_value = calculate_stat_value(controller_state);
+            state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+            handle_irq_raise(state, controller_state, 1)?;
+        }
     }
 
     Ok(())
 }
 
+/// Stops reading at the end of the disc, raising a data end interrupt.
+fn end_read(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    controller_state.reading = false;
+
+    let stat_value = calculate_stat_value(controller_state);
+    state.cdrom.response.write_one(stat_value).map_err(|_| "Couldn't write to the response FIFO".to_owned())?;
+    handle_irq_raise(state, controller_state, 4)
+}
+
 fn fill_data_fifo(state: &State, controller_state: &mut ControllerState) {
     loop {
         if state.cdrom.data.is_full() {
@@ -70,20 +92,59 @@ fn fill_data_fifo(state: &State, controller_state: &mut ControllerState) {
     }
 }
 
-fn read_sector(controller_state: &mut ControllerState, cdrom_backend: &CdromBackend) -> ControllerResult<()> {
+/// Reads the next sector; returns false if it was an XA-ADPCM sector consumed by the audio decoder, or an unreadable
+/// CD-DA sector.
+fn read_sector(state: &State, controller_state: &mut ControllerState, cdrom_backend: &CdromBackend) -> ControllerResult<bool> {
     assert_eq!(controller_state.sector_buffer.len(), 0);
     let msf_address_base = controller_state.msf_address_base;
     let msf_address_offset = controller_state.msf_address_offset;
-    let data_block = backend_dispatch::read_sector(cdrom_backend, msf_address_base, msf_address_offset)?.map_err(|_| "No backend available for reading sector".to_owned())?;
-    assert_eq!(data_block.len(), 2048);
+    let address = current_address(controller_state);
+    let raw_sector = backend_dispatch::read_sector(cdrom_backend, msf_address_base, msf_address_offset)?.map_err(|_| "No backend available for reading sector".to_owned())?;
+    if raw_sector.len() != SECTOR_RAW_SIZE {
+        return Err(format!("Backend returned a sector of {} bytes (expected a raw sector)", raw_sector.len()));
+    }
+
+    update_subchannel_q(controller_state, cdrom_backend, address)?;
     controller_state.msf_address_offset += 1;
-    controller_state.sector_buffer.extend(&data_block);
+    controller_state.head_address = current_address(controller_state);
+
+    if raw_sector[0..SECTOR_SYNC_PATTERN.len()] == SECTOR_SYNC_PATTERN {
+        let mut header = [0; 8];
+        header.copy_from_slice(&raw_sector[SECTOR_HEADER_OFFSET..(SECTOR_HEADER_OFFSET + 8)]);
+        controller_state.last_sector_header = Some(header);
+    }
+
+    if is_xa_audio_sector(controller_state, &raw_sector) {
+        handle_xa_sector(state, controller_state, &raw_sector)?;
+        return Ok(false);
+    }
+
+    let data_block = if raw_sector[0..SECTOR_SYNC_PATTERN.len()] != SECTOR_SYNC_PATTERN {
+        // CD-DA sector; only readable in CDDA mode, as the whole raw sector.
+        if MODE_CDDA.extract_from(controller_state.mode) == 0 {
+            log::warn!("Skipping CD-DA sector read without CDDA mode set");
+            return Ok(false);
+        }
+
+        &raw_sector[..]
+    } else if MODE_SECTOR_SIZE.extract_from(controller_state.mode) > 0 {
+        // Whole sector: everything except the sync pattern.
+        &raw_sector[SECTOR_HEADER_OFFSET..(SECTOR_HEADER_OFFSET + SECTOR_WHOLE_SIZE)]
+    } else {
+        let data_offset = match raw_sector[SECTOR_MODE_OFFSET] {
+            1 => SECTOR_MODE1_DATA_OFFSET,
+            _ => SECTOR_MODE2_DATA_OFFSET,
+        };
+        &raw_sector[data_offset..(data_offset + SECTOR_DATA_SIZE)]
+    };
+
+    controller_state.sector_buffer.extend(data_block);
 
     // log::debug!("Sector {:?} + offset {} read ok", msf_address_base, msf_address_offset);
 
     if false {
-        log::debug!("{}", &binary_to_ascii_escaped(&data_block));
+        log::debug!("{}", &binary_to_ascii_escaped(data_block));
     }
 
-    Ok(())
+    Ok(true)
 }
@@ -75,12 +75,22 @@ pub(crate) fn handle_interrupt_flag(state: &State, controller_state: &mut Contro
     })
 }
 
-pub(crate) fn handle_audio_apply(state: &State, _controller_state: &mut ControllerState) -> ControllerResult<()> {
+pub(crate) fn handle_audio_apply(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
     state.cdrom.audio_apply.acknowledge(|value, latch_kind| {
         match latch_kind {
             LatchKind::Read => Err("Tried to read from the audio apply register".into()),
             LatchKind::Write => {
-                log::warn!("Write to audio apply register unimplemented");
+                controller_state.adpcm_muted = AUDIO_APPLY_MUTE_ADPCM.extract_from(value) > 0;
+
+                if AUDIO_APPLY_CHANGES.extract_from(value) > 0 {
+                    controller_state.audio_volume = AudioVolume {
+                        left_to_left: state.cdrom.audio_left_to_left.read_u8(),
+                        left_to_right: state.cdrom.audio_left_to_right.read_u8(),
+                        right_to_left: state.cdrom.audio_right_to_left.read_u8(),
+                        right_to_right: state.cdrom.audio_right_to_right.read_u8(),
+                    };
+                }
+
                 Ok(value)
             },
         }
@@ -1,7 +1,14 @@
 use crate::{
-    system::cdrom::types::ControllerState,
+    system::cdrom::{
+        constants::*,
+        types::ControllerState,
+    },
     types::bitfield::Bitfield,
-    utilities::bool_to_flag,
+    utilities::{
+        bcd_to_binary,
+        binary_to_bcd,
+        bool_to_flag,
+    },
 };
 
 pub(crate) fn calculate_stat_value(state: &ControllerState) -> u8 {
@@ -9,18 +16,22 @@ pub(crate) fn calculate_stat_value(state: &ControllerState) -> u8 {
     const MOTOR_ON: Bitfield = Bitfield::new(1, 1);
     const _SEEK_ERROR: Bitfield = Bitfield::new(2, 1);
     const _ID_ERROR: Bitfield = Bitfield::new(3, 1);
-    const _SHELL_OPEN: Bitfield = Bitfield::new(4, 1);
+    const SHELL_OPEN: Bitfield = Bitfield::new(4, 1);
     const READ: Bitfield = Bitfield::new(5, 1);
     const SEEK: Bitfield = Bitfield::new(6, 1);
-    const _PLAY: Bitfield = Bitfield::new(7, 1);
+    const PLAY: Bitfield = Bitfield::new(7, 1);
     const READ_SEEK_PLAY: Bitfield = Bitfield::new(5, 3);
 
     let reading = state.reading;
     let seeking = state.seeking;
+    let playing = state.playing;
+    let motor_on = state.motor_on;
+    let shell_open = state.shell_open;
 
     let mut value = 0;
 
-    value = MOTOR_ON.insert_into(value, 1); // Motor always on.
+    value = MOTOR_ON.insert_into(value, bool_to_flag(motor_on) as u8);
+    value = SHELL_OPEN.insert_into(value, bool_to_flag(shell_open) as u8);
 
     // Only one of the three states below are allowed to be on, or none at all.
 
@@ -34,5 +45,31 @@ pub(crate) fn calculate_stat_value(state: &ControllerState) -> u8 {
         value = SEEK.insert_into(value, bool_to_flag(seeking) as u8);
     }
 
+    if playing {
+        assert!(READ_SEEK_PLAY.extract_from(value) == 0);
+        value = PLAY.insert_into(value, bool_to_flag(playing) as u8);
+    }
+
     value
 }
+
+/// Current absolute sector address (00:00:00 = 0).
+pub(crate) fn current_address(state: &ControllerState) -> usize {
+    let (minute, second, frame) = state.msf_address_base;
+    let base = (bcd_to_binary(minute) as usize * SECTORS_PER_MINUTE) + (bcd_to_binary(second) as usize * SECTORS_PER_SECOND) + bcd_to_binary(frame) as usize;
+    base + state.msf_address_offset
+}
+
+/// Moves the current position to an absolute sector address.
+pub(crate) fn set_address(state: &mut ControllerState, address: usize) {
+    state.msf_address_base = address_to_msf(address);
+    state.msf_address_offset = 0;
+}
+
+/// Converts an absolute sector address (or a length in sectors) to BCD minutes, seconds and frames.
+pub(crate) fn address_to_msf(address: usize) -> (u8, u8, u8) {
+    let minute = binary_to_bcd((address / SECTORS_PER_MINUTE) as u8);
+    let second = binary_to_bcd(((address / SECTORS_PER_SECOND) % 60) as u8);
+    let frame = binary_to_bcd((address % SECTORS_PER_SECOND) as u8);
+    (minute, second, frame)
+}
@@ -0,0 +1,148 @@
+//! Subchannel Q tracking; the drive only latches Q data with a valid CRC, which is what LibCrypt protection relies on.
+
+use crate::{
+    backends::cdrom::{
+        image::SUBCHANNEL_Q_SIZE,
+        CdromBackend,
+    },
+    system::{
+        cdrom::{
+            controllers::{
+                backend_dispatch,
+                play::find_toc_track,
+                state::*,
+            },
+            types::*,
+        },
+        types::ControllerResult,
+    },
+    utilities::binary_to_bcd,
+};
+
+/// Reads the Q data of a sector passing under the head, latching it if the CRC is valid.
+pub(crate) fn update_subchannel_q(controller_state: &mut ControllerState, cdrom_backend: &CdromBackend, address: usize) -> ControllerResult<()> {
+    let q = backend_dispatch::subchannel_q(cdrom_backend, address)?.map_err(|_| "No backend available for reading subchannel Q".to_owned())?;
+
+    if subchannel_q_crc_valid(&q) {
+        controller_state.last_subchannel_q = Some(q);
+    } else {
+        log::debug!("Subchannel Q CRC mismatch at sector {}, keeping the previous position", address);
+    }
+
+    Ok(())
+}
+
+/// Last valid Q data, or the Q data of the current position if no sector has been read since the last seek.
+pub(crate) fn current_subchannel_q(controller_state: &mut ControllerState, cdrom_backend: &CdromBackend) -> ControllerResult<[u8; SUBCHANNEL_Q_SIZE]> {
+    if controller_state.last_subchannel_q.is_none() {
+        let address = current_address(controller_state);
+        update_subchannel_q(controller_state, cdrom_backend, address)?;
+    }
+
+    Ok(controller_state.last_subchannel_q.unwrap_or([0; SUBCHANNEL_Q_SIZE]))
+}
+
+/// Generates the Q data (ADR 1, current position) of a sector from the TOC, for backends that don't provide it.
+/// The relative position counts down towards index 1 within the pregap.
+pub(crate) fn generate_subchannel_q(toc: &[TocTrack], address: usize) -> [u8; SUBCHANNEL_Q_SIZE] {
+    let mut q = [0; SUBCHANNEL_Q_SIZE];
+
+    let (audio, track_number, index, relative) = match find_toc_track(toc, address) {
+        Some(track) if address >= track.start => (track.audio, binary_to_bcd(track.number as u8), 1, address - track.start),
+        Some(track) => (track.audio, binary_to_bcd(track.number as u8), 0, track.start - address),
+        None => {
+            // Lead-out.
+            let (audio, lead_out) = toc.last().map(|track| (track.audio, track.end)).unwrap_or((false, 0));
+            (audio, 0xAA, 1, address.saturating_sub(lead_out))
+        },
+    };
+
+    let relative_msf = address_to_msf(relative);
+    let absolute_msf = address_to_msf(address);
+
+    q[0] = if audio {
+        0x01
+    } else {
+        0x41
+    };
+    q[1] = track_number;
+    q[2] = index;
+    q[3..6].copy_from_slice(&[relative_msf.0, relative_msf.1, relative_msf.2]);
+    q[7..10].copy_from_slice(&[absolute_msf.0, absolute_msf.1, absolute_msf.2]);
+
+    let crc = subchannel_q_crc(&q[0..10]);
+    q[10..12].copy_from_slice(&crc.to_be_bytes());
+    q
+}
+
+pub(crate) fn subchannel_q_crc_valid(q: &[u8; SUBCHANNEL_Q_SIZE]) -> bool {
+    subchannel_q_crc(&q[0..10]).to_be_bytes() == q[10..12]
+}
+
+/// CRC-16-CCITT (polynomial 0x1021, initial value 0), stored inverted.
+fn subchannel_q_crc(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+
+    for &byte in data.iter() {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if (crc & 0x8000) > 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toc() -> Vec<TocTrack> {
+        vec![
+            TocTrack {
+                number: 1,
+                audio: false,
+                pregap_start: 0,
+                start: 150,
+                end: 1000,
+            },
+            TocTrack {
+                number: 2,
+                audio: true,
+                pregap_start: 1000,
+                start: 1150,
+                end: 2000,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(subchannel_q_crc(&[0x41, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00]), 0x2832);
+    }
+
+    #[test]
+    fn test_generate() {
+        let toc = toc();
+
+        // Track 1 index 1, relative 00:00:01, absolute 00:02:01.
+        assert_eq!(generate_subchannel_q(&toc, 151), [0x41, 0x01, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x02, 0x01, 0x92, 0x42]);
+        // Track 2 pregap (index 0), counting down 00:01:25 to index 1, absolute 00:14:00.
+        assert_eq!(generate_subchannel_q(&toc, 1050), [0x01, 0x02, 0x00, 0x00, 0x01, 0x25, 0x00, 0x00, 0x14, 0x00, 0xF7, 0x29]);
+        // Lead-out, relative 00:00:05, absolute 00:26:55.
+        assert_eq!(generate_subchannel_q(&toc, 2005), [0x01, 0xAA, 0x01, 0x00, 0x00, 0x05, 0x00, 0x00, 0x26, 0x55, 0xA5, 0xF8]);
+    }
+
+    #[test]
+    fn test_crc_valid() {
+        let mut q = generate_subchannel_q(&toc(), 151);
+        assert!(subchannel_q_crc_valid(&q));
+
+        q[11] ^= 1;
+        assert!(!subchannel_q_crc_valid(&q));
+    }
+}
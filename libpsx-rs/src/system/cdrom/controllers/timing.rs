@@ -0,0 +1,174 @@
+//! Drive timing model: sector read rate at 1x / 2x speed, seek time by distance, motor spin-up and command response
+//! latencies.
+
+use crate::system::{
+    cdrom::{
+        constants::*,
+        controllers::state::*,
+        types::ControllerState,
+    },
+    types::ControllerResult,
+};
+
+/// Cycles between sectors while reading or playing.
+pub(crate) fn sector_cycles(controller_state: &ControllerState) -> usize {
+    let double_speed = MODE_DOUBLE_SPEED.extract_from(controller_state.mode) > 0;
+    let cycles = if double_speed {
+        SECTOR_DELAY_CYCLES_SINGLE_SPEED / 2
+    } else {
+        SECTOR_DELAY_CYCLES_SINGLE_SPEED
+    };
+
+    // Streamed audio (CD-DA or XA-ADPCM) has to be delivered in real time.
+    let streaming = controller_state.playing || (MODE_XA_ADPCM.extract_from(controller_state.mode) > 0);
+    if controller_state.fast_loading && !streaming {
+        cycles / FAST_LOADING_FACTOR
+    } else {
+        cycles
+    }
+}
+
+/// Cycles to move the head to the current (Setloc) position, including spinning up the motor if it's stopped.
+pub(crate) fn seek_cycles(controller_state: &ControllerState) -> usize {
+    let target_address = current_address(controller_state);
+    let head_address = controller_state.head_address;
+    let distance = if target_address > head_address {
+        target_address - head_address
+    } else {
+        head_address - target_address
+    };
+
+    let mut cycles = SEEK_MIN_CYCLES + (distance * SEEK_CYCLES_PER_SECTOR);
+    if distance >= SEEK_LONG_SECTORS {
+        cycles += SEEK_LONG_CYCLES;
+    }
+    cycles = cycles.min(SEEK_MAX_CYCLES);
+
+    if controller_state.fast_loading {
+        cycles /= FAST_LOADING_FACTOR;
+    }
+
+    cycles + spin_up_cycles(controller_state)
+}
+
+/// Cycles to spin up the motor; none if it's already on.
+pub(crate) fn spin_up_cycles(controller_state: &ControllerState) -> usize {
+    if controller_state.motor_on {
+        return 0;
+    }
+
+    if controller_state.fast_loading {
+        SPIN_UP_CYCLES / FAST_LOADING_FACTOR
+    } else {
+        SPIN_UP_CYCLES
+    }
+}
+
+/// Cycles from closing the lid until the drive is ready: spinning up the motor and reading the TOC.
+pub(crate) fn tray_close_cycles(controller_state: &ControllerState) -> usize {
+    let toc_cycles = if controller_state.fast_loading {
+        COMMAND_SECOND_READ_TOC_WAIT_CYCLES / FAST_LOADING_FACTOR
+    } else {
+        COMMAND_SECOND_READ_TOC_WAIT_CYCLES
+    };
+
+    spin_up_cycles(controller_state) + toc_cycles
+}
+
+/// Seeks to the current position, returning the cycles until the first sector is available.
+pub(crate) fn start_seek(controller_state: &mut ControllerState) -> usize {
+    let cycles = seek_cycles(controller_state);
+    controller_state.head_address = current_address(controller_state);
+    controller_state.motor_on = true;
+    controller_state.last_subchannel_q = None;
+    cycles
+}
+
+/// First response (INT3) latency; commands respond quicker when the motor is stopped.
+pub(crate) fn first_response_cycles(controller_state: &ControllerState) -> usize {
+    if controller_state.motor_on {
+        COMMAND_FIRST_DEFAULT_WAIT_CYCLES
+    } else {
+        COMMAND_FIRST_MOTOR_OFF_WAIT_CYCLES
+    }
+}
+
+pub(crate) fn default_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => Ok(COMMAND_SECOND_DEFAULT_WAIT_CYCLES),
+        _ => Err(format!("Default wait cycles for command iteration {} not implemented", command_iteration)),
+    }
+}
+
+pub(crate) fn motor_on_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => Ok(COMMAND_SECOND_DEFAULT_WAIT_CYCLES + spin_up_cycles(controller_state)),
+        _ => Err(format!("MotorOn: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+pub(crate) fn stop_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => {
+            if controller_state.motor_on {
+                Ok(COMMAND_SECOND_STOP_WAIT_CYCLES[speed_index(controller_state)])
+            } else {
+                Ok(COMMAND_SECOND_STOP_IDLE_WAIT_CYCLES)
+            }
+        },
+        _ => Err(format!("Stop: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+pub(crate) fn pause_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => {
+            if controller_state.already_paused {
+                Ok(COMMAND_SECOND_PAUSE_IDLE_WAIT_CYCLES)
+            } else {
+                Ok(COMMAND_SECOND_PAUSE_WAIT_CYCLES[speed_index(controller_state)])
+            }
+        },
+        _ => Err(format!("Pause: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+pub(crate) fn init_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(COMMAND_FIRST_INIT_WAIT_CYCLES),
+        1 => Ok(COMMAND_SECOND_DEFAULT_WAIT_CYCLES + spin_up_cycles(controller_state)),
+        _ => Err(format!("Init: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+pub(crate) fn seek_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => Ok(seek_cycles(controller_state)),
+        _ => Err(format!("Seek: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+pub(crate) fn get_id_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => Ok(COMMAND_SECOND_GET_ID_WAIT_CYCLES),
+        _ => Err(format!("GetID: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+pub(crate) fn read_toc_wait_cycles(controller_state: &ControllerState, command_iteration: usize) -> ControllerResult<usize> {
+    match command_iteration {
+        0 => Ok(first_response_cycles(controller_state)),
+        1 => Ok(COMMAND_SECOND_READ_TOC_WAIT_CYCLES + spin_up_cycles(controller_state)),
+        _ => Err(format!("ReadTOC: Invalid command iteration {}", command_iteration)),
+    }
+}
+
+fn speed_index(controller_state: &ControllerState) -> usize {
+    MODE_DOUBLE_SPEED.extract_from(controller_state.mode) as usize
+}
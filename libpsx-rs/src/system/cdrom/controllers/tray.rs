@@ -0,0 +1,78 @@
+//! Disc tray model: opening the lid stops the drive, and closing it spins the disc back up and re-reads the TOC before
+//! it can be accessed again.
+
+use crate::system::{
+    cdrom::{
+        constants::*,
+        controllers::{
+            command::command_requires_disc,
+            interrupt::*,
+            timing::*,
+        },
+        types::*,
+    },
+    types::{
+        ControllerResult,
+        State,
+    },
+};
+
+pub(crate) fn handle_tray(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    match controller_state.tray_state {
+        TrayState::Closed | TrayState::SpinningUp(_) if controller_state.lid_open => {
+            // A busy drive reports the lid opening with an INT5, once the previous interrupt has been acknowledged.
+            let busy = controller_state.reading || controller_state.playing || controller_state.seeking;
+            if busy && (controller_state.interrupt_index > 0) {
+                return Ok(());
+            }
+
+            open_tray(controller_state);
+
+            if busy {
+                handle_error_response(state, controller_state, ERROR_DOOR_OPENED)?;
+            }
+        },
+        TrayState::Open if !controller_state.lid_open => {
+            log::debug!("Lid closed, spinning up");
+            controller_state.tray_state = TrayState::SpinningUp(tray_close_cycles(controller_state));
+        },
+        TrayState::SpinningUp(0) => close_tray(controller_state),
+        TrayState::SpinningUp(cycles) => controller_state.tray_state = TrayState::SpinningUp(cycles - 1),
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// Stops the drive, abandoning any command still waiting on the disc.
+fn open_tray(controller_state: &mut ControllerState) {
+    log::debug!("Lid opened, stopping the drive");
+
+    controller_state.tray_state = TrayState::Open;
+    controller_state.shell_open = true;
+    controller_state.motor_on = false;
+    controller_state.reading = false;
+    controller_state.playing = false;
+    controller_state.seeking = false;
+    controller_state.play_scan = PlayScan::Normal;
+    controller_state.sector_delay_counter = 0;
+
+    if let Some(command_index) = controller_state.command_index {
+        if (controller_state.command_iteration > 0) && command_requires_disc(command_index) {
+            controller_state.command_index = None;
+            controller_state.command_iteration = 0;
+            controller_state.command_wait_cycles = WaitCyclesMode::Ready;
+        }
+    }
+}
+
+/// The TOC has been read; the (possibly different) disc is ready from the start.
+fn close_tray(controller_state: &mut ControllerState) {
+    log::debug!("Disc TOC read, drive ready");
+
+    controller_state.tray_state = TrayState::Closed;
+    controller_state.motor_on = true;
+    controller_state.head_address = 0;
+    controller_state.last_sector_header = None;
+    controller_state.last_subchannel_q = None;
+}
@@ -0,0 +1,235 @@
+use crate::{
+    system::{
+        cdrom::{
+            constants::*,
+            controllers::audio::push_audio,
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    types::stereo::Stereo,
+};
+
+impl XaCodingInfo {
+    pub(crate) fn from_byte(value: u8) -> XaCodingInfo {
+        XaCodingInfo {
+            stereo: XA_CODING_STEREO.extract_from(value) == 1,
+            sample_rate: if XA_CODING_SAMPLE_RATE.extract_from(value) == 1 {
+                18900
+            } else {
+                37800
+            },
+            bits_8: XA_CODING_BITS_PER_SAMPLE.extract_from(value) == 1,
+        }
+    }
+}
+
+/// Returns true for real-time XA-ADPCM sectors that should be sent to the SPU instead of the data FIFO.
+pub(crate) fn is_xa_audio_sector(controller_state: &ControllerState, raw_sector: &[u8]) -> bool {
+    if MODE_XA_ADPCM.extract_from(controller_state.mode) == 0 {
+        return false;
+    }
+
+    let submode = raw_sector[SECTOR_SUBHEADER_OFFSET + 2];
+    (raw_sector[SECTOR_MODE_OFFSET] == 2) && (SUBMODE_AUDIO.extract_from(submode) > 0) && (SUBMODE_FORM2.extract_from(submode) > 0) && (SUBMODE_REAL_TIME.extract_from(submode) > 0)
+}
+
+/// Decodes an XA-ADPCM sector (if it passes the file / channel filter) and sends it to the SPU.
+pub(crate) fn handle_xa_sector(state: &State, controller_state: &mut ControllerState, raw_sector: &[u8]) -> ControllerResult<()> {
+    let file = raw_sector[SECTOR_SUBHEADER_OFFSET];
+    let channel = raw_sector[SECTOR_SUBHEADER_OFFSET + 1];
+    let coding_info = XaCodingInfo::from_byte(raw_sector[SECTOR_SUBHEADER_OFFSET + 3]);
+
+    if MODE_XA_FILTER.extract_from(controller_state.mode) > 0 {
+        if (file != controller_state.filter_file) || (channel != controller_state.filter_channel) {
+            return Ok(());
+        }
+    }
+
+    let data = &raw_sector[SECTOR_MODE2_DATA_OFFSET..(SECTOR_MODE2_DATA_OFFSET + XA_DATA_SIZE)];
+    let samples = decode_xa_sector(&mut controller_state.xa_adpcm_state, coding_info, data);
+    let frames = resample_xa(&mut controller_state.xa_resampler_state, coding_info, &samples);
+
+    if controller_state.adpcm_muted {
+        return Ok(());
+    }
+
+    push_audio(state, controller_state, &frames)
+}
+
+/// Resamples decoded XA samples (interleaved if stereo) to 44.1 kHz stereo frames, using linear interpolation.
+pub(crate) fn resample_xa(resampler_state: &mut XaResamplerState, coding_info: XaCodingInfo, samples: &[i16]) -> Vec<Stereo> {
+    let input_frames: Vec<Stereo> = if coding_info.stereo {
+        samples.chunks_exact(2).map(|frame| Stereo::new(frame[0], frame[1])).collect()
+    } else {
+        samples.iter().map(|&sample| Stereo::new(sample, sample)).collect()
+    };
+
+    let interpolate = |previous: i16, current: i16, position: usize| -> i16 {
+        (previous as i64 + (((current as i64 - previous as i64) * position as i64) / XA_OUTPUT_SAMPLE_RATE as i64)) as i16
+    };
+
+    let mut output = Vec::with_capacity((input_frames.len() * XA_OUTPUT_SAMPLE_RATE) / coding_info.sample_rate + 1);
+
+    for current in input_frames.iter() {
+        let previous = resampler_state.previous;
+
+        while resampler_state.position < XA_OUTPUT_SAMPLE_RATE {
+            let position = resampler_state.position;
+            output.push(Stereo::new(interpolate(previous.left, current.left, position), interpolate(previous.right, current.right, position)));
+            resampler_state.position += coding_info.sample_rate;
+        }
+
+        resampler_state.position -= XA_OUTPUT_SAMPLE_RATE;
+        resampler_state.previous = *current;
+    }
+
+    output
+}
+
+/// Decodes the sound groups of an XA-ADPCM sector (the 2324 byte data area following the subheader).
+/// Returns 16-bit samples, interleaved left/right for stereo sectors.
+pub(crate) fn decode_xa_sector(adpcm_state: &mut XaAdpcmState, coding_info: XaCodingInfo, data: &[u8]) -> Vec<i16> {
+    let mut samples = Vec::new();
+
+    for group in data.chunks_exact(XA_SOUND_GROUP_SIZE).take(XA_SOUND_GROUP_COUNT) {
+        if coding_info.bits_8 {
+            decode_group_8bit(adpcm_state, coding_info.stereo, group, &mut samples);
+        } else {
+            decode_group_4bit(adpcm_state, coding_info.stereo, group, &mut samples);
+        }
+    }
+
+    samples
+}
+
+fn decode_group_4bit(adpcm_state: &mut XaAdpcmState, stereo: bool, group: &[u8], samples: &mut Vec<i16>) {
+    // 8 sound units; units are stored in the low/high nibbles of each byte column.
+    for block in 0..4 {
+        let mut units = [[0; XA_SAMPLES_PER_UNIT]; 2];
+
+        for nibble in 0..2 {
+            let unit = (block * 2) + nibble;
+            let channel = if stereo {
+                nibble
+            } else {
+                0
+            };
+            let raw = (0..XA_SAMPLES_PER_UNIT).map(|j| {
+                let value = (group[16 + block + (j * 4)] >> (nibble * 4)) & 0xF;
+                (((value as i8) << 4) >> 4) as i32
+            });
+            decode_unit(adpcm_state, channel, group[4 + unit], 4, raw, &mut units[nibble]);
+        }
+
+        push_units(stereo, &units, samples);
+    }
+}
+
+fn decode_group_8bit(adpcm_state: &mut XaAdpcmState, stereo: bool, group: &[u8], samples: &mut Vec<i16>) {
+    // 4 sound units; one per byte column.
+    for block in 0..2 {
+        let mut units = [[0; XA_SAMPLES_PER_UNIT]; 2];
+
+        for index in 0..2 {
+            let unit = (block * 2) + index;
+            let channel = if stereo {
+                index
+            } else {
+                0
+            };
+            let raw = (0..XA_SAMPLES_PER_UNIT).map(|j| group[16 + unit + (j * 4)] as i8 as i32);
+            decode_unit(adpcm_state, channel, group[4 + unit], 8, raw, &mut units[index]);
+        }
+
+        push_units(stereo, &units, samples);
+    }
+}
+
+fn decode_unit<I: Iterator<Item = i32>>(adpcm_state: &mut XaAdpcmState, channel: usize, header: u8, sample_bits: usize, raw: I, output: &mut [i16; XA_SAMPLES_PER_UNIT]) {
+    let mut shift = (header & 0xF) as usize;
+    if shift > 12 {
+        // Reserved values behave as 9.
+        shift = 9;
+    }
+
+    let filter = ((header >> 4) & 0x3) as usize;
+    let positive = XA_FILTER_POSITIVE[filter];
+    let negative = XA_FILTER_NEGATIVE[filter];
+
+    for (j, value) in raw.enumerate() {
+        let old = adpcm_state.old[channel];
+        let older = adpcm_state.older[channel];
+
+        let sample = ((value << (16 - sample_bits)) >> shift) + (((old * positive) + (older * negative) + 32) / 64);
+        let sample = sample.max(std::i16::MIN as i32).min(std::i16::MAX as i32);
+
+        output[j] = sample as i16;
+        adpcm_state.older[channel] = old;
+        adpcm_state.old[channel] = sample;
+    }
+}
+
+fn push_units(stereo: bool, units: &[[i16; XA_SAMPLES_PER_UNIT]; 2], samples: &mut Vec<i16>) {
+    if stereo {
+        for j in 0..XA_SAMPLES_PER_UNIT {
+            samples.push(units[0][j]);
+            samples.push(units[1][j]);
+        }
+    } else {
+        samples.extend_from_slice(&units[0]);
+        samples.extend_from_slice(&units[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_4bit_mono_group() {
+        // Units 0 / 1 / 2 use filter 0 shift 12, filter 1 shift 8 and filter 2 shift 12; every even unit nibble is 1
+        // and every odd unit nibble is 2.
+        let mut group = [0x21; XA_SOUND_GROUP_SIZE];
+        group[4..16].copy_from_slice(&[0x0C, 0x18, 0x2C, 0, 0, 0, 0, 0, 0x0C, 0x18, 0x2C, 0]);
+        group.copy_within(4..8, 0);
+
+        let coding_info = XaCodingInfo::from_byte(0x00);
+        assert_eq!(
+            coding_info,
+            XaCodingInfo {
+                stereo: false,
+                sample_rate: 37800,
+                bits_8: false,
+            }
+        );
+
+        let mut adpcm_state = XaAdpcmState::new();
+        let samples = decode_xa_sector(&mut adpcm_state, coding_info, &group);
+        assert_eq!(samples.len(), 8 * XA_SAMPLES_PER_UNIT);
+
+        // Filter 0: the nibble shifted to the top of the sample, then right by the shift.
+        assert_eq!(&samples[0..28], &[1; 28]);
+        // Filter 1: 32 + (old * 60 + 32) / 64.
+        assert_eq!(&samples[28..32], &[33, 63, 91, 117]);
+        assert_eq!(samples[55], 429);
+        // Filter 2: 1 + (old * 115 - older * 52 + 32) / 64.
+        assert_eq!(&samples[56..60], &[428, 422, 412, 398]);
+        assert_eq!(samples[83], 72);
+    }
+
+    #[test]
+    fn test_resample_mono() {
+        let coding_info = XaCodingInfo::from_byte(0x00);
+        let mut resampler_state = XaResamplerState::new();
+
+        // 37.8 kHz -> 44.1 kHz: a ramp of 4410 per input sample becomes a ramp of 3780 per output sample.
+        let frames = resample_xa(&mut resampler_state, coding_info, &[4410, 8820, 13230]);
+        let frames: Vec<(i16, i16)> = frames.iter().map(|frame| (frame.left, frame.right)).collect();
+        assert_eq!(frames, vec![(0, 0), (3780, 3780), (7560, 7560), (11340, 11340)]);
+        assert_eq!(resampler_state.position, 18900);
+    }
+}
@@ -1,13 +1,20 @@
 use crate::{
-    backends::cdrom::CdromBackend,
-    system::types::{
-        ControllerResult,
-        State as SystemState,
+    backends::cdrom::{
+        image::SUBCHANNEL_Q_SIZE,
+        CdromBackend,
+    },
+    system::{
+        cdrom::constants::*,
+        types::{
+            ControllerResult,
+            State as SystemState,
+        },
     },
     types::{
         exclusive_state::ExclusiveState,
         fifo::Fifo,
         memory::*,
+        stereo::Stereo,
     },
 };
 #[cfg(feature = "serialization")]
@@ -17,7 +24,7 @@ use serde::{
 };
 use std::collections::VecDeque;
 
-pub(crate) type WaitCyclesFn = fn(usize) -> ControllerResult<usize>;
+pub(crate) type WaitCyclesFn = fn(&ControllerState, usize) -> ControllerResult<usize>;
 
 pub(crate) type LengthFn = fn(usize) -> usize;
 
@@ -31,6 +38,103 @@ pub(crate) enum WaitCyclesMode {
     Executing,
 }
 
+/// Direction of CD-DA playback, as set by Play / Forward / Backward.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum PlayScan {
+    Normal,
+    Forward,
+    Backward,
+}
+
+/// Disc tray (lid) state, as seen by the drive.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum TrayState {
+    Closed,
+    Open,
+    /// Lid closed; spinning up and reading the TOC for the remaining cycles.
+    SpinningUp(usize),
+}
+
+/// Track layout reported by the backend; addresses are absolute sector numbers (00:00:00 = 0).
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TocTrack {
+    pub(crate) number: usize,
+    pub(crate) audio: bool,
+    /// Start of the pregap (index 0); equal to start if there is none.
+    pub(crate) pregap_start: usize,
+    /// Start of index 1.
+    pub(crate) start: usize,
+    /// Address following the last sector of the track.
+    pub(crate) end: usize,
+}
+
+/// Sound format of an XA-ADPCM sector, as specified by the subheader coding info byte.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct XaCodingInfo {
+    pub(crate) stereo: bool,
+    pub(crate) sample_rate: usize,
+    pub(crate) bits_8: bool,
+}
+
+/// ADPCM decoder history; the previous 2 samples for each channel (left/mono, right).
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub(crate) struct XaAdpcmState {
+    pub(crate) old: [i32; 2],
+    pub(crate) older: [i32; 2],
+}
+
+impl XaAdpcmState {
+    pub(crate) fn new() -> XaAdpcmState {
+        XaAdpcmState {
+            old: [0; 2],
+            older: [0; 2],
+        }
+    }
+}
+
+/// Linear resampler from the XA sample rate to 44.1 kHz.
+/// Position is the time of the next output sample after the previous input sample, in units of 1 / (input rate * output
+/// rate).
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub(crate) struct XaResamplerState {
+    pub(crate) previous: Stereo,
+    pub(crate) position: usize,
+}
+
+impl XaResamplerState {
+    pub(crate) fn new() -> XaResamplerState {
+        XaResamplerState {
+            previous: Stereo::new(0, 0),
+            position: 0,
+        }
+    }
+}
+
+/// CD audio output to SPU input volume matrix (0x80 = 100%).
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct AudioVolume {
+    pub(crate) left_to_left: u8,
+    pub(crate) left_to_right: u8,
+    pub(crate) right_to_left: u8,
+    pub(crate) right_to_right: u8,
+}
+
+impl AudioVolume {
+    pub(crate) fn new() -> AudioVolume {
+        AudioVolume {
+            left_to_left: AUDIO_VOLUME_UNITY as u8,
+            left_to_right: 0,
+            right_to_left: 0,
+            right_to_right: AUDIO_VOLUME_UNITY as u8,
+        }
+    }
+}
+
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 pub(crate) struct ControllerState {
@@ -42,18 +146,58 @@ pub(crate) struct ControllerState {
     pub(crate) command_index: Option<u8>,
     pub(crate) command_iteration: usize,
     pub(crate) command_wait_cycles: WaitCyclesMode,
+    /// Drive motor status; reads, seeks and playback need to spin it up first.
+    pub(crate) motor_on: bool,
+    /// Lid position requested through the core API; the drive reacts to it on the next tick.
+    pub(crate) lid_open: bool,
+    pub(crate) tray_state: TrayState,
+    /// Shell open status bit; set while the lid is open, and stays set until the first GetStat after it has closed.
+    pub(crate) shell_open: bool,
     /// Seeking status.
     pub(crate) seeking: bool,
     /// Reading status.
     pub(crate) reading: bool,
+    /// CD-DA playing status.
+    pub(crate) playing: bool,
+    pub(crate) play_scan: PlayScan,
+    /// Track being played, for auto pause at the end of the track.
+    pub(crate) play_track: usize,
     /// Current MSF address.
     /// Base is stored in BCD format.
     pub(crate) msf_address_base: (u8, u8, u8),
     pub(crate) msf_address_offset: usize,
+    /// Absolute sector address of the drive head, for seek times.
+    pub(crate) head_address: usize,
+    /// Pause was issued while already paused (the second response is quicker).
+    pub(crate) already_paused: bool,
+    /// Fast loading timing override.
+    pub(crate) fast_loading: bool,
+    /// Licence (SCEx) region letter accepted by the drive: A (NTSC-U), I (NTSC-J) or E (PAL).
+    pub(crate) region_code: u8,
+    /// Licence check bypassed.
+    pub(crate) modchip: bool,
+    /// Drive mode, as set by Setmode.
+    pub(crate) mode: u8,
     pub(crate) sector_delay_counter: usize,
     pub(crate) sector_buffer: VecDeque<u8>,
     pub(crate) load_data_flag: bool,
     pub(crate) loading_data: bool,
+    /// XA-ADPCM file / channel filter, as set by SetFilter.
+    pub(crate) filter_file: u8,
+    pub(crate) filter_channel: u8,
+    pub(crate) xa_adpcm_state: XaAdpcmState,
+    pub(crate) xa_resampler_state: XaResamplerState,
+    /// Applied audio volume matrix; the volume registers take effect on an audio apply write.
+    pub(crate) audio_volume: AudioVolume,
+    pub(crate) adpcm_muted: bool,
+    /// All CD audio output muted, as set by Mute / Demute.
+    pub(crate) muted: bool,
+    /// Header and subheader of the last data sector read, for GetlocL.
+    pub(crate) last_sector_header: Option<[u8; 8]>,
+    /// Subchannel Q response prepared by the first stage of GetQ.
+    pub(crate) get_q_response: Option<[u8; 10]>,
+    /// Last subchannel Q data read with a valid CRC, for GetlocP and report mode; cleared when seeking.
+    pub(crate) last_subchannel_q: Option<[u8; SUBCHANNEL_Q_SIZE]>,
 }
 
 impl ControllerState {
@@ -64,14 +208,37 @@ impl ControllerState {
             command_index: None,
             command_iteration: 0,
             command_wait_cycles: WaitCyclesMode::Ready,
+            motor_on: true,
+            lid_open: false,
+            tray_state: TrayState::Closed,
+            shell_open: false,
             seeking: false,
             reading: false,
+            playing: false,
+            play_scan: PlayScan::Normal,
+            play_track: 0,
             msf_address_base: (0, 0, 0),
             msf_address_offset: 0,
+            head_address: 0,
+            already_paused: false,
+            fast_loading: false,
+            region_code: b'A',
+            modchip: false,
+            mode: 0,
             sector_delay_counter: 0,
             sector_buffer: VecDeque::new(),
             load_data_flag: false,
             loading_data: false,
+            filter_file: 0,
+            filter_channel: 0,
+            xa_adpcm_state: XaAdpcmState::new(),
+            xa_resampler_state: XaResamplerState::new(),
+            audio_volume: AudioVolume::new(),
+            adpcm_muted: false,
+            muted: false,
+            last_sector_header: None,
+            get_q_response: None,
+            last_subchannel_q: None,
         }
     }
 }
@@ -83,6 +250,8 @@ pub(crate) struct State {
     pub(crate) response: Fifo<u8>,
     pub(crate) parameter: Fifo<u8>,
     pub(crate) data: Fifo<u8>,
+    /// CD audio (44.1 kHz) sent to the SPU CD audio input.
+    pub(crate) audio: Fifo<Stereo>,
     pub(crate) command: B8EdgeRegister,
     pub(crate) interrupt_enable: B8LevelRegister,
     pub(crate) interrupt_flag: B8EdgeRegister,
@@ -101,7 +270,8 @@ impl State {
             status: B8LevelRegister::new(),
             response: Fifo::new(16),
             parameter: Fifo::new(16),
-            data: Fifo::new(2048),
+            data: Fifo::new(DATA_FIFO_SIZE),
+            audio: Fifo::new(AUDIO_FIFO_SIZE),
             command: B8EdgeRegister::new(),
             interrupt_enable: B8LevelRegister::new(),
             interrupt_flag: B8EdgeRegister::with_value(0xE0),
@@ -29,8 +29,8 @@ fn run_time(state: &State, duration: f32) -> ControllerResult<()> {
     let controller_state = &mut state.dmac.controller_state.lock();
     controller_state.clock += duration;
 
-    let mut channel_id = 6;
     while controller_state.clock > 0.0 {
+        let channel_id = controller_state.channel_id;
         handle_dpcr(state, controller_state)?;
         handle_dicr(state, controller_state)?;
         handle_chcr(state, controller_state, channel_id)?;
@@ -41,11 +41,14 @@ fn run_time(state: &State, duration: f32) -> ControllerResult<()> {
 
         if cooloff_required {
             // Delay the DMAC a litte bit and allow the CPU to use the memory bus.
+            // The next channel is processed afterwards, as the channel may be waiting on data from another channel (ie:
+            // MDECout waiting on MDECin).
             controller_state.clock = -100.0 * CLOCK_SPEED_PERIOD;
+            controller_state.channel_id = (channel_id + CHANNEL_COUNT - 1) % CHANNEL_COUNT;
         } else {
             if ticks_used <= 1 {
                 // Channel had no meaningful progress made, so process the next one.
-                channel_id = (channel_id + CHANNEL_COUNT - 1) % CHANNEL_COUNT;
+                controller_state.channel_id = (channel_id + CHANNEL_COUNT - 1) % CHANNEL_COUNT;
             }
         }
     }
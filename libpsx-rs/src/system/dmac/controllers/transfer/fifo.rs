@@ -1,5 +1,13 @@
 use crate::system::{
     dmac::constants::*,
+    mdec::constants::{
+        STATUS_DATA_IN_REQUEST,
+        STATUS_DATA_OUT_REQUEST,
+    },
+    spu::constants::{
+        STAT_DMA_R_REQUEST,
+        STAT_DMA_W_REQUEST,
+    },
     types::{
         ControllerResult,
         State,
@@ -8,6 +16,14 @@ use crate::system::{
 
 pub(crate) fn pop_channel_data(state: &State, channel_id: usize, current_address: u32, last_transfer: bool) -> ControllerResult<Option<u32>> {
     let result = match channel_id {
+        1 => {
+            // Only transfer when the MDEC is requesting data to be read out.
+            if state.mdec.status.read_bitfield(STATUS_DATA_OUT_REQUEST) == 0 {
+                return Ok(None);
+            }
+
+            state.mdec.data_out.read_one()
+        },
         2 => state.gpu.read.read_one(),
         3 => {
             let fifo = &state.cdrom.data;
@@ -23,6 +39,23 @@ pub(crate) fn pop_channel_data(state: &State, channel_id: usize, current_address
 
             Ok(u32::from_le_bytes([result1, result2, result3, result4]))
         },
+        4 => {
+            // Only transfer when the SPU is requesting data to be read out.
+            if state.spu.stat.read_bitfield(STAT_DMA_R_REQUEST) == 0 {
+                return Ok(None);
+            }
+
+            let fifo = &state.spu.data_fifo;
+
+            if fifo.read_available() < 2 {
+                return Ok(None);
+            }
+
+            let data_u16_1 = fifo.read_one().unwrap();
+            let data_u16_2 = fifo.read_one().unwrap();
+
+            Ok(data_u16_1 as u32 | (data_u16_2 as u32) << 16)
+        },
         6 => {
             Ok(if !last_transfer {
                 (current_address - DATA_SIZE) & 0x00FF_FFFF
@@ -38,8 +71,21 @@ pub(crate) fn pop_channel_data(state: &State, channel_id: usize, current_address
 
 pub(crate) fn push_channel_data(state: &State, channel_id: usize, value: u32) -> ControllerResult<Option<()>> {
     let result = match channel_id {
+        0 => {
+            // Only transfer when the MDEC is requesting data to be written in.
+            if state.mdec.status.read_bitfield(STATUS_DATA_IN_REQUEST) == 0 {
+                return Ok(None);
+            }
+
+            state.mdec.data_in.write_one(value)
+        },
         2 => state.gpu.gp0.write_one(value),
         4 => {
+            // Only transfer when the SPU is requesting data to be written in.
+            if state.spu.stat.read_bitfield(STAT_DMA_W_REQUEST) == 0 {
+                return Ok(None);
+            }
+
             let fifo = &state.spu.data_fifo;
 
             if fifo.write_available() < 2 {
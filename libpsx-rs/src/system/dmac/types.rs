@@ -1,7 +1,10 @@
-use crate::types::{
-    exclusive_state::ExclusiveState,
-    flag::Flag,
-    memory::*,
+use crate::{
+    system::dmac::constants::CHANNEL_COUNT,
+    types::{
+        exclusive_state::ExclusiveState,
+        flag::Flag,
+        memory::*,
+    },
 };
 #[cfg(feature = "serialization")]
 use serde::{
@@ -127,6 +130,9 @@ pub(crate) struct ControllerState {
     pub(crate) master_interrupt_enabled: bool,
     pub(crate) master_interrupted: bool,
 
+    /// Channel currently being processed.
+    pub(crate) channel_id: usize,
+
     /// Channel transfer states.
     pub(crate) mdecin_transfer_state: TransferState,
     pub(crate) mdecout_transfer_state: TransferState,
@@ -143,6 +149,7 @@ impl ControllerState {
             clock: 0.0,
             master_interrupt_enabled: false,
             master_interrupted: false,
+            channel_id: CHANNEL_COUNT - 1,
             mdecin_transfer_state: TransferState::new(),
             mdecout_transfer_state: TransferState::new(),
             gpu_transfer_state: TransferState::new(),
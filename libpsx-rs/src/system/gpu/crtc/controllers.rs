@@ -1,6 +1,7 @@
 pub(crate) mod backend_dispatch;
 pub(crate) mod display;
 pub(crate) mod interrupt;
+pub(crate) mod lightgun;
 
 use crate::{
     system::{
@@ -11,7 +12,9 @@ use crate::{
                 controllers::{
                     display::*,
                     interrupt::*,
+                    lightgun::*,
                 },
+                types::ControllerState,
             },
         },
         timers::{
@@ -50,7 +53,7 @@ pub(crate) fn run_time(state: &State, video_backend: &VideoBackend, duration: f3
                 get_hblank(state, timer_id).store(true);
             }
 
-            handle_scanline_tick(state);
+            handle_scanline_tick(state, controller_state);
             controller_state.scanline_clock -= SCANLINE_NTSC_PERIOD;
             handled = true;
         }
@@ -60,7 +63,7 @@ pub(crate) fn run_time(state: &State, video_backend: &VideoBackend, duration: f3
                 get_vblank(state, timer_id).store(true);
             }
 
-            handle_frame_tick(state, video_backend)?;
+            handle_frame_tick(state, controller_state, video_backend)?;
             controller_state.frame_clock -= REFRESH_RATE_NTSC_PERIOD;
             handled = true;
         }
@@ -73,12 +76,17 @@ pub(crate) fn run_time(state: &State, video_backend: &VideoBackend, duration: f3
     Ok(())
 }
 
-fn handle_scanline_tick(state: &State) {
+fn handle_scanline_tick(state: &State, controller_state: &mut ControllerState) {
     let drawing_odd_bit = state.gpu.stat.read_bitfield(STAT_DRAWING_ODD) ^ 1;
     state.gpu.stat.write_bitfield(STAT_DRAWING_ODD, drawing_odd_bit);
+
+    handle_lightgun_scanline(state, controller_state);
+    controller_state.scanline += 1;
 }
 
-fn handle_frame_tick(state: &State, video_backend: &VideoBackend) -> ControllerResult<()> {
+fn handle_frame_tick(state: &State, controller_state: &mut ControllerState, video_backend: &VideoBackend) -> ControllerResult<()> {
+    controller_state.scanline = 0;
+    handle_lightgun_frame(state);
     handle_vblank_interrupt(state);
     handle_render(state, video_backend)?;
 
@@ -0,0 +1,54 @@
+use crate::system::{
+    gpu::{
+        constants::CLOCK_SPEED_NTSC,
+        crtc::types::ControllerState,
+    },
+    padmc::constants::GUNCON_CLOCK_SPEED,
+    types::State,
+};
+
+/// Called at the start of each frame (vblank); resolves lightgun pointers into beam positions using the current display
+/// ranges. Pointers that were not hit by the beam during the previous frame report that no light was detected.
+pub(crate) fn handle_lightgun_frame(state: &State) {
+    let (x1, x2, y1, y2) = {
+        let gpu_controller_state = state.gpu.controller_state.lock();
+        (
+            gpu_controller_state.horizontal_display_range_x1,
+            gpu_controller_state.horizontal_display_range_x2,
+            gpu_controller_state.vertical_display_range_y1,
+            gpu_controller_state.vertical_display_range_y2,
+        )
+    };
+
+    let lightgun_state = &mut state.padmc.lightgun_state.lock();
+
+    for port_index in 0..2 {
+        if !lightgun_state.latched_this_frame[port_index] {
+            lightgun_state.latches[port_index] = None;
+        }
+
+        lightgun_state.latched_this_frame[port_index] = false;
+
+        lightgun_state.targets[port_index] = lightgun_state.pointers[port_index].map(|(x, y)| {
+            // Horizontal range is in GPU video clocks since hsync; the GunCon counts in 8 MHz clocks.
+            let video_clock = x1 as f32 + (x * (x2.saturating_sub(x1)) as f32);
+            let target_x = (video_clock * (GUNCON_CLOCK_SPEED / CLOCK_SPEED_NTSC)) as u16;
+            let target_y = (y1 as f32 + (y * (y2.saturating_sub(y1)) as f32)) as u16;
+            (target_x, target_y)
+        });
+    }
+}
+
+/// Called on each scanline (hblank); latches the beam position for lightguns targeting the current scanline.
+pub(crate) fn handle_lightgun_scanline(state: &State, controller_state: &ControllerState) {
+    let lightgun_state = &mut state.padmc.lightgun_state.lock();
+
+    for port_index in 0..2 {
+        if let Some((target_x, target_y)) = lightgun_state.targets[port_index] {
+            if target_y as usize == controller_state.scanline {
+                lightgun_state.latches[port_index] = Some((target_x, target_y));
+                lightgun_state.latched_this_frame[port_index] = true;
+            }
+        }
+    }
+}
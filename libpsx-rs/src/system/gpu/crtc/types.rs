@@ -10,6 +10,8 @@ use serde::{
 pub(crate) struct ControllerState {
     pub(crate) scanline_clock: f32,
     pub(crate) frame_clock: f32,
+    /// Current scanline since the start of the frame (vsync).
+    pub(crate) scanline: usize,
 }
 
 impl ControllerState {
@@ -17,6 +19,7 @@ impl ControllerState {
         ControllerState {
             scanline_clock: 0.0,
             frame_clock: 0.0,
+            scanline: 0,
         }
     }
 }
@@ -0,0 +1,4 @@
+pub(crate) mod constants;
+pub(crate) mod controllers;
+pub(crate) mod memory;
+pub(crate) mod types;
@@ -0,0 +1,51 @@
+use crate::types::bitfield::Bitfield;
+
+pub(crate) const CLOCK_SPEED: f32 = 33.8688 * 1e6; // 33.8688 MHz
+pub(crate) const CLOCK_SPEED_PERIOD: f32 = 1.0 / CLOCK_SPEED;
+
+pub(crate) const STATUS_PARAMETER_WORDS: Bitfield = Bitfield::new(0, 16);
+pub(crate) const STATUS_CURRENT_BLOCK: Bitfield = Bitfield::new(16, 3);
+pub(crate) const STATUS_OUTPUT_BIT15: Bitfield = Bitfield::new(23, 1);
+pub(crate) const STATUS_OUTPUT_SIGNED: Bitfield = Bitfield::new(24, 1);
+pub(crate) const STATUS_OUTPUT_DEPTH: Bitfield = Bitfield::new(25, 2);
+pub(crate) const STATUS_DATA_OUT_REQUEST: Bitfield = Bitfield::new(27, 1);
+pub(crate) const STATUS_DATA_IN_REQUEST: Bitfield = Bitfield::new(28, 1);
+pub(crate) const STATUS_COMMAND_BUSY: Bitfield = Bitfield::new(29, 1);
+pub(crate) const STATUS_DATA_IN_FULL: Bitfield = Bitfield::new(30, 1);
+pub(crate) const STATUS_DATA_OUT_EMPTY: Bitfield = Bitfield::new(31, 1);
+
+pub(crate) const CONTROL_DATA_OUT_ENABLE: Bitfield = Bitfield::new(29, 1);
+pub(crate) const CONTROL_DATA_IN_ENABLE: Bitfield = Bitfield::new(30, 1);
+pub(crate) const CONTROL_RESET: Bitfield = Bitfield::new(31, 1);
+
+pub(crate) const COMMAND_OPCODE: Bitfield = Bitfield::new(29, 3);
+pub(crate) const COMMAND_OUTPUT_BIT15: Bitfield = Bitfield::new(25, 1);
+pub(crate) const COMMAND_OUTPUT_SIGNED: Bitfield = Bitfield::new(26, 1);
+pub(crate) const COMMAND_OUTPUT_DEPTH: Bitfield = Bitfield::new(27, 2);
+pub(crate) const COMMAND_PARAMETER_WORDS: Bitfield = Bitfield::new(0, 16);
+pub(crate) const COMMAND_QUANT_COLOR: Bitfield = Bitfield::new(0, 1);
+
+pub(crate) const OPCODE_DECODE_MACROBLOCK: u32 = 1;
+pub(crate) const OPCODE_SET_QUANT_TABLE: u32 = 2;
+pub(crate) const OPCODE_SET_SCALE_TABLE: u32 = 3;
+
+pub(crate) const BLOCK_SIZE: usize = 64;
+/// Block order within a colored macroblock: Cr, Cb, then Y1-Y4 (top-left, top-right, bottom-left, bottom-right).
+pub(crate) const BLOCK_INDEX_CR: usize = 4;
+pub(crate) const BLOCK_INDEX_CB: usize = 5;
+pub(crate) const COLOR_BLOCK_ORDER: [usize; 6] = [BLOCK_INDEX_CR, BLOCK_INDEX_CB, 0, 1, 2, 3];
+/// Monochrome macroblocks consist of a single Y block, reported as block 4.
+pub(crate) const MONOCHROME_BLOCK_INDEX: usize = 4;
+
+/// End of block/padding code.
+pub(crate) const RLE_END_OF_BLOCK: u16 = 0xFE00;
+
+/// Maps a coefficient index in zig-zag order to its position in the (row-major) block.
+pub(crate) const ZAGZIG: [usize; BLOCK_SIZE] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20, 13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37,
+    44, 51, 58, 59, 52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+pub(crate) const DATA_IN_FIFO_SIZE: usize = 32;
+/// Sized to hold a full 24-bit macroblock (16 x 16 x 3 bytes), rather than the 32 words of the real hardware.
+pub(crate) const DATA_OUT_FIFO_SIZE: usize = 192;
@@ -0,0 +1,47 @@
+pub(crate) mod command;
+pub(crate) mod decode;
+pub(crate) mod output;
+pub(crate) mod register;
+
+use crate::system::{
+    mdec::{
+        constants::*,
+        controllers::{
+            command::*,
+            register::*,
+        },
+        types::*,
+    },
+    types::{
+        ControllerContext,
+        ControllerResult,
+        Event,
+        State,
+    },
+};
+
+pub(crate) fn run(context: &ControllerContext, event: Event) -> ControllerResult<()> {
+    match event {
+        Event::Time(duration) => run_time(context.state, duration),
+    }
+}
+
+fn run_time(state: &State, duration: f32) -> ControllerResult<()> {
+    let controller_state = &mut state.mdec.controller_state.lock();
+    controller_state.clock += duration;
+
+    while controller_state.clock > 0.0 {
+        tick(state, controller_state)?;
+        controller_state.clock -= CLOCK_SPEED_PERIOD;
+    }
+
+    Ok(())
+}
+
+fn tick(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    handle_control(state, controller_state)?;
+    handle_command(state, controller_state);
+    handle_status(state, controller_state);
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use crate::system::{
+    mdec::{
+        constants::*,
+        controllers::{
+            decode::*,
+            output::*,
+        },
+        types::*,
+    },
+    types::State,
+};
+
+pub(crate) fn handle_command(state: &State, controller_state: &mut ControllerState) {
+    match controller_state.command {
+        Command::None => {
+            if let Ok(value) = state.mdec.data_in.read_one() {
+                handle_command_start(state, controller_state, value);
+            }
+        },
+        Command::DecodeMacroblock => handle_decode_macroblock(state, controller_state),
+        Command::SetQuantTable(color) => {
+            if handle_parameter(state, controller_state) {
+                handle_set_quant_table(controller_state, color);
+                controller_state.command = Command::None;
+            }
+        },
+        Command::SetScaleTable => {
+            if handle_parameter(state, controller_state) {
+                handle_set_scale_table(controller_state);
+                controller_state.command = Command::None;
+            }
+        },
+    }
+}
+
+fn handle_command_start(state: &State, controller_state: &mut ControllerState, value: u32) {
+    state.mdec.status.write_bitfield(STATUS_OUTPUT_BIT15, COMMAND_OUTPUT_BIT15.extract_from(value));
+    state.mdec.status.write_bitfield(STATUS_OUTPUT_SIGNED, COMMAND_OUTPUT_SIGNED.extract_from(value));
+    state.mdec.status.write_bitfield(STATUS_OUTPUT_DEPTH, COMMAND_OUTPUT_DEPTH.extract_from(value));
+
+    controller_state.parameters.clear();
+
+    match COMMAND_OPCODE.extract_from(value) {
+        OPCODE_DECODE_MACROBLOCK => {
+            controller_state.output_depth = OutputDepth::from_bits(COMMAND_OUTPUT_DEPTH.extract_from(value));
+            controller_state.output_signed = COMMAND_OUTPUT_SIGNED.extract_from(value) > 0;
+            controller_state.output_bit15 = COMMAND_OUTPUT_BIT15.extract_from(value) > 0;
+            controller_state.parameter_words = COMMAND_PARAMETER_WORDS.extract_from(value) as usize;
+            controller_state.rle_buffer.clear();
+            controller_state.blocks.clear();
+            controller_state.command = Command::DecodeMacroblock;
+        },
+        OPCODE_SET_QUANT_TABLE => {
+            let color = COMMAND_QUANT_COLOR.extract_from(value) > 0;
+            controller_state.parameter_words = if color {
+                32
+            } else {
+                16
+            };
+            controller_state.command = Command::SetQuantTable(color);
+        },
+        OPCODE_SET_SCALE_TABLE => {
+            controller_state.parameter_words = 32;
+            controller_state.command = Command::SetScaleTable;
+        },
+        _ => {
+            log::warn!("Unknown MDEC command 0x{:08X}; ignoring", value);
+        },
+    }
+}
+
+/// Receives a parameter word for the table commands; returns true when all parameters have been received.
+fn handle_parameter(state: &State, controller_state: &mut ControllerState) -> bool {
+    if controller_state.parameter_words > 0 {
+        if let Ok(value) = state.mdec.data_in.read_one() {
+            controller_state.parameters.push(value);
+            controller_state.parameter_words -= 1;
+        }
+    }
+
+    controller_state.parameter_words == 0
+}
+
+fn handle_set_quant_table(controller_state: &mut ControllerState, color: bool) {
+    let bytes: Vec<u8> = controller_state.parameters.iter().flat_map(|value| value.to_le_bytes().to_vec()).collect();
+
+    controller_state.luminance_quant_table.copy_from_slice(&bytes[0..BLOCK_SIZE]);
+
+    if color {
+        controller_state.color_quant_table.copy_from_slice(&bytes[BLOCK_SIZE..(BLOCK_SIZE * 2)]);
+    }
+}
+
+fn handle_set_scale_table(controller_state: &mut ControllerState) {
+    for (index, value) in controller_state.parameters.iter().enumerate() {
+        controller_state.scale_table[index * 2] = *value as u16 as i16;
+        controller_state.scale_table[(index * 2) + 1] = (*value >> 16) as u16 as i16;
+    }
+}
+
+fn handle_decode_macroblock(state: &State, controller_state: &mut ControllerState) {
+    let block_count = macroblock_block_count(controller_state.output_depth);
+
+    if controller_state.blocks.len() == block_count {
+        // Wait until there is room for the whole macroblock in the output FIFO.
+        if state.mdec.data_out.write_available() < macroblock_word_count(controller_state.output_depth) {
+            return;
+        }
+
+        handle_macroblock_output(state, controller_state);
+        controller_state.blocks.clear();
+    }
+
+    if controller_state.parameter_words > 0 {
+        if let Ok(value) = state.mdec.data_in.read_one() {
+            controller_state.parameter_words -= 1;
+            controller_state.rle_buffer.push_back(value as u16);
+            controller_state.rle_buffer.push_back((value >> 16) as u16);
+            handle_block_decode(controller_state);
+        }
+    } else if !handle_block_decode(controller_state) {
+        // All data has been received and decoded; anything left over is padding or an incomplete macroblock.
+        controller_state.rle_buffer.clear();
+        controller_state.blocks.clear();
+        controller_state.command = Command::None;
+    }
+}
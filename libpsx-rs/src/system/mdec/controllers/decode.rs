@@ -0,0 +1,173 @@
+use crate::system::mdec::{
+    constants::*,
+    types::*,
+};
+use std::collections::VecDeque;
+
+/// Decodes the next block from the RLE buffer if it is complete, adding it to the current macroblock.
+/// Returns false if there is not enough data buffered (or the macroblock is already complete).
+pub(crate) fn handle_block_decode(controller_state: &mut ControllerState) -> bool {
+    if controller_state.blocks.len() >= macroblock_block_count(controller_state.output_depth) {
+        return false;
+    }
+
+    // The Cr/Cb blocks are the first 2 blocks of a colored macroblock.
+    let quant_table = if controller_state.output_depth.is_monochrome() || controller_state.blocks.len() >= 2 {
+        &controller_state.luminance_quant_table
+    } else {
+        &controller_state.color_quant_table
+    };
+
+    let (mut block, consumed) = match decode_rle_block(&controller_state.rle_buffer, quant_table) {
+        Some(result) => result,
+        None => return false,
+    };
+
+    controller_state.rle_buffer.drain(..consumed);
+    idct(&mut block, &controller_state.scale_table);
+    controller_state.blocks.push(block);
+
+    true
+}
+
+pub(crate) fn macroblock_block_count(output_depth: OutputDepth) -> usize {
+    if output_depth.is_monochrome() {
+        1
+    } else {
+        COLOR_BLOCK_ORDER.len()
+    }
+}
+
+fn signed_10bit(value: u16) -> i32 {
+    (((value & 0x3FF) as i32) << 22) >> 22
+}
+
+/// Run-length decodes and dequantizes a block; returns the block and the number of halfwords consumed.
+/// Returns None if the buffer ends before the block does.
+fn decode_rle_block(buffer: &VecDeque<u16>, quant_table: &[u8]) -> Option<(Vec<i16>, usize)> {
+    let mut data = buffer.iter().copied();
+    let mut consumed = 0;
+    let mut block = vec![0; BLOCK_SIZE];
+
+    // Skip any padding before the DC coefficient.
+    let mut value = loop {
+        let value = data.next()?;
+        consumed += 1;
+        if value != RLE_END_OF_BLOCK {
+            break value;
+        }
+    };
+
+    let scale = ((value >> 10) & 0x3F) as i32;
+    let mut index = 0;
+    let mut coefficient = signed_10bit(value) * quant_table[0] as i32;
+
+    loop {
+        if scale == 0 {
+            coefficient = signed_10bit(value) * 2;
+        }
+
+        coefficient = coefficient.max(-0x400).min(0x3FF);
+
+        if scale > 0 {
+            block[ZAGZIG[index]] = coefficient as i16;
+        } else {
+            block[index] = coefficient as i16;
+        }
+
+        value = data.next()?;
+        consumed += 1;
+
+        // The end of block code has a run length of 63, which always terminates the block.
+        index += ((value >> 10) & 0x3F) as usize + 1;
+        if index >= BLOCK_SIZE {
+            break;
+        }
+
+        coefficient = ((signed_10bit(value) * quant_table[index] as i32 * scale) + 4) / 8;
+    }
+
+    Some((block, consumed))
+}
+
+/// Performs the inverse DCT in-place, using the scale table (a 2D matrix multiplication, done in 2 passes).
+/// The result is clamped to signed 8-bit values.
+fn idct(block: &mut [i16], scale_table: &[i16]) {
+    let mut temp = [0i64; BLOCK_SIZE];
+
+    for x in 0..8 {
+        for y in 0..8 {
+            let mut sum = 0;
+            for u in 0..8 {
+                sum += block[(u * 8) + x] as i64 * scale_table[(u * 8) + y] as i64;
+            }
+            temp[x + (y * 8)] = sum;
+        }
+    }
+
+    for x in 0..8 {
+        for y in 0..8 {
+            let mut sum = 0;
+            for u in 0..8 {
+                sum += temp[u + (y * 8)] * scale_table[(u * 8) + x] as i64;
+            }
+
+            // Round and sign extend from 9 bits.
+            let value = ((sum >> 32) + ((sum >> 31) & 1)) as i32;
+            let value = (value << 23) >> 23;
+            block[x + (y * 8)] = value.max(-128).min(127) as i16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The scale table uploaded by the BIOS / PsyQ libraries.
+    const SCALE_TABLE: [u16; BLOCK_SIZE] = [
+        0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x7D8A, 0x6A6D, 0x471C, 0x18F8, 0xE707, 0xB8E3, 0x9592, 0x8275, 0x7641, 0x30FB, 0xCF04, 0x89BE, 0x89BE,
+        0xCF04, 0x30FB, 0x7641, 0x6A6D, 0xE707, 0x8275, 0xB8E3, 0x471C, 0x7D8A, 0x18F8, 0x9592, 0x5A82, 0xA57D, 0xA57D, 0x5A82, 0x5A82, 0xA57D, 0xA57D, 0x5A82, 0x471C, 0x8275,
+        0x18F8, 0x6A6D, 0x9592, 0xE707, 0x7D8A, 0xB8E3, 0x30FB, 0x89BE, 0x7641, 0xCF04, 0xCF04, 0x7641, 0x89BE, 0x30FB, 0x18F8, 0xB8E3, 0x6A6D, 0x8275, 0x7D8A, 0x9592, 0x471C,
+        0xE707,
+    ];
+
+    fn scale_table() -> Vec<i16> {
+        SCALE_TABLE.iter().map(|&value| value as i16).collect()
+    }
+
+    #[test]
+    fn test_rle_decode() {
+        let mut quant_table = [16; BLOCK_SIZE];
+        quant_table[0] = 2;
+
+        // Padding, DC 100 (scale 2), run 0 AC 5, run 2 AC -3, end of block.
+        let buffer: VecDeque<u16> = vec![RLE_END_OF_BLOCK, (2 << 10) | 100, 5, (2 << 10) | (-3i16 as u16 & 0x3FF), RLE_END_OF_BLOCK, 0x1234].into();
+        let (block, consumed) = decode_rle_block(&buffer, &quant_table).unwrap();
+        assert_eq!(consumed, 5);
+
+        let mut expected = vec![0; BLOCK_SIZE];
+        // DC: 100 * 2.
+        expected[0] = 200;
+        // Zigzag index 1: (5 * 16 * 2 + 4) / 8.
+        expected[ZAGZIG[1]] = 20;
+        // Zigzag index 4: (-3 * 16 * 2 + 4) / 8.
+        expected[ZAGZIG[4]] = -11;
+        assert_eq!(block, expected);
+
+        // Incomplete blocks are left in the buffer.
+        let buffer: VecDeque<u16> = vec![(2 << 10) | 100, 5].into();
+        assert!(decode_rle_block(&buffer, &quant_table).is_none());
+    }
+
+    #[test]
+    fn test_idct_dc_only() {
+        // A DC only block decodes to a flat block of DC / 8.
+        for &(dc, expected) in [(800, 100), (-400, -50), (0x3FF, 127), (-0x400, -128)].iter() {
+            let mut block = vec![0; BLOCK_SIZE];
+            block[0] = dc;
+            idct(&mut block, &scale_table());
+            assert_eq!(block, vec![expected; BLOCK_SIZE], "DC {}", dc);
+        }
+    }
+}
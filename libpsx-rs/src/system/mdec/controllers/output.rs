@@ -0,0 +1,116 @@
+use crate::system::{
+    mdec::{
+        constants::*,
+        types::*,
+    },
+    types::State,
+};
+
+/// Positions of the Y1-Y4 blocks within a 16 x 16 colored macroblock.
+const Y_BLOCK_OFFSETS: [(usize, usize); 4] = [(0, 0), (8, 0), (0, 8), (8, 8)];
+
+/// Number of output words produced per macroblock.
+pub(crate) fn macroblock_word_count(output_depth: OutputDepth) -> usize {
+    match output_depth {
+        OutputDepth::Bit4 => BLOCK_SIZE / 8,
+        OutputDepth::Bit8 => BLOCK_SIZE / 4,
+        OutputDepth::Bit24 => (16 * 16 * 3) / 4,
+        OutputDepth::Bit15 => (16 * 16) / 2,
+    }
+}
+
+/// Converts the decoded blocks of the current macroblock into the output format, and pushes it to the output FIFO.
+pub(crate) fn handle_macroblock_output(state: &State, controller_state: &ControllerState) {
+    let words = match controller_state.output_depth {
+        OutputDepth::Bit4 => output_mono_4bit(controller_state),
+        OutputDepth::Bit8 => output_mono_8bit(controller_state),
+        OutputDepth::Bit24 => output_color_24bit(controller_state),
+        OutputDepth::Bit15 => output_color_15bit(controller_state),
+    };
+
+    for word in words {
+        state.mdec.data_out.write_one(word).expect("Output FIFO space should have been checked");
+    }
+}
+
+fn to_output_u8(value: i32, signed: bool) -> u8 {
+    let value = value.max(-128).min(127) as i8 as u8;
+
+    if signed {
+        value
+    } else {
+        value ^ 0x80
+    }
+}
+
+fn pack_u8(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks(4).map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])).collect()
+}
+
+fn output_mono(controller_state: &ControllerState) -> Vec<u8> {
+    controller_state.blocks[0].iter().map(|value| to_output_u8(*value as i32, controller_state.output_signed)).collect()
+}
+
+fn output_mono_4bit(controller_state: &ControllerState) -> Vec<u32> {
+    let bytes: Vec<u8> = output_mono(controller_state).chunks(2).map(|pair| (pair[0] >> 4) | (pair[1] & 0xF0)).collect();
+    pack_u8(&bytes)
+}
+
+fn output_mono_8bit(controller_state: &ControllerState) -> Vec<u32> {
+    pack_u8(&output_mono(controller_state))
+}
+
+/// Converts the macroblock to RGB (YUV 4:2:0 to RGB), returning 16 x 16 pixels in row-major order.
+fn yuv_to_rgb(controller_state: &ControllerState) -> Vec<[u8; 3]> {
+    let cr_block = &controller_state.blocks[0];
+    let cb_block = &controller_state.blocks[1];
+    let mut pixels = vec![[0; 3]; 16 * 16];
+
+    for (y_index, &(x_offset, y_offset)) in Y_BLOCK_OFFSETS.iter().enumerate() {
+        let y_block = &controller_state.blocks[2 + y_index];
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let chroma_index = ((x + x_offset) / 2) + (((y + y_offset) / 2) * 8);
+                let cr = cr_block[chroma_index] as i32;
+                let cb = cb_block[chroma_index] as i32;
+
+                // Fixed point (14 bits) versions of the 1.402, -0.3437, -0.7143 and 1.772 coefficients.
+                let r = (22970 * cr) >> 14;
+                let g = ((-5638 * cb) >> 14) + ((-11700 * cr) >> 14);
+                let b = (29032 * cb) >> 14;
+
+                let luma = y_block[x + (y * 8)] as i32;
+                let signed = controller_state.output_signed;
+                pixels[(x + x_offset) + ((y + y_offset) * 16)] = [to_output_u8(luma + r, signed), to_output_u8(luma + g, signed), to_output_u8(luma + b, signed)];
+            }
+        }
+    }
+
+    pixels
+}
+
+fn output_color_24bit(controller_state: &ControllerState) -> Vec<u32> {
+    let bytes: Vec<u8> = yuv_to_rgb(controller_state).iter().flat_map(|pixel| pixel.to_vec()).collect();
+    pack_u8(&bytes)
+}
+
+fn output_color_15bit(controller_state: &ControllerState) -> Vec<u32> {
+    let bit15 = if controller_state.output_bit15 {
+        0x8000
+    } else {
+        0
+    };
+
+    let halfwords: Vec<u32> = yuv_to_rgb(controller_state)
+        .iter()
+        .map(|pixel| {
+            let r = (pixel[0] >> 3) as u32;
+            let g = (pixel[1] >> 3) as u32;
+            let b = (pixel[2] >> 3) as u32;
+            bit15 | r | (g << 5) | (b << 10)
+        })
+        .collect();
+
+    halfwords.chunks(2).map(|pair| pair[0] | (pair[1] << 16)).collect()
+}
@@ -0,0 +1,74 @@
+use crate::{
+    system::{
+        mdec::{
+            constants::*,
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    types::memory::LatchKind,
+    utilities::bool_to_flag,
+};
+
+pub(crate) fn handle_control(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    state.mdec.control.acknowledge(|value, latch_kind| {
+        match latch_kind {
+            LatchKind::Read => Ok(value),
+            LatchKind::Write => {
+                if CONTROL_RESET.extract_from(value) > 0 {
+                    handle_reset(state, controller_state);
+                }
+
+                controller_state.data_in_enabled = CONTROL_DATA_IN_ENABLE.extract_from(value) > 0;
+                controller_state.data_out_enabled = CONTROL_DATA_OUT_ENABLE.extract_from(value) > 0;
+
+                Ok(value)
+            },
+        }
+    })
+}
+
+pub(crate) fn handle_status(state: &State, controller_state: &mut ControllerState) {
+    let status = &state.mdec.status;
+
+    // Reports the number of remaining parameter words minus 1 (0xFFFF when none remain).
+    let parameter_words = (controller_state.parameter_words as u32).wrapping_sub(1);
+    status.write_bitfield(STATUS_PARAMETER_WORDS, parameter_words);
+
+    if controller_state.command == Command::DecodeMacroblock {
+        let current_block = if controller_state.output_depth.is_monochrome() {
+            MONOCHROME_BLOCK_INDEX
+        } else {
+            COLOR_BLOCK_ORDER[controller_state.blocks.len() % COLOR_BLOCK_ORDER.len()]
+        };
+        status.write_bitfield(STATUS_CURRENT_BLOCK, current_block as u32);
+    }
+
+    let busy = (controller_state.command != Command::None) || (!state.mdec.data_in.is_empty());
+    status.write_bitfield(STATUS_COMMAND_BUSY, bool_to_flag(busy));
+
+    let data_in_request = controller_state.data_in_enabled && (!state.mdec.data_in.is_full());
+    status.write_bitfield(STATUS_DATA_IN_REQUEST, bool_to_flag(data_in_request));
+
+    let data_out_request = controller_state.data_out_enabled && (!state.mdec.data_out.is_empty());
+    status.write_bitfield(STATUS_DATA_OUT_REQUEST, bool_to_flag(data_out_request));
+}
+
+/// Aborts the current command and clears the FIFOs; the quant/scale tables are kept.
+fn handle_reset(state: &State, controller_state: &mut ControllerState) {
+    state.mdec.data_in.clear();
+    state.mdec.data_out.clear();
+
+    controller_state.command = Command::None;
+    controller_state.parameter_words = 0;
+    controller_state.parameters.clear();
+    controller_state.rle_buffer.clear();
+    controller_state.blocks.clear();
+
+    state.mdec.status.write_u32(0);
+    state.mdec.status.write_bitfield(STATUS_CURRENT_BLOCK, MONOCHROME_BLOCK_INDEX as u32);
+    state.mdec.status.write_bitfield(STATUS_PARAMETER_WORDS, 0xFFFF);
+}
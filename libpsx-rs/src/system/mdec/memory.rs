@@ -0,0 +1,31 @@
+use crate::{
+    system::{
+        bus::types::*,
+        mdec::constants::*,
+        types::State,
+    },
+    utilities::bool_to_flag,
+};
+
+pub(crate) fn data_read_u32(state: &State, offset: u32) -> ReadResult<u32> {
+    assert_eq!(offset, 0);
+    Ok(state.mdec.data_out.read_one().unwrap_or(0))
+}
+
+pub(crate) fn data_write_u32(state: &State, offset: u32, value: u32) -> WriteResult {
+    assert_eq!(offset, 0);
+    state.mdec.data_in.write_one(value).map_err(|_| WriteErrorKind::Full)
+}
+
+pub(crate) fn control_read_u32(state: &State, offset: u32) -> ReadResult<u32> {
+    assert_eq!(offset, 0);
+    let mut value = state.mdec.status.read_u32();
+    value = STATUS_DATA_IN_FULL.insert_into(value, bool_to_flag(state.mdec.data_in.is_full()));
+    value = STATUS_DATA_OUT_EMPTY.insert_into(value, bool_to_flag(state.mdec.data_out.is_empty()));
+    Ok(value)
+}
+
+pub(crate) fn control_write_u32(state: &State, offset: u32, value: u32) -> WriteResult {
+    assert_eq!(offset, 0);
+    state.mdec.control.write_u32(value).map_err(|_| WriteErrorKind::NotReady)
+}
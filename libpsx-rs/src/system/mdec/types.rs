@@ -0,0 +1,122 @@
+use crate::{
+    system::mdec::constants::*,
+    types::{
+        exclusive_state::ExclusiveState,
+        fifo::Fifo,
+        memory::*,
+    },
+};
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::VecDeque;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) enum OutputDepth {
+    Bit4,
+    Bit8,
+    Bit24,
+    Bit15,
+}
+
+impl OutputDepth {
+    pub(crate) fn from_bits(value: u32) -> OutputDepth {
+        match value {
+            0 => OutputDepth::Bit4,
+            1 => OutputDepth::Bit8,
+            2 => OutputDepth::Bit24,
+            3 => OutputDepth::Bit15,
+            _ => unreachable!("Invalid MDEC output depth"),
+        }
+    }
+
+    pub(crate) fn is_monochrome(self) -> bool {
+        match self {
+            OutputDepth::Bit4 | OutputDepth::Bit8 => true,
+            OutputDepth::Bit24 | OutputDepth::Bit15 => false,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) enum Command {
+    None,
+    DecodeMacroblock,
+    /// Contains whether the color table is also being set (in addition to the luminance table).
+    SetQuantTable(bool),
+    SetScaleTable,
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct ControllerState {
+    pub(crate) clock: f32,
+    pub(crate) data_in_enabled: bool,
+    pub(crate) data_out_enabled: bool,
+    pub(crate) command: Command,
+    /// Number of parameter words still to be received for the current command.
+    pub(crate) parameter_words: usize,
+    /// Parameters of the current command, for the table commands.
+    pub(crate) parameters: Vec<u32>,
+    pub(crate) output_depth: OutputDepth,
+    pub(crate) output_signed: bool,
+    pub(crate) output_bit15: bool,
+    /// Undecoded RLE data of the current macroblock.
+    pub(crate) rle_buffer: VecDeque<u16>,
+    /// Decoded (IDCT'd) blocks of the current macroblock, in stream order.
+    pub(crate) blocks: Vec<Vec<i16>>,
+    pub(crate) luminance_quant_table: Vec<u8>,
+    pub(crate) color_quant_table: Vec<u8>,
+    pub(crate) scale_table: Vec<i16>,
+}
+
+impl ControllerState {
+    pub(crate) fn new() -> ControllerState {
+        ControllerState {
+            clock: 0.0,
+            data_in_enabled: false,
+            data_out_enabled: false,
+            command: Command::None,
+            parameter_words: 0,
+            parameters: Vec::new(),
+            output_depth: OutputDepth::Bit4,
+            output_signed: false,
+            output_bit15: false,
+            rle_buffer: VecDeque::new(),
+            blocks: Vec::new(),
+            luminance_quant_table: vec![0; BLOCK_SIZE],
+            color_quant_table: vec![0; BLOCK_SIZE],
+            scale_table: vec![0; BLOCK_SIZE],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct State {
+    pub(crate) data_in: Fifo<u32>,
+    pub(crate) data_out: Fifo<u32>,
+    pub(crate) status: B32LevelRegister,
+    pub(crate) control: B32EdgeRegister,
+    pub(crate) controller_state: ExclusiveState<ControllerState>,
+}
+
+impl State {
+    pub(crate) fn new() -> State {
+        let status = B32LevelRegister::new();
+        status.write_bitfield(STATUS_CURRENT_BLOCK, MONOCHROME_BLOCK_INDEX as u32);
+        status.write_bitfield(STATUS_PARAMETER_WORDS, 0xFFFF);
+
+        State {
+            data_in: Fifo::new(DATA_IN_FIFO_SIZE),
+            data_out: Fifo::new(DATA_OUT_FIFO_SIZE),
+            status,
+            control: B32EdgeRegister::new(),
+            controller_state: ExclusiveState::new(ControllerState::new()),
+        }
+    }
+}
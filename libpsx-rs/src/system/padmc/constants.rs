@@ -6,12 +6,14 @@ pub(crate) const CLOCK_SPEED_PERIOD: f32 = 1.0 / CLOCK_SPEED;
 pub(crate) const STAT_TXRDY_1: Bitfield = Bitfield::new(0, 1);
 pub(crate) const STAT_RXFIFO_READY: Bitfield = Bitfield::new(1, 1);
 pub(crate) const STAT_TXRDY_2: Bitfield = Bitfield::new(2, 1);
-pub(crate) const _STAT_RXERR_PARITY: Bitfield = Bitfield::new(3, 1);
-pub(crate) const _STAT_ACK_LEVEL: Bitfield = Bitfield::new(7, 1);
-pub(crate) const _STAT_IRQ: Bitfield = Bitfield::new(9, 1);
-pub(crate) const _STAT_TIMER: Bitfield = Bitfield::new(11, 21);
+pub(crate) const STAT_RXERR_PARITY: Bitfield = Bitfield::new(3, 1);
+pub(crate) const STAT_RXFIFO_OVERRUN: Bitfield = Bitfield::new(4, 1);
+pub(crate) const STAT_RXERR_STOP: Bitfield = Bitfield::new(5, 1);
+pub(crate) const STAT_ACK_LEVEL: Bitfield = Bitfield::new(7, 1);
+pub(crate) const STAT_IRQ: Bitfield = Bitfield::new(9, 1);
+pub(crate) const STAT_TIMER: Bitfield = Bitfield::new(11, 21);
 
-pub(crate) const _MODE_RATE_RELOADF: Bitfield = Bitfield::new(0, 2);
+pub(crate) const MODE_RATE_RELOADF: Bitfield = Bitfield::new(0, 2);
 pub(crate) const _MODE_CHAR_LENGTH: Bitfield = Bitfield::new(2, 2);
 pub(crate) const _MODE_PARITY_ENABLE: Bitfield = Bitfield::new(4, 1);
 pub(crate) const _MODE_PARITY_TYPE: Bitfield = Bitfield::new(5, 1);
@@ -22,8 +24,90 @@ pub(crate) const CTRL_JOYN_OUTPUT: Bitfield = Bitfield::new(1, 1);
 pub(crate) const _CTRL_RXEN: Bitfield = Bitfield::new(2, 1);
 pub(crate) const CTRL_ACK: Bitfield = Bitfield::new(4, 1);
 pub(crate) const CTRL_RESET: Bitfield = Bitfield::new(6, 1);
-pub(crate) const _CTRL_RXINT_MODE: Bitfield = Bitfield::new(8, 2);
-pub(crate) const _CTRL_TXINT_ENABLE: Bitfield = Bitfield::new(10, 1);
-pub(crate) const _CTRL_RXINT_ENABLE: Bitfield = Bitfield::new(11, 1);
+pub(crate) const CTRL_RXINT_MODE: Bitfield = Bitfield::new(8, 2);
+pub(crate) const CTRL_TXINT_ENABLE: Bitfield = Bitfield::new(10, 1);
+pub(crate) const CTRL_RXINT_ENABLE: Bitfield = Bitfield::new(11, 1);
 pub(crate) const CTRL_ACKINT_ENABLE: Bitfield = Bitfield::new(12, 1);
 pub(crate) const CTRL_JOY_SLOT: Bitfield = Bitfield::new(13, 1);
+
+/// Number of bits shifted per transfer (8 data bits; no start/stop bits on the joypad port).
+pub(crate) const TRANSFER_BITS: usize = 8;
+/// Baud rate reload factors, indexed by MODE_RATE_RELOADF.
+pub(crate) const BAUD_RELOAD_FACTORS: [usize; 4] = [1, 1, 16, 64];
+/// Number of bytes in the RX FIFO needed to raise an interrupt, indexed by CTRL_RXINT_MODE.
+pub(crate) const RXINT_MODE_COUNTS: [usize; 4] = [1, 2, 4, 8];
+pub(crate) const RX_FIFO_SIZE: usize = 8;
+/// Number of cycles the ACK line is held low for by a device.
+pub(crate) const ACK_LOW_CYCLES: usize = 100;
+
+pub(crate) const ADDRESS_CONTROLLER: u8 = 0x01;
+pub(crate) const ADDRESS_MEMORY_CARD: u8 = 0x81;
+
+pub(crate) const JOYPAD_ACK_DELAY_CYCLES: usize = 338;
+pub(crate) const JOYPAD_COMMAND_READ: u8 = 0x42;
+pub(crate) const JOYPAD_COMMAND_CONFIG: u8 = 0x43;
+pub(crate) const JOYPAD_COMMAND_SET_MODE: u8 = 0x44;
+pub(crate) const JOYPAD_COMMAND_GET_STATUS: u8 = 0x45;
+pub(crate) const JOYPAD_COMMAND_GET_ACTUATOR_INFO: u8 = 0x46;
+pub(crate) const JOYPAD_COMMAND_GET_ACTUATOR_COMBINATIONS: u8 = 0x47;
+pub(crate) const JOYPAD_COMMAND_GET_MODE_INFO: u8 = 0x4C;
+pub(crate) const JOYPAD_COMMAND_SET_MOTOR_MAPPING: u8 = 0x4D;
+pub(crate) const JOYPAD_ID_DIGITAL: u8 = 0x41;
+pub(crate) const JOYPAD_ID_ANALOG: u8 = 0x73;
+pub(crate) const JOYPAD_ID_CONFIG: u8 = 0xF3;
+pub(crate) const JOYPAD_ID_HIGH: u8 = 0x5A;
+pub(crate) const JOYPAD_AXIS_CENTER: u8 = 0x80;
+pub(crate) const JOYPAD_MOTOR_SMALL: u8 = 0x00;
+pub(crate) const JOYPAD_MOTOR_LARGE: u8 = 0x01;
+pub(crate) const JOYPAD_MOTOR_UNMAPPED: u8 = 0xFF;
+
+pub(crate) const BUTTON_SELECT: Bitfield = Bitfield::new(0, 1);
+pub(crate) const BUTTON_L3: Bitfield = Bitfield::new(1, 1);
+pub(crate) const BUTTON_R3: Bitfield = Bitfield::new(2, 1);
+pub(crate) const BUTTON_START: Bitfield = Bitfield::new(3, 1);
+pub(crate) const BUTTON_UP: Bitfield = Bitfield::new(4, 1);
+pub(crate) const BUTTON_RIGHT: Bitfield = Bitfield::new(5, 1);
+pub(crate) const BUTTON_DOWN: Bitfield = Bitfield::new(6, 1);
+pub(crate) const BUTTON_LEFT: Bitfield = Bitfield::new(7, 1);
+pub(crate) const BUTTON_L2: Bitfield = Bitfield::new(8, 1);
+pub(crate) const BUTTON_R2: Bitfield = Bitfield::new(9, 1);
+pub(crate) const BUTTON_L1: Bitfield = Bitfield::new(10, 1);
+pub(crate) const BUTTON_R1: Bitfield = Bitfield::new(11, 1);
+pub(crate) const BUTTON_TRIANGLE: Bitfield = Bitfield::new(12, 1);
+pub(crate) const BUTTON_CIRCLE: Bitfield = Bitfield::new(13, 1);
+pub(crate) const BUTTON_CROSS: Bitfield = Bitfield::new(14, 1);
+pub(crate) const BUTTON_SQUARE: Bitfield = Bitfield::new(15, 1);
+
+pub(crate) const MEMORY_CARD_ACK_DELAY_CYCLES: usize = 170;
+pub(crate) const MEMORY_CARD_COMMAND_READ: u8 = 0x52;
+pub(crate) const MEMORY_CARD_COMMAND_GET_ID: u8 = 0x53;
+pub(crate) const MEMORY_CARD_COMMAND_WRITE: u8 = 0x57;
+pub(crate) const MEMORY_CARD_SIZE: usize = 0x2_0000;
+pub(crate) const MEMORY_CARD_SECTOR_SIZE: usize = 0x80;
+pub(crate) const MEMORY_CARD_SECTOR_COUNT: usize = MEMORY_CARD_SIZE / MEMORY_CARD_SECTOR_SIZE;
+pub(crate) const MEMORY_CARD_FLAG_NEW: Bitfield = Bitfield::new(3, 1);
+pub(crate) const MEMORY_CARD_END_GOOD: u8 = 0x47;
+pub(crate) const MEMORY_CARD_END_BAD_CHECKSUM: u8 = 0x4E;
+pub(crate) const MEMORY_CARD_END_BAD_SECTOR: u8 = 0xFF;
+/// Emulated time (seconds) a modified card has to go without writes before it is flushed to disk, so a save spanning
+/// many sectors is only written out once.
+pub(crate) const MEMORY_CARD_FLUSH_DELAY: f32 = 0.25;
+pub(crate) const MEMORY_CARD_ID_RESPONSE: [u8; 8] = [0x5A, 0x5D, 0x5C, 0x5D, 0x04, 0x00, 0x00, 0x80];
+
+pub(crate) const MULTITAP_ID: u8 = 0x80;
+pub(crate) const MULTITAP_SLOT_TRANSFER_LENGTH: usize = 8;
+
+pub(crate) const MOUSE_ID: u8 = 0x12;
+pub(crate) const MOUSE_BUTTON_RIGHT: Bitfield = Bitfield::new(10, 1);
+pub(crate) const MOUSE_BUTTON_LEFT: Bitfield = Bitfield::new(11, 1);
+
+pub(crate) const GUNCON_ID: u8 = 0x63;
+pub(crate) const GUNCON_BUTTON_A: Bitfield = Bitfield::new(3, 1);
+pub(crate) const GUNCON_BUTTON_TRIGGER: Bitfield = Bitfield::new(13, 1);
+pub(crate) const GUNCON_BUTTON_B: Bitfield = Bitfield::new(14, 1);
+/// The GunCon measures the X position in 8 MHz clocks since horizontal sync, and the Y position in scanlines since
+/// vertical sync.
+pub(crate) const GUNCON_CLOCK_SPEED: f32 = 8.0 * 1e6; // 8 MHz
+/// Position reported when no light was detected (pointing off-screen).
+pub(crate) const GUNCON_OFFSCREEN_X: u16 = 0x0001;
+pub(crate) const GUNCON_OFFSCREEN_Y: u16 = 0x000A;
@@ -1,9 +1,22 @@
+pub(crate) mod baud;
+pub(crate) mod device;
+pub(crate) mod interrupt;
+pub(crate) mod joypad;
+pub(crate) mod memory_card;
+pub(crate) mod multitap;
+pub(crate) mod pointer;
 pub(crate) mod register;
+pub(crate) mod transfer;
 
 use crate::system::{
     padmc::{
         constants::*,
-        controllers::register::*,
+        controllers::{
+            baud::*,
+            interrupt::*,
+            register::*,
+            transfer::*,
+        },
         types::*,
     },
     types::{
@@ -34,12 +47,10 @@ fn run_time(state: &State, duration: f32) -> ControllerResult<()> {
 
 pub(crate) fn tick(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
     handle_ctrl(state, controller_state)?;
-
-    if controller_state.tx_enabled {
-        if let Ok(_) = state.padmc.tx_fifo.read_one() {
-            state.padmc.rx_fifo.write_one(0xFF).map_err(|_| "Error pushing to RX FIFO".to_owned())?;
-        }
-    }
+    handle_baud_timer(state, controller_state);
+    handle_transfer(state, controller_state)?;
+    handle_ack(state, controller_state);
+    handle_tx_rx_interrupts(state, controller_state);
 
     Ok(())
 }
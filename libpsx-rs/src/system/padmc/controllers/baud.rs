@@ -0,0 +1,29 @@
+use crate::system::{
+    padmc::{
+        constants::*,
+        types::*,
+    },
+    types::State,
+};
+
+/// Returns the number of cycles per bit, as set by the baud rate reload value and the mode reload factor.
+pub(crate) fn calculate_bit_cycles(state: &State) -> usize {
+    let reload_value = state.padmc.baud_reload.read_u16() as usize;
+    let factor = BAUD_RELOAD_FACTORS[MODE_RATE_RELOADF.extract_from(state.padmc.mode.read_u16()) as usize];
+    (reload_value * factor).max(1)
+}
+
+/// Returns the number of cycles needed to shift a byte in/out.
+pub(crate) fn calculate_transfer_cycles(state: &State) -> usize {
+    calculate_bit_cycles(state) * TRANSFER_BITS
+}
+
+pub(crate) fn handle_baud_timer(state: &State, controller_state: &mut ControllerState) {
+    if controller_state.baud_timer == 0 {
+        controller_state.baud_timer = (calculate_bit_cycles(state) / 2).max(1);
+    } else {
+        controller_state.baud_timer -= 1;
+    }
+
+    state.padmc.stat.write_bitfield(STAT_TIMER, controller_state.baud_timer as u32);
+}
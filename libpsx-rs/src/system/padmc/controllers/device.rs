@@ -0,0 +1,130 @@
+use crate::system::{
+    padmc::{
+        constants::*,
+        controllers::{
+            joypad::*,
+            memory_card::*,
+            multitap::*,
+            pointer::*,
+        },
+        types::*,
+    },
+    types::State,
+};
+
+/// Passes a byte to the device attached to the currently selected port.
+/// Returns the response byte, and the number of cycles until the device pulls the ACK line low (if it acknowledges).
+pub(crate) fn handle_device_transfer(state: &State, controller_state: &mut ControllerState, value: u8) -> (u8, Option<usize>) {
+    if !controller_state.joy_select_enabled {
+        return (0xFF, None);
+    }
+
+    let index = controller_state.sequence_index;
+    controller_state.sequence_index += 1;
+
+    let port_index = if controller_state.use_joy2 {
+        1
+    } else {
+        0
+    };
+
+    let port_state = &mut controller_state.port_states[port_index];
+
+    if index == 0 {
+        controller_state.active_device = select_device(port_state, value);
+    }
+
+    match controller_state.active_device {
+        ActiveDevice::Controller(slot_index) => {
+            if port_state.multitap && (slot_index == 0) && (index == 2) {
+                port_state.multitap_all_slots = value == 0x01;
+            }
+
+            let (response, ack) = handle_controller_transfer(state, port_index, &mut port_state.controller_states[slot_index], value);
+            (response, ack_delay(ack, JOYPAD_ACK_DELAY_CYCLES))
+        },
+        ActiveDevice::MemoryCard(slot_index) => {
+            let (response, ack) = match port_state.memory_card_states[slot_index] {
+                Some(ref mut memory_card_state) => handle_memory_card_transfer(memory_card_state, value),
+                None => (0xFF, false),
+            };
+            (response, ack_delay(ack, MEMORY_CARD_ACK_DELAY_CYCLES))
+        },
+        ActiveDevice::Multitap => {
+            let (response, ack) = handle_multitap_transfer(state, port_index, port_state, index, value);
+            (response, ack_delay(ack, JOYPAD_ACK_DELAY_CYCLES))
+        },
+        ActiveDevice::None | ActiveDevice::Invalid => (0xFF, None),
+    }
+}
+
+/// Passes a byte to a controller slot device, returning the response byte and if the device acknowledged it.
+pub(crate) fn handle_controller_transfer(state: &State, port_index: usize, controller_device_state: &mut ControllerDeviceState, value: u8) -> (u8, bool) {
+    match controller_device_state {
+        ControllerDeviceState::None => (0xFF, false),
+        ControllerDeviceState::Joypad(ref mut joypad_state) => handle_joypad_transfer(joypad_state, value),
+        ControllerDeviceState::Mouse(ref mut mouse_state) => handle_mouse_transfer(mouse_state, value),
+        ControllerDeviceState::GunCon(ref mut guncon_state) => {
+            let latch = state.padmc.lightgun_state.lock().latches[port_index];
+            handle_guncon_transfer(guncon_state, latch, value)
+        },
+    }
+}
+
+/// Resets the communication sequence of a controller slot device.
+pub(crate) fn reset_controller(controller_device_state: &mut ControllerDeviceState) {
+    match controller_device_state {
+        ControllerDeviceState::None => {},
+        ControllerDeviceState::Joypad(ref mut joypad_state) => joypad_state.sequence_index = 0,
+        ControllerDeviceState::Mouse(ref mut mouse_state) => mouse_state.sequence_index = 0,
+        ControllerDeviceState::GunCon(ref mut guncon_state) => guncon_state.sequence_index = 0,
+    }
+}
+
+/// Resets the communication sequence of all devices; happens when the port select line is deasserted.
+pub(crate) fn reset_devices(controller_state: &mut ControllerState) {
+    controller_state.active_device = ActiveDevice::None;
+    controller_state.sequence_index = 0;
+
+    for port_state in controller_state.port_states.iter_mut() {
+        port_state.multitap_slot_finished = false;
+
+        for controller_device_state in port_state.controller_states.iter_mut() {
+            reset_controller(controller_device_state);
+        }
+
+        for memory_card_state in port_state.memory_card_states.iter_mut() {
+            if let Some(memory_card_state) = memory_card_state {
+                memory_card_state.sequence_index = 0;
+            }
+        }
+    }
+}
+
+fn select_device(port_state: &PortState, address: u8) -> ActiveDevice {
+    let slot_count = port_state.slot_count();
+
+    if (address >= ADDRESS_CONTROLLER) && ((address - ADDRESS_CONTROLLER) < slot_count as u8) {
+        let slot_index = (address - ADDRESS_CONTROLLER) as usize;
+
+        if port_state.multitap && (slot_index == 0) && port_state.multitap_all_slots {
+            return ActiveDevice::Multitap;
+        }
+
+        return ActiveDevice::Controller(slot_index);
+    }
+
+    if (address >= ADDRESS_MEMORY_CARD) && ((address - ADDRESS_MEMORY_CARD) < slot_count as u8) {
+        return ActiveDevice::MemoryCard((address - ADDRESS_MEMORY_CARD) as usize);
+    }
+
+    ActiveDevice::Invalid
+}
+
+fn ack_delay(ack: bool, delay: usize) -> Option<usize> {
+    if ack {
+        Some(delay)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,31 @@
+use crate::system::{
+    intc::types::Line,
+    padmc::{
+        constants::*,
+        types::*,
+    },
+    types::State,
+};
+
+/// Raises the IRQ flag; the interrupt line is only asserted if the flag was not already set (it needs to be
+/// acknowledged through CTRL).
+pub(crate) fn handle_irq_raise(state: &State) {
+    if state.padmc.stat.read_bitfield(STAT_IRQ) == 0 {
+        state.padmc.stat.write_bitfield(STAT_IRQ, 1);
+        state.intc.stat.assert_line(Line::Padmc);
+    }
+}
+
+pub(crate) fn handle_tx_rx_interrupts(state: &State, controller_state: &mut ControllerState) {
+    let tx_ready = state.padmc.tx_fifo.is_empty();
+    if controller_state.tx_interrupt_enabled && tx_ready && !controller_state.tx_ready_old {
+        handle_irq_raise(state);
+    }
+    controller_state.tx_ready_old = tx_ready;
+
+    let rx_ready = state.padmc.rx_fifo.read_available() >= controller_state.rx_interrupt_count;
+    if controller_state.rx_interrupt_enabled && rx_ready && !controller_state.rx_ready_old {
+        handle_irq_raise(state);
+    }
+    controller_state.rx_ready_old = rx_ready;
+}
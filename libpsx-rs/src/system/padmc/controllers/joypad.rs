@@ -0,0 +1,157 @@
+use crate::system::padmc::{
+    constants::*,
+    types::*,
+};
+
+/// Handles a byte sent to a joypad (DualShock compatible), returning the response byte and if the joypad acknowledged
+/// it.
+pub(crate) fn handle_joypad_transfer(joypad_state: &mut JoypadState, value: u8) -> (u8, bool) {
+    let index = joypad_state.sequence_index;
+    joypad_state.sequence_index += 1;
+
+    match index {
+        0 => (0xFF, true),
+        1 => handle_command(joypad_state, value),
+        2 => (JOYPAD_ID_HIGH, true),
+        _ => handle_payload(joypad_state, index - 3, value),
+    }
+}
+
+fn handle_command(joypad_state: &mut JoypadState, value: u8) -> (u8, bool) {
+    let supported = if !joypad_state.dualshock {
+        value == JOYPAD_COMMAND_READ
+    } else if joypad_state.config_mode {
+        (0x40..=0x4F).contains(&value)
+    } else {
+        value == JOYPAD_COMMAND_READ || value == JOYPAD_COMMAND_CONFIG
+    };
+
+    if !supported {
+        log::debug!("Unsupported joypad command 0x{:X} (config mode = {})", value, joypad_state.config_mode);
+        return (0xFF, false);
+    }
+
+    let id = if joypad_state.config_mode {
+        JOYPAD_ID_CONFIG
+    } else if joypad_state.analog_mode {
+        JOYPAD_ID_ANALOG
+    } else {
+        JOYPAD_ID_DIGITAL
+    };
+
+    joypad_state.command = value;
+    joypad_state.pending_config_mode = joypad_state.config_mode;
+    joypad_state.response = [0; 6];
+    joypad_state.response_length = (id & 0xF) as usize * 2;
+
+    match value {
+        JOYPAD_COMMAND_READ => fill_poll_response(joypad_state),
+        JOYPAD_COMMAND_CONFIG if !joypad_state.config_mode => fill_poll_response(joypad_state),
+        JOYPAD_COMMAND_GET_STATUS => joypad_state.response = [0x01, 0x02, joypad_state.analog_mode as u8, 0x02, 0x01, 0x00],
+        JOYPAD_COMMAND_GET_ACTUATOR_COMBINATIONS => joypad_state.response = [0x00, 0x00, 0x02, 0x00, 0x01, 0x00],
+        JOYPAD_COMMAND_SET_MOTOR_MAPPING => joypad_state.response = joypad_state.motor_mapping,
+        _ => {},
+    }
+
+    (id, true)
+}
+
+fn fill_poll_response(joypad_state: &mut JoypadState) {
+    let mut buttons = joypad_state.buttons;
+
+    if !joypad_state.analog_mode && !joypad_state.config_mode {
+        // L3/R3 are not reported in digital mode.
+        buttons = BUTTON_L3.insert_into(buttons, 1);
+        buttons = BUTTON_R3.insert_into(buttons, 1);
+    }
+
+    joypad_state.response = [buttons as u8, (buttons >> 8) as u8, joypad_state.right_x, joypad_state.right_y, joypad_state.left_x, joypad_state.left_y];
+}
+
+fn handle_payload(joypad_state: &mut JoypadState, payload_index: usize, value: u8) -> (u8, bool) {
+    if payload_index >= joypad_state.response_length {
+        return (0xFF, false);
+    }
+
+    let response = joypad_state.response[payload_index];
+
+    match joypad_state.command {
+        JOYPAD_COMMAND_READ => handle_motor_value(joypad_state, payload_index, value),
+        JOYPAD_COMMAND_CONFIG => {
+            if payload_index == 0 {
+                joypad_state.pending_config_mode = value == 0x01;
+            }
+        },
+        JOYPAD_COMMAND_SET_MODE => {
+            match payload_index {
+                0 => {
+                    if value <= 0x01 {
+                        joypad_state.analog_mode = value == 0x01;
+                    }
+                },
+                1 => joypad_state.analog_mode_locked = value == 0x03,
+                _ => {},
+            }
+        },
+        JOYPAD_COMMAND_GET_ACTUATOR_INFO => {
+            if payload_index == 0 {
+                match value {
+                    0x00 => joypad_state.response = [0x00, 0x00, 0x01, 0x02, 0x00, 0x0A],
+                    0x01 => joypad_state.response = [0x00, 0x00, 0x01, 0x01, 0x01, 0x14],
+                    _ => {},
+                }
+            }
+        },
+        JOYPAD_COMMAND_GET_MODE_INFO => {
+            if payload_index == 0 {
+                match value {
+                    0x00 => joypad_state.response = [0x00, 0x00, 0x00, 0x04, 0x00, 0x00],
+                    0x01 => joypad_state.response = [0x00, 0x00, 0x00, 0x07, 0x00, 0x00],
+                    _ => {},
+                }
+            }
+        },
+        JOYPAD_COMMAND_SET_MOTOR_MAPPING => joypad_state.motor_mapping[payload_index] = value,
+        _ => {},
+    }
+
+    let last = (payload_index + 1) == joypad_state.response_length;
+
+    if last && (joypad_state.command == JOYPAD_COMMAND_CONFIG) {
+        if joypad_state.pending_config_mode && !joypad_state.config_mode {
+            // Motors are stopped when entering config mode.
+            set_motors(joypad_state, 0, 0);
+        }
+
+        joypad_state.config_mode = joypad_state.pending_config_mode;
+    }
+
+    (response, !last)
+}
+
+fn handle_motor_value(joypad_state: &mut JoypadState, payload_index: usize, value: u8) {
+    let mut small_motor = joypad_state.small_motor;
+    let mut large_motor = joypad_state.large_motor;
+
+    match joypad_state.motor_mapping[payload_index] {
+        JOYPAD_MOTOR_SMALL => {
+            small_motor = if (value & 1) > 0 {
+                0xFF
+            } else {
+                0
+            };
+        },
+        JOYPAD_MOTOR_LARGE => large_motor = value,
+        _ => return,
+    }
+
+    set_motors(joypad_state, small_motor, large_motor);
+}
+
+fn set_motors(joypad_state: &mut JoypadState, small_motor: u8, large_motor: u8) {
+    if (joypad_state.small_motor != small_motor) || (joypad_state.large_motor != large_motor) {
+        joypad_state.small_motor = small_motor;
+        joypad_state.large_motor = large_motor;
+        joypad_state.motors_changed = true;
+    }
+}
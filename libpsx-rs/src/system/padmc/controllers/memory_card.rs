@@ -0,0 +1,133 @@
+use crate::system::padmc::{
+    constants::*,
+    types::*,
+};
+
+/// Handles a byte sent to a memory card, returning the response byte and if the card acknowledged it.
+pub(crate) fn handle_memory_card_transfer(memory_card_state: &mut MemoryCardState, value: u8) -> (u8, bool) {
+    let index = memory_card_state.sequence_index;
+    memory_card_state.sequence_index += 1;
+
+    let response = match index {
+        0 => (0xFF, true),
+        1 => {
+            memory_card_state.command = value;
+            match value {
+                MEMORY_CARD_COMMAND_READ | MEMORY_CARD_COMMAND_WRITE | MEMORY_CARD_COMMAND_GET_ID => (memory_card_state.flag, true),
+                _ => {
+                    log::debug!("Unsupported memory card command 0x{:X}", value);
+                    (memory_card_state.flag, false)
+                },
+            }
+        },
+        _ => {
+            match memory_card_state.command {
+                MEMORY_CARD_COMMAND_READ => handle_read(memory_card_state, index, value),
+                MEMORY_CARD_COMMAND_WRITE => handle_write(memory_card_state, index, value),
+                MEMORY_CARD_COMMAND_GET_ID => handle_get_id(index),
+                _ => (0xFF, false),
+            }
+        },
+    };
+
+    memory_card_state.previous_value = value;
+    response
+}
+
+fn handle_read(memory_card_state: &mut MemoryCardState, index: usize, value: u8) -> (u8, bool) {
+    match index {
+        2 => (0x5A, true),
+        3 => (0x5D, true),
+        4 => {
+            memory_card_state.address = (value as usize) << 8;
+            (0x00, true)
+        },
+        5 => {
+            memory_card_state.address |= value as usize;
+            (memory_card_state.previous_value, true)
+        },
+        6 => (0x5C, true),
+        7 => (0x5D, true),
+        8 => {
+            if memory_card_state.address >= MEMORY_CARD_SECTOR_COUNT {
+                // Invalid sector; the card responds with 0xFFFF as the confirmed address and stops.
+                return (0xFF, true);
+            }
+
+            memory_card_state.checksum = (memory_card_state.address >> 8) as u8;
+            ((memory_card_state.address >> 8) as u8, true)
+        },
+        9 => {
+            if memory_card_state.address >= MEMORY_CARD_SECTOR_COUNT {
+                return (0xFF, false);
+            }
+
+            memory_card_state.checksum ^= memory_card_state.address as u8;
+            (memory_card_state.address as u8, true)
+        },
+        10..=137 => {
+            let offset = memory_card_state.address * MEMORY_CARD_SECTOR_SIZE + (index - 10);
+            let data = memory_card_state.data[offset];
+            memory_card_state.checksum ^= data;
+            (data, true)
+        },
+        138 => (memory_card_state.checksum, true),
+        139 => (MEMORY_CARD_END_GOOD, false),
+        _ => (0xFF, false),
+    }
+}
+
+fn handle_write(memory_card_state: &mut MemoryCardState, index: usize, value: u8) -> (u8, bool) {
+    match index {
+        2 => (0x5A, true),
+        3 => (0x5D, true),
+        4 => {
+            memory_card_state.address = (value as usize) << 8;
+            memory_card_state.checksum = value;
+            (0x00, true)
+        },
+        5 => {
+            memory_card_state.address |= value as usize;
+            memory_card_state.checksum ^= value;
+            (memory_card_state.previous_value, true)
+        },
+        6..=133 => {
+            memory_card_state.sector_buffer[index - 6] = value;
+            memory_card_state.checksum ^= value;
+            (memory_card_state.previous_value, true)
+        },
+        134 => {
+            memory_card_state.received_checksum = value;
+            (memory_card_state.previous_value, true)
+        },
+        135 => (0x5C, true),
+        136 => (0x5D, true),
+        137 => {
+            if memory_card_state.address >= MEMORY_CARD_SECTOR_COUNT {
+                return (MEMORY_CARD_END_BAD_SECTOR, false);
+            }
+
+            if memory_card_state.checksum != memory_card_state.received_checksum {
+                return (MEMORY_CARD_END_BAD_CHECKSUM, false);
+            }
+
+            let offset = memory_card_state.address * MEMORY_CARD_SECTOR_SIZE;
+            memory_card_state.data[offset..offset + MEMORY_CARD_SECTOR_SIZE].copy_from_slice(&memory_card_state.sector_buffer);
+            memory_card_state.flag = MEMORY_CARD_FLAG_NEW.insert_into(memory_card_state.flag, 0);
+            memory_card_state.dirty = true;
+            memory_card_state.idle_time = 0.0;
+            (MEMORY_CARD_END_GOOD, false)
+        },
+        _ => (0xFF, false),
+    }
+}
+
+fn handle_get_id(index: usize) -> (u8, bool) {
+    let id_index = index - 2;
+
+    if id_index < MEMORY_CARD_ID_RESPONSE.len() {
+        (MEMORY_CARD_ID_RESPONSE[id_index], id_index < (MEMORY_CARD_ID_RESPONSE.len() - 1))
+    } else {
+        (0xFF, false)
+    }
+}
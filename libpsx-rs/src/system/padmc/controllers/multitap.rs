@@ -0,0 +1,57 @@
+use crate::system::{
+    padmc::{
+        constants::*,
+        controllers::device::{
+            handle_controller_transfer,
+            reset_controller,
+        },
+        types::*,
+    },
+    types::State,
+};
+
+/// Handles a byte of a multitap transfer, where the data for all four controller slots is returned in one sequence.
+/// Each slot occupies 8 bytes; the bytes sent by the host are forwarded to the slot device as its command sequence,
+/// and the response is padded with 0xFF once the slot device stops acknowledging (or if there is no device).
+pub(crate) fn handle_multitap_transfer(state: &State, port_index: usize, port_state: &mut PortState, index: usize, value: u8) -> (u8, bool) {
+    let slots_length = MULTITAP_SLOT_TRANSFER_LENGTH * 4;
+
+    match index {
+        0 => (0xFF, true),
+        1 => {
+            if value == JOYPAD_COMMAND_READ {
+                (MULTITAP_ID, true)
+            } else {
+                log::debug!("Unsupported multitap command 0x{:X}", value);
+                (0xFF, false)
+            }
+        },
+        2 => {
+            port_state.multitap_all_slots = value == 0x01;
+            (JOYPAD_ID_HIGH, true)
+        },
+        _ if (index - 3) < slots_length => {
+            let slot_index = (index - 3) / MULTITAP_SLOT_TRANSFER_LENGTH;
+            let byte_index = (index - 3) % MULTITAP_SLOT_TRANSFER_LENGTH;
+            let controller_device_state = &mut port_state.controller_states[slot_index];
+
+            if byte_index == 0 {
+                // Start the slot device's sequence by addressing it.
+                reset_controller(controller_device_state);
+                let (_, ack) = handle_controller_transfer(state, port_index, controller_device_state, ADDRESS_CONTROLLER);
+                port_state.multitap_slot_finished = !ack;
+            }
+
+            let response = if port_state.multitap_slot_finished {
+                0xFF
+            } else {
+                let (response, ack) = handle_controller_transfer(state, port_index, controller_device_state, value);
+                port_state.multitap_slot_finished = !ack;
+                response
+            };
+
+            (response, (index - 3 + 1) < slots_length)
+        },
+        _ => (0xFF, false),
+    }
+}
@@ -0,0 +1,56 @@
+use crate::system::padmc::{
+    constants::*,
+    types::*,
+};
+
+/// Handles a byte sent to a mouse, returning the response byte and if the mouse acknowledged it.
+pub(crate) fn handle_mouse_transfer(mouse_state: &mut MouseState, value: u8) -> (u8, bool) {
+    let index = mouse_state.sequence_index;
+    mouse_state.sequence_index += 1;
+
+    match index {
+        0 => (0xFF, true),
+        1 => {
+            if value != JOYPAD_COMMAND_READ {
+                log::debug!("Unsupported mouse command 0x{:X}", value);
+                return (0xFF, false);
+            }
+
+            // Motion is reported relative to the last poll.
+            let delta_x = mouse_state.delta_x.max(i8::MIN as i32).min(i8::MAX as i32);
+            let delta_y = mouse_state.delta_y.max(i8::MIN as i32).min(i8::MAX as i32);
+            mouse_state.delta_x -= delta_x;
+            mouse_state.delta_y -= delta_y;
+
+            mouse_state.response = [mouse_state.buttons as u8, (mouse_state.buttons >> 8) as u8, delta_x as i8 as u8, delta_y as i8 as u8];
+            (MOUSE_ID, true)
+        },
+        2 => (JOYPAD_ID_HIGH, true),
+        3..=6 => (mouse_state.response[index - 3], index < 6),
+        _ => (0xFF, false),
+    }
+}
+
+/// Handles a byte sent to a GunCon, returning the response byte and if the GunCon acknowledged it.
+/// The latch is the beam position at which the light was last detected.
+pub(crate) fn handle_guncon_transfer(guncon_state: &mut GunConState, latch: Option<(u16, u16)>, value: u8) -> (u8, bool) {
+    let index = guncon_state.sequence_index;
+    guncon_state.sequence_index += 1;
+
+    match index {
+        0 => (0xFF, true),
+        1 => {
+            if value != JOYPAD_COMMAND_READ {
+                log::debug!("Unsupported GunCon command 0x{:X}", value);
+                return (0xFF, false);
+            }
+
+            let (x, y) = latch.unwrap_or((GUNCON_OFFSCREEN_X, GUNCON_OFFSCREEN_Y));
+            guncon_state.response = [guncon_state.buttons as u8, (guncon_state.buttons >> 8) as u8, x as u8, (x >> 8) as u8, y as u8, (y >> 8) as u8];
+            (GUNCON_ID, true)
+        },
+        2 => (JOYPAD_ID_HIGH, true),
+        3..=8 => (guncon_state.response[index - 3], index < 8),
+        _ => (0xFF, false),
+    }
+}
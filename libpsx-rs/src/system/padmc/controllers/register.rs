@@ -2,6 +2,7 @@ use crate::{
     system::{
         padmc::{
             constants::*,
+            controllers::device::*,
             types::*,
         },
         types::{
@@ -17,24 +18,65 @@ pub(crate) fn handle_ctrl(state: &State, controller_state: &mut ControllerState)
         match latch_kind {
             LatchKind::Read => Ok(value),
             LatchKind::Write => {
+                if CTRL_RESET.extract_from(value) > 0 {
+                    handle_reset(state, controller_state);
+                    return Ok(0);
+                }
+
                 controller_state.tx_enabled = CTRL_TXEN.extract_from(value) > 0;
 
-                controller_state.joy_select_enabled = CTRL_JOYN_OUTPUT.extract_from(value) > 0;
+                let joy_select_enabled = CTRL_JOYN_OUTPUT.extract_from(value) > 0;
+                if controller_state.joy_select_enabled && !joy_select_enabled {
+                    reset_devices(controller_state);
+                }
+                controller_state.joy_select_enabled = joy_select_enabled;
 
-                controller_state.ack_interrupt_enabled = CTRL_ACKINT_ENABLE.extract_from(value) > 0;
+                controller_state.rx_interrupt_count = RXINT_MODE_COUNTS[CTRL_RXINT_MODE.extract_from(value) as usize];
 
-                controller_state.use_joy2 = CTRL_JOY_SLOT.extract_from(value) > 0;
+                controller_state.tx_interrupt_enabled = CTRL_TXINT_ENABLE.extract_from(value) > 0;
 
-                if CTRL_ACK.extract_from(value) > 0 {
-                    // log::debug!("ACK bit acknowledged");
+                controller_state.rx_interrupt_enabled = CTRL_RXINT_ENABLE.extract_from(value) > 0;
+
+                controller_state.ack_interrupt_enabled = CTRL_ACKINT_ENABLE.extract_from(value) > 0;
+
+                let use_joy2 = CTRL_JOY_SLOT.extract_from(value) > 0;
+                if controller_state.use_joy2 != use_joy2 {
+                    reset_devices(controller_state);
                 }
+                controller_state.use_joy2 = use_joy2;
 
-                if CTRL_RESET.extract_from(value) > 0 {
-                    // log::debug!("RESET bit acknowledged")
+                if CTRL_ACK.extract_from(value) > 0 {
+                    state.padmc.stat.write_bitfield(STAT_RXERR_PARITY, 0);
+                    state.padmc.stat.write_bitfield(STAT_RXFIFO_OVERRUN, 0);
+                    state.padmc.stat.write_bitfield(STAT_RXERR_STOP, 0);
+                    state.padmc.stat.write_bitfield(STAT_IRQ, 0);
                 }
 
-                Ok(value)
+                // The acknowledge bit is write-only.
+                Ok(CTRL_ACK.insert_into(value, 0))
             },
         }
     })
 }
+
+/// Resets the serial interface state (most registers cleared, FIFOs emptied, transfers aborted).
+fn handle_reset(state: &State, controller_state: &mut ControllerState) {
+    state.padmc.rx_fifo.clear();
+    state.padmc.tx_fifo.clear();
+    state.padmc.stat.write_u32(0);
+    state.padmc.mode.write_u16(0);
+
+    controller_state.tx_enabled = false;
+    controller_state.joy_select_enabled = false;
+    controller_state.ack_interrupt_enabled = false;
+    controller_state.tx_interrupt_enabled = false;
+    controller_state.rx_interrupt_enabled = false;
+    controller_state.rx_interrupt_count = RXINT_MODE_COUNTS[0];
+    controller_state.use_joy2 = false;
+    controller_state.baud_timer = 0;
+    controller_state.tx_ready_old = false;
+    controller_state.rx_ready_old = false;
+    controller_state.transfer_state = TransferState::Idle;
+    controller_state.ack_state = AckState::Idle;
+    reset_devices(controller_state);
+}
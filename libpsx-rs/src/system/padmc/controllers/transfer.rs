@@ -0,0 +1,83 @@
+use crate::{
+    system::{
+        padmc::{
+            constants::*,
+            controllers::{
+                baud::*,
+                device::*,
+                interrupt::*,
+            },
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    utilities::bool_to_flag,
+};
+
+pub(crate) fn handle_transfer(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    match controller_state.transfer_state {
+        TransferState::Idle => {
+            if controller_state.tx_enabled {
+                if let Ok(value) = state.padmc.tx_fifo.read_one() {
+                    controller_state.transfer_state = TransferState::Transferring(calculate_transfer_cycles(state), value);
+                }
+            }
+        },
+        TransferState::Transferring(cycles, value) => {
+            if cycles > 0 {
+                controller_state.transfer_state = TransferState::Transferring(cycles - 1, value);
+                return Ok(());
+            }
+
+            let (response, ack_delay) = handle_device_transfer(state, controller_state, value);
+
+            if state.padmc.rx_fifo.write_one(response).is_err() {
+                // The newest byte is lost when the RX FIFO is full.
+                state.padmc.stat.write_bitfield(STAT_RXFIFO_OVERRUN, 1);
+            }
+
+            if let Some(ack_delay) = ack_delay {
+                controller_state.ack_state = AckState::Pending(ack_delay);
+            }
+
+            controller_state.transfer_state = TransferState::Idle;
+        },
+    }
+
+    let tx_finished = controller_state.transfer_state == TransferState::Idle;
+    state.padmc.stat.write_bitfield(STAT_TXRDY_2, bool_to_flag(tx_finished));
+
+    Ok(())
+}
+
+pub(crate) fn handle_ack(state: &State, controller_state: &mut ControllerState) {
+    match controller_state.ack_state {
+        AckState::Idle => {},
+        AckState::Pending(cycles) => {
+            if cycles > 0 {
+                controller_state.ack_state = AckState::Pending(cycles - 1);
+                return;
+            }
+
+            state.padmc.stat.write_bitfield(STAT_ACK_LEVEL, 1);
+
+            if controller_state.ack_interrupt_enabled {
+                handle_irq_raise(state);
+            }
+
+            controller_state.ack_state = AckState::Asserted(ACK_LOW_CYCLES);
+        },
+        AckState::Asserted(cycles) => {
+            if cycles > 0 {
+                controller_state.ack_state = AckState::Asserted(cycles - 1);
+                return;
+            }
+
+            state.padmc.stat.write_bitfield(STAT_ACK_LEVEL, 0);
+            controller_state.ack_state = AckState::Idle;
+        },
+    }
+}
@@ -20,42 +20,42 @@ pub(crate) fn padmc1040_write_u8(state: &State, offset: u32, value: u8) -> Write
     state.padmc.tx_fifo.write_one(value).map_err(|_| WriteErrorKind::Full)
 }
 
-pub(crate) fn padmc1040_read_u32(_state: &State, offset: u32) -> ReadResult<u32> {
+pub(crate) fn padmc1040_read_u32(state: &State, offset: u32) -> ReadResult<u32> {
     assert_eq!(offset, 0);
-    unimplemented!();
+    // Only a single byte is removed from the FIFO; the upper bytes are not emulated.
+    padmc1040_read_u8(state, offset).map(|value| value as u32)
 }
 
-pub(crate) fn padmc1040_write_u32(_state: &State, offset: u32, _value: u32) -> WriteResult {
+pub(crate) fn padmc1040_write_u32(state: &State, offset: u32, value: u32) -> WriteResult {
     assert_eq!(offset, 0);
-    unimplemented!();
+    padmc1040_write_u8(state, offset, value as u8)
 }
 
 pub(crate) fn stat_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
     assert_eq!(offset, 0);
-    let mut value = state.padmc.stat.read_u16(0);
-    // TODO: implement properly.
-    value = STAT_TXRDY_1.insert_into(value, 1);
-    value = STAT_RXFIFO_READY.insert_into(value, !bool_to_flag(state.padmc.rx_fifo.is_empty()) as u16);
-    value = STAT_TXRDY_2.insert_into(value, 1);
-    Ok(value)
+    stat_read_u32(state, offset).map(|value| value as u16)
 }
 
-pub(crate) fn stat_write_u16(_state: &State, _offset: u32, _value: u16) -> WriteResult {
-    unimplemented!();
+pub(crate) fn stat_write_u16(_state: &State, offset: u32, _value: u16) -> WriteResult {
+    assert_eq!(offset, 0);
+    // Read-only register.
+    Ok(())
 }
 
 pub(crate) fn stat_read_u32(state: &State, offset: u32) -> ReadResult<u32> {
     assert_eq!(offset, 0);
     let mut value = state.padmc.stat.read_u32();
-    // TODO: implement properly.
-    value = STAT_TXRDY_1.insert_into(value, 1);
-    value = STAT_RXFIFO_READY.insert_into(value, !bool_to_flag(state.padmc.rx_fifo.is_empty()));
-    value = STAT_TXRDY_2.insert_into(value, 1);
+    let tx_empty = state.padmc.tx_fifo.is_empty();
+    value = STAT_TXRDY_1.insert_into(value, bool_to_flag(tx_empty));
+    value = STAT_RXFIFO_READY.insert_into(value, bool_to_flag(!state.padmc.rx_fifo.is_empty()));
+    value = STAT_TXRDY_2.insert_into(value, bool_to_flag(tx_empty) & STAT_TXRDY_2.extract_from(value));
     Ok(value)
 }
 
-pub(crate) fn stat_write_u32(_state: &State, _offset: u32, _value: u32) -> WriteResult {
-    unimplemented!();
+pub(crate) fn stat_write_u32(_state: &State, offset: u32, _value: u32) -> WriteResult {
+    assert_eq!(offset, 0);
+    // Read-only register.
+    Ok(())
 }
 
 pub(crate) fn mode_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
@@ -1,13 +1,27 @@
-use crate::types::{
-    exclusive_state::ExclusiveState,
-    fifo::Fifo,
-    memory::*,
+mod joypad;
+mod memory_card;
+mod pointer;
+mod port;
+mod transfer;
+
+use crate::{
+    system::padmc::constants::*,
+    types::{
+        exclusive_state::ExclusiveState,
+        fifo::Fifo,
+        memory::*,
+    },
 };
+pub(crate) use joypad::*;
+pub(crate) use memory_card::*;
+pub(crate) use pointer::*;
+pub(crate) use port::*;
 #[cfg(feature = "serialization")]
 use serde::{
     Deserialize,
     Serialize,
 };
+pub(crate) use transfer::*;
 
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 #[derive(Clone)]
@@ -16,7 +30,22 @@ pub(crate) struct ControllerState {
     pub(crate) tx_enabled: bool,
     pub(crate) joy_select_enabled: bool,
     pub(crate) ack_interrupt_enabled: bool,
+    pub(crate) tx_interrupt_enabled: bool,
+    pub(crate) rx_interrupt_enabled: bool,
+    /// Number of bytes in the RX FIFO needed to raise an RX interrupt.
+    pub(crate) rx_interrupt_count: usize,
     pub(crate) use_joy2: bool,
+    /// Baud rate timer; reloaded with (reload value * factor / 2) when it reaches 0.
+    pub(crate) baud_timer: usize,
+    /// Previous TX ready (TXRDY1 | TXRDY2) and RX interrupt conditions, used to raise interrupts on their rising edges.
+    pub(crate) tx_ready_old: bool,
+    pub(crate) rx_ready_old: bool,
+    pub(crate) transfer_state: TransferState,
+    pub(crate) ack_state: AckState,
+    pub(crate) active_device: ActiveDevice,
+    /// Current byte index since the port was selected.
+    pub(crate) sequence_index: usize,
+    pub(crate) port_states: [PortState; 2],
 }
 
 impl ControllerState {
@@ -26,7 +55,18 @@ impl ControllerState {
             tx_enabled: false,
             joy_select_enabled: false,
             ack_interrupt_enabled: false,
+            tx_interrupt_enabled: false,
+            rx_interrupt_enabled: false,
+            rx_interrupt_count: 1,
             use_joy2: false,
+            baud_timer: 0,
+            tx_ready_old: false,
+            rx_ready_old: false,
+            transfer_state: TransferState::Idle,
+            ack_state: AckState::Idle,
+            active_device: ActiveDevice::None,
+            sequence_index: 0,
+            port_states: [PortState::new(), PortState::new()],
         }
     }
 }
@@ -40,18 +80,20 @@ pub(crate) struct State {
     pub(crate) mode: B16LevelRegister,
     pub(crate) ctrl: B16EdgeRegister,
     pub(crate) baud_reload: B16LevelRegister,
+    pub(crate) lightgun_state: ExclusiveState<LightgunState>,
     pub(crate) controller_state: ExclusiveState<ControllerState>,
 }
 
 impl State {
     pub(crate) fn new() -> State {
         State {
-            rx_fifo: Fifo::new(16),
+            rx_fifo: Fifo::new(RX_FIFO_SIZE),
             tx_fifo: Fifo::new(16),
             stat: B32LevelRegister::new(),
             mode: B16LevelRegister::new(),
             ctrl: B16EdgeRegister::new(),
             baud_reload: B16LevelRegister::new(),
+            lightgun_state: ExclusiveState::new(LightgunState::new()),
             controller_state: ExclusiveState::new(ControllerState::new()),
         }
     }
@@ -0,0 +1,67 @@
+use crate::system::padmc::constants::*;
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct JoypadState {
+    /// DualShock (analog capable) joypad, otherwise a digital only joypad.
+    pub(crate) dualshock: bool,
+    /// Button state, stored in the active-low form sent over the wire (0 = pressed).
+    pub(crate) buttons: u16,
+    /// Analog stick axes (0x00 = left/up, 0x80 = center, 0xFF = right/down).
+    pub(crate) left_x: u8,
+    pub(crate) left_y: u8,
+    pub(crate) right_x: u8,
+    pub(crate) right_y: u8,
+    /// Analog mode (ID 0x73) is active, otherwise the pad behaves as a digital pad (ID 0x41).
+    pub(crate) analog_mode: bool,
+    /// The analog button has been locked by the software (through command 0x44).
+    pub(crate) analog_mode_locked: bool,
+    /// Config mode (ID 0xF3) is active.
+    pub(crate) config_mode: bool,
+    /// Config mode state to take effect at the end of the current 0x43 command.
+    pub(crate) pending_config_mode: bool,
+    /// Motor mapping set by command 0x4D; indexed by the payload byte position in the 0x42 command.
+    pub(crate) motor_mapping: [u8; 6],
+    /// Current motor values.
+    pub(crate) small_motor: u8,
+    pub(crate) large_motor: u8,
+    /// Motor values have changed since they were last reported to the frontend.
+    pub(crate) motors_changed: bool,
+    /// Command currently being processed.
+    pub(crate) command: u8,
+    /// Payload response for the current command.
+    pub(crate) response: [u8; 6],
+    pub(crate) response_length: usize,
+    /// Current byte index within the command sequence.
+    pub(crate) sequence_index: usize,
+}
+
+impl JoypadState {
+    pub(crate) fn new(dualshock: bool) -> JoypadState {
+        JoypadState {
+            dualshock,
+            buttons: 0xFFFF,
+            left_x: JOYPAD_AXIS_CENTER,
+            left_y: JOYPAD_AXIS_CENTER,
+            right_x: JOYPAD_AXIS_CENTER,
+            right_y: JOYPAD_AXIS_CENTER,
+            analog_mode: false,
+            analog_mode_locked: false,
+            config_mode: false,
+            pending_config_mode: false,
+            motor_mapping: [JOYPAD_MOTOR_UNMAPPED; 6],
+            small_motor: 0,
+            large_motor: 0,
+            motors_changed: false,
+            command: 0,
+            response: [0; 6],
+            response_length: 0,
+            sequence_index: 0,
+        }
+    }
+}
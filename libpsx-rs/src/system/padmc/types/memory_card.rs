@@ -0,0 +1,51 @@
+use crate::system::padmc::constants::*;
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct MemoryCardState {
+    /// Raw card contents (1024 sectors of 128 bytes).
+    pub(crate) data: Vec<u8>,
+    /// Card status flag, sent in response to the command byte.
+    pub(crate) flag: u8,
+    /// Current byte index within the command sequence.
+    pub(crate) sequence_index: usize,
+    /// Command currently being processed.
+    pub(crate) command: u8,
+    /// Sector address of the current read/write command.
+    pub(crate) address: usize,
+    /// Running checksum of the current read/write command (address bytes and data XOR'd).
+    pub(crate) checksum: u8,
+    /// Last byte received; used for the delayed echo during write commands.
+    pub(crate) previous_value: u8,
+    /// Received checksum during write commands.
+    pub(crate) received_checksum: u8,
+    /// Sector data received during write commands, committed at the end of the sequence.
+    pub(crate) sector_buffer: Vec<u8>,
+    /// Contents have changed since the last time the card was flushed to disk.
+    pub(crate) dirty: bool,
+    /// Emulated time since the last completed write command.
+    pub(crate) idle_time: f32,
+}
+
+impl MemoryCardState {
+    pub(crate) fn new() -> MemoryCardState {
+        MemoryCardState {
+            data: vec![0; MEMORY_CARD_SIZE],
+            flag: MEMORY_CARD_FLAG_NEW.insert_into(0, 1),
+            sequence_index: 0,
+            command: 0,
+            address: 0,
+            checksum: 0,
+            previous_value: 0,
+            received_checksum: 0,
+            sector_buffer: vec![0; MEMORY_CARD_SECTOR_SIZE],
+            dirty: false,
+            idle_time: 0.0,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct MouseState {
+    /// Button state, stored in the active-low form sent over the wire (0 = pressed).
+    pub(crate) buttons: u16,
+    /// Relative motion accumulated since the last poll.
+    pub(crate) delta_x: i32,
+    pub(crate) delta_y: i32,
+    /// Payload response for the current command.
+    pub(crate) response: [u8; 4],
+    /// Current byte index within the command sequence.
+    pub(crate) sequence_index: usize,
+}
+
+impl MouseState {
+    pub(crate) fn new() -> MouseState {
+        MouseState {
+            buttons: 0xFFFF,
+            delta_x: 0,
+            delta_y: 0,
+            response: [0; 4],
+            sequence_index: 0,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct GunConState {
+    /// Button state, stored in the active-low form sent over the wire (0 = pressed).
+    pub(crate) buttons: u16,
+    /// Payload response for the current command.
+    pub(crate) response: [u8; 6],
+    /// Current byte index within the command sequence.
+    pub(crate) sequence_index: usize,
+}
+
+impl GunConState {
+    pub(crate) fn new() -> GunConState {
+        GunConState {
+            buttons: 0xFFFF,
+            response: [0; 6],
+            sequence_index: 0,
+        }
+    }
+}
+
+/// Lightgun state shared with the CRTC, which latches the beam position as it passes the pointer target.
+/// Indexed by port.
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct LightgunState {
+    /// Pointer position, normalized to the visible display area (0.0 to 1.0); None when pointing off-screen.
+    pub(crate) pointers: [Option<(f32, f32)>; 2],
+    /// Beam position (X in 8 MHz clocks since hsync, Y in scanlines since vsync) the pointer corresponds to for the
+    /// current frame.
+    pub(crate) targets: [Option<(u16, u16)>; 2],
+    /// Beam position latched when the light was last detected; None if no light was detected during the last frame.
+    pub(crate) latches: [Option<(u16, u16)>; 2],
+    /// Light was detected during the current frame.
+    pub(crate) latched_this_frame: [bool; 2],
+}
+
+impl LightgunState {
+    pub(crate) fn new() -> LightgunState {
+        LightgunState {
+            pointers: [None; 2],
+            targets: [None; 2],
+            latches: [None; 2],
+            latched_this_frame: [false; 2],
+        }
+    }
+}
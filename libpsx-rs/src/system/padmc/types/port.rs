@@ -0,0 +1,92 @@
+use crate::{
+    input::{
+        ControllerKind,
+        PortConfig,
+    },
+    system::padmc::types::{
+        joypad::JoypadState,
+        memory_card::MemoryCardState,
+        pointer::{
+            GunConState,
+            MouseState,
+        },
+    },
+};
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) enum ControllerDeviceState {
+    None,
+    Joypad(JoypadState),
+    Mouse(MouseState),
+    GunCon(GunConState),
+}
+
+impl ControllerDeviceState {
+    pub(crate) fn from_kind(kind: ControllerKind) -> ControllerDeviceState {
+        match kind {
+            ControllerKind::None => ControllerDeviceState::None,
+            ControllerKind::Digital => ControllerDeviceState::Joypad(JoypadState::new(false)),
+            ControllerKind::DualShock => ControllerDeviceState::Joypad(JoypadState::new(true)),
+            ControllerKind::Mouse => ControllerDeviceState::Mouse(MouseState::new()),
+            ControllerKind::GunCon => ControllerDeviceState::GunCon(GunConState::new()),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct PortState {
+    /// A multitap adapter is connected; slots B-D are only reachable through it.
+    pub(crate) multitap: bool,
+    /// The multitap will return the data of all four slots in the next controller transfer.
+    /// Armed by sending 0x01 as the third byte of a controller transfer.
+    pub(crate) multitap_all_slots: bool,
+    /// The slot device currently being forwarded to ended its sequence (rest of the slot is padded with 0xFF).
+    pub(crate) multitap_slot_finished: bool,
+    pub(crate) controller_states: [ControllerDeviceState; 4],
+    pub(crate) memory_card_states: [Option<MemoryCardState>; 4],
+}
+
+impl PortState {
+    pub(crate) fn new() -> PortState {
+        PortState::from_config(&PortConfig::new())
+    }
+
+    pub(crate) fn from_config(config: &PortConfig) -> PortState {
+        let memory_card_state = |index: usize| {
+            if config.memory_cards[index] {
+                Some(MemoryCardState::new())
+            } else {
+                None
+            }
+        };
+
+        PortState {
+            multitap: config.multitap,
+            multitap_all_slots: false,
+            multitap_slot_finished: false,
+            controller_states: [
+                ControllerDeviceState::from_kind(config.controllers[0]),
+                ControllerDeviceState::from_kind(config.controllers[1]),
+                ControllerDeviceState::from_kind(config.controllers[2]),
+                ControllerDeviceState::from_kind(config.controllers[3]),
+            ],
+            memory_card_states: [memory_card_state(0), memory_card_state(1), memory_card_state(2), memory_card_state(3)],
+        }
+    }
+
+    /// Number of slots reachable on this port.
+    pub(crate) fn slot_count(&self) -> usize {
+        if self.multitap {
+            4
+        } else {
+            1
+        }
+    }
+}
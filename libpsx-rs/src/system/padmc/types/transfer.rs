@@ -0,0 +1,37 @@
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) enum TransferState {
+    Idle,
+    /// Byte being shifted out/in; contains the remaining cycles and the TX value.
+    Transferring(usize, u8),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) enum AckState {
+    Idle,
+    /// Device is about to pull the ACK line low; contains the remaining delay cycles.
+    Pending(usize),
+    /// ACK line is currently held low; contains the remaining cycles.
+    Asserted(usize),
+}
+
+/// The device which responded to the address byte since the last time the port was selected.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) enum ActiveDevice {
+    None,
+    /// Controller in the given slot.
+    Controller(usize),
+    /// Memory card in the given slot.
+    MemoryCard(usize),
+    /// Multitap transfer returning all four controller slots.
+    Multitap,
+    Invalid,
+}
@@ -0,0 +1,46 @@
+use crate::types::bitfield::Bitfield;
+
+pub(crate) const CLOCK_SPEED: f32 = 33.8688 * 1e6; // 33.8688 MHz
+pub(crate) const CLOCK_SPEED_PERIOD: f32 = 1.0 / CLOCK_SPEED;
+
+pub(crate) const STAT_TXRDY_1: Bitfield = Bitfield::new(0, 1);
+pub(crate) const STAT_RXFIFO_READY: Bitfield = Bitfield::new(1, 1);
+pub(crate) const STAT_TXRDY_2: Bitfield = Bitfield::new(2, 1);
+pub(crate) const STAT_RXERR_PARITY: Bitfield = Bitfield::new(3, 1);
+pub(crate) const STAT_RXFIFO_OVERRUN: Bitfield = Bitfield::new(4, 1);
+pub(crate) const STAT_RXERR_STOP: Bitfield = Bitfield::new(5, 1);
+pub(crate) const STAT_RX_LEVEL: Bitfield = Bitfield::new(6, 1);
+pub(crate) const STAT_DSR_LEVEL: Bitfield = Bitfield::new(7, 1);
+pub(crate) const STAT_CTS_LEVEL: Bitfield = Bitfield::new(8, 1);
+pub(crate) const STAT_IRQ: Bitfield = Bitfield::new(9, 1);
+pub(crate) const STAT_TIMER: Bitfield = Bitfield::new(11, 21);
+
+pub(crate) const MODE_RATE_RELOADF: Bitfield = Bitfield::new(0, 2);
+pub(crate) const MODE_CHAR_LENGTH: Bitfield = Bitfield::new(2, 2);
+pub(crate) const MODE_PARITY_ENABLE: Bitfield = Bitfield::new(4, 1);
+pub(crate) const _MODE_PARITY_TYPE: Bitfield = Bitfield::new(5, 1);
+pub(crate) const MODE_STOP_LENGTH: Bitfield = Bitfield::new(6, 2);
+
+pub(crate) const CTRL_TXEN: Bitfield = Bitfield::new(0, 1);
+pub(crate) const CTRL_DTR_OUTPUT: Bitfield = Bitfield::new(1, 1);
+pub(crate) const CTRL_RXEN: Bitfield = Bitfield::new(2, 1);
+pub(crate) const _CTRL_TX_LEVEL: Bitfield = Bitfield::new(3, 1);
+pub(crate) const CTRL_ACK: Bitfield = Bitfield::new(4, 1);
+pub(crate) const CTRL_RTS_OUTPUT: Bitfield = Bitfield::new(5, 1);
+pub(crate) const CTRL_RESET: Bitfield = Bitfield::new(6, 1);
+pub(crate) const CTRL_RXINT_MODE: Bitfield = Bitfield::new(8, 2);
+pub(crate) const CTRL_TXINT_ENABLE: Bitfield = Bitfield::new(10, 1);
+pub(crate) const CTRL_RXINT_ENABLE: Bitfield = Bitfield::new(11, 1);
+pub(crate) const CTRL_DSRINT_ENABLE: Bitfield = Bitfield::new(12, 1);
+
+/// Baud rate reload factors, indexed by MODE_RATE_RELOADF.
+pub(crate) const BAUD_RELOAD_FACTORS: [usize; 4] = [1, 1, 16, 64];
+/// Number of data bits per character, indexed by MODE_CHAR_LENGTH.
+pub(crate) const CHAR_LENGTH_BITS: [usize; 4] = [5, 6, 7, 8];
+/// Number of stop half-bits per character, indexed by MODE_STOP_LENGTH (0 is reserved and behaves as 1 stop bit).
+pub(crate) const STOP_LENGTH_HALF_BITS: [usize; 4] = [2, 2, 3, 4];
+/// Number of bytes in the RX FIFO needed to raise an interrupt, indexed by CTRL_RXINT_MODE.
+pub(crate) const RXINT_MODE_COUNTS: [usize; 4] = [1, 2, 4, 8];
+pub(crate) const RX_FIFO_SIZE: usize = 8;
+/// The TX "FIFO" only holds a single byte (in addition to the byte being shifted out).
+pub(crate) const TX_FIFO_SIZE: usize = 1;
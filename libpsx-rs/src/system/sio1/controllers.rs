@@ -0,0 +1,126 @@
+pub(crate) mod baud;
+pub(crate) mod interrupt;
+pub(crate) mod link;
+pub(crate) mod register;
+pub(crate) mod transfer;
+
+use crate::{
+    backends::sio::SioBackend,
+    system::{
+        sio1::{
+            constants::*,
+            controllers::{
+                baud::*,
+                interrupt::*,
+                link::*,
+                register::*,
+                transfer::*,
+            },
+            types::*,
+        },
+        types::{
+            ControllerContext,
+            ControllerResult,
+            Event,
+            State,
+        },
+    },
+};
+
+pub(crate) fn run(context: &ControllerContext, event: Event) -> ControllerResult<()> {
+    match event {
+        Event::Time(duration) => run_time(context.state, context.sio_backend, duration),
+    }
+}
+
+fn run_time(state: &State, sio_backend: &SioBackend, duration: f32) -> ControllerResult<()> {
+    let controller_state = &mut state.sio1.controller_state.lock();
+    controller_state.clock += duration;
+
+    // The link backend is only polled once per time slice, rather than every tick.
+    handle_link_input(sio_backend, controller_state)?;
+
+    while controller_state.clock > 0.0 {
+        tick(state, controller_state)?;
+        controller_state.clock -= CLOCK_SPEED_PERIOD;
+    }
+
+    handle_link_output(sio_backend, controller_state)
+}
+
+fn tick(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    handle_ctrl(state, controller_state)?;
+    handle_baud_timer(state, controller_state);
+    handle_tx_transfer(state, controller_state);
+    handle_rx_transfer(state, controller_state);
+    handle_input_lines(state, controller_state);
+    handle_interrupts(state, controller_state);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backends::sio::pipe,
+        system::{
+            intc::constants::SIO,
+            sio1::memory::*,
+        },
+    };
+
+    const SLICE_DURATION: f32 = 1000.0 * CLOCK_SPEED_PERIOD;
+
+    fn setup(state: &State, ctrl: u32) {
+        // 8 data bits, 1 stop bit, no parity; 16 cycles per bit.
+        mode_write_u16(state, 0, (MODE_CHAR_LENGTH.insert_into(0, 3) | MODE_STOP_LENGTH.insert_into(0, 1)) as u16).unwrap();
+        baud_reload_write_u16(state, 0, 16).unwrap();
+        ctrl_write_u16(state, 0, ctrl as u16).unwrap();
+    }
+
+    fn run_link(ends: &[(&State, &SioBackend)], slices: usize) {
+        for _ in 0..slices {
+            for &(state, sio_backend) in ends.iter() {
+                run_time(state, sio_backend, SLICE_DURATION).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipe_link_transfer() {
+        let (end_a, end_b) = pipe::create();
+        let (backend_a, backend_b) = (SioBackend::link(end_a), SioBackend::link(end_b));
+        let (state_a, state_b) = (State::new(), State::new());
+        let ends = [(&*state_a, &backend_a), (&*state_b, &backend_b)];
+
+        let ctrl_a = CTRL_TXEN.insert_into(CTRL_DTR_OUTPUT.insert_into(CTRL_RTS_OUTPUT.insert_into(0, 1), 1), 1);
+        let ctrl_b = CTRL_RXEN.insert_into(CTRL_RXINT_ENABLE.insert_into(CTRL_RTS_OUTPUT.insert_into(0, 1), 1), 1);
+        setup(&state_a, ctrl_a);
+        setup(&state_b, ctrl_b);
+        run_link(&ends, 2);
+
+        // Each end's DTR / RTS outputs are seen as the other end's DSR / CTS inputs.
+        assert_eq!(state_b.sio1.stat.read_bitfield(STAT_DSR_LEVEL), 1);
+        assert_eq!(state_b.sio1.stat.read_bitfield(STAT_CTS_LEVEL), 1);
+        assert_eq!(state_a.sio1.stat.read_bitfield(STAT_DSR_LEVEL), 0);
+        assert_eq!(state_a.sio1.stat.read_bitfield(STAT_CTS_LEVEL), 1);
+        assert_eq!(state_b.sio1.stat.read_bitfield(STAT_IRQ), 0);
+
+        data_write_u8(&state_a, 0, 0x5A).unwrap();
+        run_link(&ends, 2);
+
+        assert_eq!(state_b.sio1.rx_fifo.read_available(), 1);
+        assert_eq!(state_b.sio1.stat.read_bitfield(STAT_IRQ), 1);
+        assert_eq!(SIO.extract_from(state_b.intc.stat.read_u32()), 1);
+        assert_eq!(data_read_u8(&state_b, 0).unwrap(), 0x5A);
+        assert_eq!(state_a.sio1.rx_fifo.read_available(), 0);
+
+        // Dropping DTR is seen by the other end.
+        setup(&state_a, CTRL_DTR_OUTPUT.insert_into(ctrl_a, 0));
+        run_link(&ends, 2);
+
+        assert_eq!(state_b.sio1.stat.read_bitfield(STAT_DSR_LEVEL), 0);
+        assert_eq!(state_b.sio1.stat.read_bitfield(STAT_CTS_LEVEL), 1);
+    }
+}
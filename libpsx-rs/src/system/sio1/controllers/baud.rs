@@ -0,0 +1,38 @@
+use crate::system::{
+    sio1::{
+        constants::*,
+        types::*,
+    },
+    types::State,
+};
+
+/// Returns the number of cycles per bit, as set by the baud rate reload value and the mode reload factor.
+pub(crate) fn calculate_bit_cycles(state: &State) -> usize {
+    let reload_value = state.sio1.baud_reload.read_u16() as usize;
+    let factor = BAUD_RELOAD_FACTORS[MODE_RATE_RELOADF.extract_from(state.sio1.mode.read_u16()) as usize];
+    (reload_value * factor).max(1)
+}
+
+/// Returns the number of data bits per character.
+pub(crate) fn calculate_char_bits(state: &State) -> usize {
+    CHAR_LENGTH_BITS[MODE_CHAR_LENGTH.extract_from(state.sio1.mode.read_u16()) as usize]
+}
+
+/// Returns the number of cycles needed to shift a character in/out, including the start, parity and stop bits.
+pub(crate) fn calculate_transfer_cycles(state: &State) -> usize {
+    let mode = state.sio1.mode.read_u16();
+    let parity_bits = MODE_PARITY_ENABLE.extract_from(mode) as usize;
+    let stop_half_bits = STOP_LENGTH_HALF_BITS[MODE_STOP_LENGTH.extract_from(mode) as usize];
+    let half_bits = ((1 + calculate_char_bits(state) + parity_bits) * 2) + stop_half_bits;
+    (calculate_bit_cycles(state) * half_bits) / 2
+}
+
+pub(crate) fn handle_baud_timer(state: &State, controller_state: &mut ControllerState) {
+    if controller_state.baud_timer == 0 {
+        controller_state.baud_timer = (calculate_bit_cycles(state) / 2).max(1);
+    } else {
+        controller_state.baud_timer -= 1;
+    }
+
+    state.sio1.stat.write_bitfield(STAT_TIMER, controller_state.baud_timer as u32);
+}
@@ -0,0 +1,37 @@
+use crate::system::{
+    intc::types::Line,
+    sio1::{
+        constants::*,
+        types::*,
+    },
+    types::State,
+};
+
+/// Raises the IRQ flag; the interrupt line is only asserted if the flag was not already set (it needs to be
+/// acknowledged through CTRL).
+pub(crate) fn handle_irq_raise(state: &State) {
+    if state.sio1.stat.read_bitfield(STAT_IRQ) == 0 {
+        state.sio1.stat.write_bitfield(STAT_IRQ, 1);
+        state.intc.stat.assert_line(Line::Sio);
+    }
+}
+
+pub(crate) fn handle_interrupts(state: &State, controller_state: &mut ControllerState) {
+    let tx_ready = state.sio1.tx_fifo.is_empty();
+    if controller_state.tx_interrupt_enabled && tx_ready && !controller_state.tx_ready_old {
+        handle_irq_raise(state);
+    }
+    controller_state.tx_ready_old = tx_ready;
+
+    let rx_ready = state.sio1.rx_fifo.read_available() >= controller_state.rx_interrupt_count;
+    if controller_state.rx_interrupt_enabled && rx_ready && !controller_state.rx_ready_old {
+        handle_irq_raise(state);
+    }
+    controller_state.rx_ready_old = rx_ready;
+
+    let dsr = controller_state.dsr_input;
+    if controller_state.dsr_interrupt_enabled && dsr && !controller_state.dsr_old {
+        handle_irq_raise(state);
+    }
+    controller_state.dsr_old = dsr;
+}
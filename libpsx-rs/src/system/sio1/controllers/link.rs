@@ -0,0 +1,61 @@
+use crate::{
+    backends::sio::{
+        LinkLines,
+        SioBackend,
+    },
+    system::{
+        sio1::types::*,
+        types::ControllerResult,
+    },
+};
+
+/// Collects the bytes received and the input line levels from the link backend.
+pub(crate) fn handle_link_input(sio_backend: &SioBackend, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    match sio_backend {
+        SioBackend::None => {
+            // Nothing connected; the input lines are inactive.
+            controller_state.dsr_input = false;
+            controller_state.cts_input = false;
+        },
+        SioBackend::Link(ref link) => {
+            let mut link = link.lock();
+
+            while let Some(value) = link.receive()? {
+                controller_state.rx_pending.push_back(value);
+            }
+
+            let lines = link.input_lines()?;
+            controller_state.dsr_input = lines.dtr;
+            controller_state.cts_input = lines.rts;
+        },
+    }
+
+    Ok(())
+}
+
+/// Sends the bytes shifted out and any output line changes to the link backend.
+pub(crate) fn handle_link_output(sio_backend: &SioBackend, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    match sio_backend {
+        SioBackend::None => {
+            controller_state.tx_pending.clear();
+        },
+        SioBackend::Link(ref link) => {
+            let mut link = link.lock();
+
+            let output_lines = (controller_state.dtr_output, controller_state.rts_output);
+            if controller_state.output_lines_sent != output_lines {
+                link.set_output_lines(LinkLines {
+                    dtr: output_lines.0,
+                    rts: output_lines.1,
+                })?;
+                controller_state.output_lines_sent = output_lines;
+            }
+
+            for value in controller_state.tx_pending.drain(..) {
+                link.transmit(value)?;
+            }
+        },
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::{
+    system::{
+        sio1::{
+            constants::*,
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    types::memory::LatchKind,
+};
+
+pub(crate) fn handle_ctrl(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    state.sio1.ctrl.acknowledge(|value, latch_kind| {
+        match latch_kind {
+            LatchKind::Read => Ok(value),
+            LatchKind::Write => {
+                if CTRL_RESET.extract_from(value) > 0 {
+                    handle_reset(state, controller_state);
+                    return Ok(0);
+                }
+
+                controller_state.tx_enabled = CTRL_TXEN.extract_from(value) > 0;
+
+                controller_state.dtr_output = CTRL_DTR_OUTPUT.extract_from(value) > 0;
+
+                controller_state.rx_enabled = CTRL_RXEN.extract_from(value) > 0;
+
+                controller_state.rts_output = CTRL_RTS_OUTPUT.extract_from(value) > 0;
+
+                controller_state.rx_interrupt_count = RXINT_MODE_COUNTS[CTRL_RXINT_MODE.extract_from(value) as usize];
+
+                controller_state.tx_interrupt_enabled = CTRL_TXINT_ENABLE.extract_from(value) > 0;
+
+                controller_state.rx_interrupt_enabled = CTRL_RXINT_ENABLE.extract_from(value) > 0;
+
+                controller_state.dsr_interrupt_enabled = CTRL_DSRINT_ENABLE.extract_from(value) > 0;
+
+                if CTRL_ACK.extract_from(value) > 0 {
+                    state.sio1.stat.write_bitfield(STAT_RXERR_PARITY, 0);
+                    state.sio1.stat.write_bitfield(STAT_RXFIFO_OVERRUN, 0);
+                    state.sio1.stat.write_bitfield(STAT_RXERR_STOP, 0);
+                    state.sio1.stat.write_bitfield(STAT_IRQ, 0);
+                }
+
+                // The acknowledge bit is write-only.
+                Ok(CTRL_ACK.insert_into(value, 0))
+            },
+        }
+    })
+}
+
+/// Resets the serial interface state (most registers cleared, FIFOs emptied, transfers aborted).
+fn handle_reset(state: &State, controller_state: &mut ControllerState) {
+    state.sio1.rx_fifo.clear();
+    state.sio1.tx_fifo.clear();
+    state.sio1.stat.write_u32(0);
+    state.sio1.mode.write_u16(0);
+
+    controller_state.tx_enabled = false;
+    controller_state.rx_enabled = false;
+    controller_state.dtr_output = false;
+    controller_state.rts_output = false;
+    controller_state.tx_interrupt_enabled = false;
+    controller_state.rx_interrupt_enabled = false;
+    controller_state.dsr_interrupt_enabled = false;
+    controller_state.rx_interrupt_count = RXINT_MODE_COUNTS[0];
+    controller_state.baud_timer = 0;
+    controller_state.tx_state = TransferState::Idle;
+    controller_state.rx_state = TransferState::Idle;
+    controller_state.tx_ready_old = false;
+    controller_state.rx_ready_old = false;
+    controller_state.dsr_old = false;
+}
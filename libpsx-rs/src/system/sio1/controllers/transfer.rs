@@ -0,0 +1,70 @@
+use crate::{
+    system::{
+        sio1::{
+            constants::*,
+            controllers::baud::*,
+            types::*,
+        },
+        types::State,
+    },
+    utilities::bool_to_flag,
+};
+
+pub(crate) fn handle_tx_transfer(state: &State, controller_state: &mut ControllerState) {
+    match controller_state.tx_state {
+        TransferState::Idle => {
+            // Transfers only start once the remote end is ready to receive (CTS asserted).
+            if controller_state.tx_enabled && controller_state.cts_input {
+                if let Ok(value) = state.sio1.tx_fifo.read_one() {
+                    let mask = ((1usize << calculate_char_bits(state)) - 1) as u8;
+                    controller_state.tx_state = TransferState::Transferring(calculate_transfer_cycles(state), value & mask);
+                }
+            }
+        },
+        TransferState::Transferring(cycles, value) => {
+            if cycles > 0 {
+                controller_state.tx_state = TransferState::Transferring(cycles - 1, value);
+            } else {
+                controller_state.tx_pending.push_back(value);
+                controller_state.tx_state = TransferState::Idle;
+            }
+        },
+    }
+
+    let tx_finished = controller_state.tx_state == TransferState::Idle;
+    state.sio1.stat.write_bitfield(STAT_TXRDY_2, bool_to_flag(tx_finished));
+}
+
+pub(crate) fn handle_rx_transfer(state: &State, controller_state: &mut ControllerState) {
+    match controller_state.rx_state {
+        TransferState::Idle => {
+            if let Some(value) = controller_state.rx_pending.pop_front() {
+                // Data arriving while the receiver is disabled is lost.
+                if controller_state.rx_enabled {
+                    controller_state.rx_state = TransferState::Transferring(calculate_transfer_cycles(state), value);
+                }
+            }
+        },
+        TransferState::Transferring(cycles, value) => {
+            if cycles > 0 {
+                controller_state.rx_state = TransferState::Transferring(cycles - 1, value);
+            } else {
+                if state.sio1.rx_fifo.write_one(value).is_err() {
+                    // The newest byte is lost when the RX FIFO is full.
+                    state.sio1.stat.write_bitfield(STAT_RXFIFO_OVERRUN, 1);
+                }
+
+                controller_state.rx_state = TransferState::Idle;
+            }
+        },
+    }
+
+    // The RX line idles high; approximated as low for the whole character while receiving.
+    let rx_idle = controller_state.rx_state == TransferState::Idle;
+    state.sio1.stat.write_bitfield(STAT_RX_LEVEL, bool_to_flag(rx_idle));
+}
+
+pub(crate) fn handle_input_lines(state: &State, controller_state: &mut ControllerState) {
+    state.sio1.stat.write_bitfield(STAT_DSR_LEVEL, bool_to_flag(controller_state.dsr_input));
+    state.sio1.stat.write_bitfield(STAT_CTS_LEVEL, bool_to_flag(controller_state.cts_input));
+}
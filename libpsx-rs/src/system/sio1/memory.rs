@@ -0,0 +1,96 @@
+use crate::{
+    system::{
+        bus::types::*,
+        sio1::constants::*,
+        types::State,
+    },
+    utilities::bool_to_flag,
+};
+
+pub(crate) fn data_read_u8(state: &State, offset: u32) -> ReadResult<u8> {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.rx_fifo.read_one().unwrap_or(0))
+}
+
+pub(crate) fn data_write_u8(state: &State, offset: u32, value: u8) -> WriteResult {
+    assert_eq!(offset, 0);
+    state.sio1.tx_fifo.write_one(value).map_err(|_| WriteErrorKind::Full)
+}
+
+pub(crate) fn data_read_u32(state: &State, offset: u32) -> ReadResult<u32> {
+    assert_eq!(offset, 0);
+    // Only a single byte is removed from the FIFO; the upper bytes are not emulated.
+    data_read_u8(state, offset).map(|value| value as u32)
+}
+
+pub(crate) fn data_write_u32(state: &State, offset: u32, value: u32) -> WriteResult {
+    assert_eq!(offset, 0);
+    data_write_u8(state, offset, value as u8)
+}
+
+pub(crate) fn stat_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
+    assert_eq!(offset, 0);
+    stat_read_u32(state, offset).map(|value| value as u16)
+}
+
+pub(crate) fn stat_write_u16(_state: &State, offset: u32, _value: u16) -> WriteResult {
+    assert_eq!(offset, 0);
+    // Read-only register.
+    Ok(())
+}
+
+pub(crate) fn stat_read_u32(state: &State, offset: u32) -> ReadResult<u32> {
+    assert_eq!(offset, 0);
+    let mut value = state.sio1.stat.read_u32();
+    let tx_empty = state.sio1.tx_fifo.is_empty();
+    value = STAT_TXRDY_1.insert_into(value, bool_to_flag(tx_empty));
+    value = STAT_RXFIFO_READY.insert_into(value, bool_to_flag(!state.sio1.rx_fifo.is_empty()));
+    value = STAT_TXRDY_2.insert_into(value, bool_to_flag(tx_empty) & STAT_TXRDY_2.extract_from(value));
+    Ok(value)
+}
+
+pub(crate) fn stat_write_u32(_state: &State, offset: u32, _value: u32) -> WriteResult {
+    assert_eq!(offset, 0);
+    // Read-only register.
+    Ok(())
+}
+
+pub(crate) fn mode_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.mode.read_u16())
+}
+
+pub(crate) fn mode_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.mode.write_u16(value))
+}
+
+pub(crate) fn ctrl_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
+    assert_eq!(offset, 0);
+    state.sio1.ctrl.read_u16().map_err(|_| ReadErrorKind::NotReady)
+}
+
+pub(crate) fn ctrl_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
+    assert_eq!(offset, 0);
+    state.sio1.ctrl.write_u16(value).map_err(|_| WriteErrorKind::NotReady)
+}
+
+pub(crate) fn misc_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.misc.read_u16())
+}
+
+pub(crate) fn misc_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.misc.write_u16(value))
+}
+
+pub(crate) fn baud_reload_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.baud_reload.read_u16())
+}
+
+pub(crate) fn baud_reload_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
+    assert_eq!(offset, 0);
+    Ok(state.sio1.baud_reload.write_u16(value))
+}
@@ -0,0 +1,109 @@
+use crate::{
+    system::sio1::constants::*,
+    types::{
+        exclusive_state::ExclusiveState,
+        fifo::Fifo,
+        memory::*,
+    },
+};
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::VecDeque;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) enum TransferState {
+    Idle,
+    /// Byte being shifted in/out; contains the remaining cycles and the value.
+    Transferring(usize, u8),
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct ControllerState {
+    pub(crate) clock: f32,
+    pub(crate) tx_enabled: bool,
+    pub(crate) rx_enabled: bool,
+    pub(crate) dtr_output: bool,
+    pub(crate) rts_output: bool,
+    pub(crate) tx_interrupt_enabled: bool,
+    pub(crate) rx_interrupt_enabled: bool,
+    pub(crate) dsr_interrupt_enabled: bool,
+    /// Number of bytes in the RX FIFO needed to raise an RX interrupt.
+    pub(crate) rx_interrupt_count: usize,
+    /// Baud rate timer; reloaded with (reload value * factor / 2) when it reaches 0.
+    pub(crate) baud_timer: usize,
+    pub(crate) tx_state: TransferState,
+    pub(crate) rx_state: TransferState,
+    /// Input lines, as driven by the remote end of the link cable.
+    pub(crate) dsr_input: bool,
+    pub(crate) cts_input: bool,
+    /// Output lines last sent to the link backend.
+    pub(crate) output_lines_sent: (bool, bool),
+    /// Bytes received from the link backend, waiting to be shifted in.
+    pub(crate) rx_pending: VecDeque<u8>,
+    /// Bytes shifted out, waiting to be sent to the link backend.
+    pub(crate) tx_pending: VecDeque<u8>,
+    /// Previous TX ready, RX interrupt and DSR conditions, used to raise interrupts on their rising edges.
+    pub(crate) tx_ready_old: bool,
+    pub(crate) rx_ready_old: bool,
+    pub(crate) dsr_old: bool,
+}
+
+impl ControllerState {
+    pub(crate) fn new() -> ControllerState {
+        ControllerState {
+            clock: 0.0,
+            tx_enabled: false,
+            rx_enabled: false,
+            dtr_output: false,
+            rts_output: false,
+            tx_interrupt_enabled: false,
+            rx_interrupt_enabled: false,
+            dsr_interrupt_enabled: false,
+            rx_interrupt_count: 1,
+            baud_timer: 0,
+            tx_state: TransferState::Idle,
+            rx_state: TransferState::Idle,
+            dsr_input: false,
+            cts_input: false,
+            output_lines_sent: (false, false),
+            rx_pending: VecDeque::new(),
+            tx_pending: VecDeque::new(),
+            tx_ready_old: false,
+            rx_ready_old: false,
+            dsr_old: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct State {
+    pub(crate) rx_fifo: Fifo<u8>,
+    pub(crate) tx_fifo: Fifo<u8>,
+    pub(crate) stat: B32LevelRegister,
+    pub(crate) mode: B16LevelRegister,
+    pub(crate) ctrl: B16EdgeRegister,
+    pub(crate) misc: B16LevelRegister,
+    pub(crate) baud_reload: B16LevelRegister,
+    pub(crate) controller_state: ExclusiveState<ControllerState>,
+}
+
+impl State {
+    pub(crate) fn new() -> State {
+        State {
+            rx_fifo: Fifo::new(RX_FIFO_SIZE),
+            tx_fifo: Fifo::new(TX_FIFO_SIZE),
+            stat: B32LevelRegister::new(),
+            mode: B16LevelRegister::new(),
+            ctrl: B16EdgeRegister::new(),
+            misc: B16LevelRegister::new(),
+            baud_reload: B16LevelRegister::new(),
+            controller_state: ExclusiveState::new(ControllerState::new()),
+        }
+    }
+}
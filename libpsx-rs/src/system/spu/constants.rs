@@ -5,19 +5,25 @@ pub(crate) const _CONTROL_EXTERNAL_AUDIO_ENABLE: Bitfield = Bitfield::new(1, 1);
 pub(crate) const _CONTROL_CD_AUDIO_REVERB: Bitfield = Bitfield::new(2, 1);
 pub(crate) const _CONTROL_EXTERNAL_AUDIO_REVERB: Bitfield = Bitfield::new(3, 1);
 pub(crate) const CONTROL_TRANSFER_MODE: Bitfield = Bitfield::new(4, 2);
+pub(crate) const CONTROL_IRQ_ENABLE: Bitfield = Bitfield::new(6, 1);
+pub(crate) const CONTROL_REVERB_ENABLE: Bitfield = Bitfield::new(7, 1);
+pub(crate) const CONTROL_NOISE_STEP: Bitfield = Bitfield::new(8, 2);
+pub(crate) const CONTROL_NOISE_SHIFT: Bitfield = Bitfield::new(10, 4);
 pub(crate) const CONTROL_UNMUTE: Bitfield = Bitfield::new(14, 1);
 pub(crate) const _CONTROL_ENABLE: Bitfield = Bitfield::new(15, 1);
 
-pub(crate) const _STAT_CD_AUDIO_ENABLE: Bitfield = Bitfield::new(0, 1);
+pub(crate) const STAT_CD_AUDIO_ENABLE: Bitfield = Bitfield::new(0, 1);
 pub(crate) const _STAT_EXTERNAL_AUDIO_ENABLE: Bitfield = Bitfield::new(1, 1);
-pub(crate) const _STAT_CD_AUDIO_REVERB: Bitfield = Bitfield::new(2, 1);
+pub(crate) const STAT_CD_AUDIO_REVERB: Bitfield = Bitfield::new(2, 1);
 pub(crate) const _STAT_EXTERNAL_AUDIO_REVERB: Bitfield = Bitfield::new(3, 1);
-pub(crate) const _STAT_IRQ_FLAG: Bitfield = Bitfield::new(6, 1);
-pub(crate) const _STAT_DMA_RW_REQUEST: Bitfield = Bitfield::new(7, 1);
-pub(crate) const _STAT_DMA_W_REQUEST: Bitfield = Bitfield::new(8, 1);
-pub(crate) const _STAT_DMA_R_REQUEST: Bitfield = Bitfield::new(9, 1);
+pub(crate) const STAT_IRQ_FLAG: Bitfield = Bitfield::new(6, 1);
+pub(crate) const STAT_DMA_RW_REQUEST: Bitfield = Bitfield::new(7, 1);
+pub(crate) const STAT_DMA_W_REQUEST: Bitfield = Bitfield::new(8, 1);
+pub(crate) const STAT_DMA_R_REQUEST: Bitfield = Bitfield::new(9, 1);
 pub(crate) const STAT_DATA_BUSY_FLAG: Bitfield = Bitfield::new(10, 1);
-pub(crate) const _STAT_WRITING_BUFFER_HALF: Bitfield = Bitfield::new(11, 1);
+pub(crate) const STAT_WRITING_BUFFER_HALF: Bitfield = Bitfield::new(11, 1);
+
+pub(crate) const DATA_TRANSFER_TYPE: Bitfield = Bitfield::new(1, 3);
 
 pub(crate) const VOLUME_MODE: Bitfield = Bitfield::new(15, 1);
 
@@ -51,3 +57,21 @@ pub(crate) const SAMPLE_RATE: f32 = 44100.0; // 44.1 kHz
 pub(crate) const SAMPLE_RATE_PERIOD: f32 = 1.0 / SAMPLE_RATE;
 pub(crate) const BUFFER_SIZE: usize = 2048;
 pub(crate) const VOICES_COUNT: usize = 24;
+/// Halfwords buffered ahead from SPU RAM in DMA read mode.
+pub(crate) const DATA_FIFO_READ_SIZE: usize = 32;
+/// Capture buffers (1 KiB each, 512 samples) at the start of SPU RAM.
+pub(crate) const CAPTURE_BUFFER_SIZE: usize = 0x400;
+pub(crate) const CAPTURE_BUFFER_CD_LEFT_ADDRESS: usize = 0x000;
+pub(crate) const CAPTURE_BUFFER_CD_RIGHT_ADDRESS: usize = 0x400;
+pub(crate) const CAPTURE_BUFFER_VOICE1_ADDRESS: usize = 0x800;
+pub(crate) const CAPTURE_BUFFER_VOICE3_ADDRESS: usize = 0xC00;
+/// Mixed output ring buffer size (frames), between the mixer and the resampler.
+pub(crate) const MIXER_BUFFER_SIZE: usize = BUFFER_SIZE * 4;
+/// Level (frames) the resampling ratio is adjusted to keep the mixer ring buffer at.
+pub(crate) const MIXER_TARGET_FRAMES: usize = BUFFER_SIZE * 2;
+/// Amount of audio (frames) queued in the audio backend, below which the ring buffer is drained into it.
+pub(crate) const TARGET_QUEUED_FRAMES: usize = BUFFER_SIZE * 2;
+/// Ticks between checks of the audio backend's queue.
+pub(crate) const BACKEND_POLL_INTERVAL: usize = BUFFER_SIZE / 8;
+/// Maximum deviation of the resampling ratio from 1.0 used for drift compensation.
+pub(crate) const MAX_RESAMPLER_ADJUSTMENT: f64 = 0.005;
@@ -1,5 +1,6 @@
 pub(crate) mod backend_dispatch;
 pub(crate) mod dac;
+pub(crate) mod interrupt;
 pub(crate) mod register;
 pub(crate) mod transfer;
 
@@ -9,7 +10,14 @@ use crate::{
         spu::{
             constants::*,
             controllers::{
-                dac::*,
+                dac::{
+                    capture::*,
+                    cd_audio::*,
+                    mixer::*,
+                    noise::*,
+                    reverb::*,
+                    *,
+                },
                 register::*,
                 transfer::*,
             },
@@ -35,14 +43,6 @@ fn run_time(state: &State, audio_backend: &AudioBackend, duration: f32) -> Contr
     controller_state.clock += duration;
     controller_state.dac_state.clock += duration;
 
-    if state.spu.voice_channel_fm.read_u32() > 0 {
-        return Err(format!("Pitch modulation not implemented"));
-    }
-
-    if state.spu.voice_channel_noise.read_u32() > 0 {
-        return Err(format!("Noise generation not implemented"));
-    }
-
     loop {
         let mut handled = false;
 
@@ -77,9 +77,19 @@ fn handle_tick(state: &State, controller_state: &mut ControllerState) -> Control
 }
 
 fn handle_dac_tick(state: &State, audio_backend: &AudioBackend, controller_state: &mut ControllerState) -> ControllerResult<()> {
+    handle_noise(controller_state);
+
     for voice_id in 0..VOICES_COUNT {
-        handle_dac(state, controller_state, audio_backend, voice_id)?;
+        handle_dac(state, controller_state, voice_id);
     }
 
+    handle_cd_audio(state, controller_state);
+
+    handle_capture_position(state, controller_state);
+
+    handle_reverb(state, controller_state);
+
+    handle_mixer(state, controller_state, audio_backend)?;
+
     Ok(())
 }
@@ -9,11 +9,21 @@ use crate::{
     types::stereo::Stereo,
 };
 
-pub(crate) fn play_pcm_samples(audio_backend: &AudioBackend, sample_buffer: &[Stereo], voice_id: usize) -> ControllerResult<Result<(), ()>> {
+pub(crate) fn play_pcm_samples(audio_backend: &AudioBackend, sample_buffer: &[Stereo]) -> ControllerResult<Result<(), ()>> {
     match audio_backend {
         AudioBackend::None => Ok(Err(())),
         #[cfg(openal)]
-        AudioBackend::Openal(ref backend_params) => Ok(Ok(openal::play_pcm_samples(backend_params, sample_buffer, voice_id)?)),
+        AudioBackend::Openal(ref backend_params) => Ok(Ok(openal::play_pcm_samples(backend_params, sample_buffer)?)),
+        _ => unimplemented!(),
+    }
+}
+
+/// Number of frames queued in the backend that haven't been played yet.
+pub(crate) fn queued_frames(audio_backend: &AudioBackend) -> ControllerResult<Result<usize, ()>> {
+    match audio_backend {
+        AudioBackend::None => Ok(Err(())),
+        #[cfg(openal)]
+        AudioBackend::Openal(ref backend_params) => Ok(Ok(openal::queued_frames(backend_params)?)),
         _ => unimplemented!(),
     }
 }
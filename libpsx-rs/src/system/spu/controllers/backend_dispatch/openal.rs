@@ -3,34 +3,76 @@ use crate::{
         rendering::*,
         *,
     },
-    system::types::ControllerResult,
+    system::{
+        spu::constants::*,
+        types::ControllerResult,
+    },
     types::stereo::*,
 };
 use openal_sys::*;
 
-pub(crate) fn play_pcm_samples(backend_params: &BackendParams, samples: &[Stereo], voice_id: usize) -> ControllerResult<()> {
+pub(crate) fn play_pcm_samples(backend_params: &BackendParams, samples: &[Stereo]) -> ControllerResult<()> {
     let (_context_guard, _context) = backend_params.context.guard();
 
     unsafe {
-        let buffer_index = if !RENDERING_ODD_BUFFER[voice_id] {
-            voice_id * 2
-        } else {
-            voice_id * 2 + 1
-        };
+        reclaim_processed_buffers();
+
+        if FREE_BUFFERS_COUNT == 0 {
+            // The mixer only sends buffers while less than its target is queued, so this shouldn't happen.
+            log::warn!("OpenAL audio backend: every buffer is still queued, dropping {} frames", samples.len());
+            return Ok(());
+        }
+
+        FREE_BUFFERS_COUNT -= 1;
+        let buffer = FREE_BUFFERS[FREE_BUFFERS_COUNT];
 
         let samples_size = (samples.len() * std::mem::size_of::<Stereo>()) as ALsizei;
-        alBufferData(BUFFERS[buffer_index], AL_FORMAT_STEREO16 as ALenum, samples.as_ptr() as *const std::ffi::c_void, samples_size, 44100);
+        alBufferData(buffer, AL_FORMAT_STEREO16 as ALenum, samples.as_ptr() as *const std::ffi::c_void, samples_size, SAMPLE_RATE as ALsizei);
+        alSourceQueueBuffers(SOURCE, 1, &buffer);
 
-        alSourceStop(SOURCES[voice_id]);
-        alSourcei(SOURCES[voice_id], AL_BUFFER as ALenum, BUFFERS[buffer_index] as ALint);
-        alSourcePlay(SOURCES[voice_id]);
+        // The source stops by itself if it runs out of queued buffers.
+        let mut source_state = 0;
+        alGetSourcei(SOURCE, AL_SOURCE_STATE as ALenum, &mut source_state);
+        if source_state != AL_PLAYING as ALint {
+            alSourcePlay(SOURCE);
+        }
 
         if alGetError() != AL_NO_ERROR as ALenum {
-            return Err("Error in OpenAL audio backend: playing source".into());
+            return Err("Error in OpenAL audio backend: queueing buffer".into());
         }
-
-        RENDERING_ODD_BUFFER[voice_id] = !RENDERING_ODD_BUFFER[voice_id];
     }
 
     Ok(())
 }
+
+pub(crate) fn queued_frames(backend_params: &BackendParams) -> ControllerResult<usize> {
+    let (_context_guard, _context) = backend_params.context.guard();
+
+    unsafe {
+        reclaim_processed_buffers();
+
+        // The sample offset is relative to the start of the buffer currently playing.
+        let mut sample_offset = 0;
+        alGetSourcei(SOURCE, AL_SAMPLE_OFFSET as ALenum, &mut sample_offset);
+
+        if alGetError() != AL_NO_ERROR as ALenum {
+            return Err("Error in OpenAL audio backend: querying source".into());
+        }
+
+        let queued_buffers = STREAM_BUFFERS_COUNT - FREE_BUFFERS_COUNT;
+        Ok((queued_buffers * BUFFER_SIZE).saturating_sub(sample_offset as usize))
+    }
+}
+
+/// Returns buffers that have finished playing to the free list.
+unsafe fn reclaim_processed_buffers() {
+    let mut processed_count = 0;
+    alGetSourcei(SOURCE, AL_BUFFERS_PROCESSED as ALenum, &mut processed_count);
+
+    for _ in 0..processed_count {
+        let mut buffer = 0;
+        alSourceUnqueueBuffers(SOURCE, 1, &mut buffer);
+        FREE_BUFFERS[FREE_BUFFERS_COUNT] = buffer;
+        FREE_BUFFERS_COUNT += 1;
+    }
+}
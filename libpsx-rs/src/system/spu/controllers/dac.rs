@@ -1,36 +1,38 @@
 pub(crate) mod adpcm;
 pub(crate) mod adsr;
+pub(crate) mod capture;
+pub(crate) mod cd_audio;
 pub(crate) mod interpolation;
+pub(crate) mod mixer;
+pub(crate) mod noise;
 pub(crate) mod pitch;
+pub(crate) mod reverb;
 pub(crate) mod voice;
 pub(crate) mod volume;
 
 use crate::{
-    backends::audio::AudioBackend,
     system::{
         spu::{
             constants::*,
-            controllers::{
-                backend_dispatch,
-                dac::{
-                    adpcm::*,
-                    adsr::*,
-                    interpolation::*,
-                    pitch::*,
-                    voice::*,
-                    volume::*,
-                },
+            controllers::dac::{
+                adpcm::*,
+                adsr::*,
+                capture::*,
+                interpolation::*,
+                mixer::*,
+                pitch::*,
+                reverb::*,
+                voice::*,
+                volume::*,
             },
             types::*,
         },
-        types::{
-            ControllerResult,
-            State,
-        },
+        types::State,
     },
+    types::bitfield::Bitfield,
 };
 
-pub(crate) fn handle_dac(state: &State, controller_state: &mut ControllerState, audio_backend: &AudioBackend, voice_id: usize) -> ControllerResult<()> {
+pub(crate) fn handle_dac(state: &State, controller_state: &mut ControllerState, voice_id: usize) {
     handle_adpcm_block(state, controller_state, voice_id);
 
     let adpcm_sample_raw = {
@@ -40,30 +42,32 @@ pub(crate) fn handle_dac(state: &State, controller_state: &mut ControllerState,
 
     let adpcm_sample = handle_interpolation(controller_state, voice_id, adpcm_sample_raw);
 
+    // Noise replaces the ADPCM output; the ADPCM decoding (and loop handling) carries on regardless.
+    let sample = if state.spu.voice_channel_noise.read_bitfield(Bitfield::new(voice_id, 1)) > 0 {
+        controller_state.dac_state.noise_state.level as i16
+    } else {
+        adpcm_sample
+    };
+
     handle_pitch_counter(state, controller_state, voice_id);
 
     handle_adsr_envelope(state, controller_state, voice_id);
 
-    let pcm_frame = apply_sample_volume(state, controller_state, voice_id, adpcm_sample);
-
-    get_voice_state(controller_state, voice_id).sample_buffer.push(pcm_frame);
+    let sample = apply_adsr_volume(controller_state, voice_id, sample);
 
-    handle_play_sound_buffer(controller_state, audio_backend, voice_id)?;
+    get_voice_state(controller_state, voice_id).output_sample = sample;
 
-    Ok(())
-}
-
-fn handle_play_sound_buffer(controller_state: &mut ControllerState, audio_backend: &AudioBackend, voice_id: usize) -> ControllerResult<()> {
-    let muted = controller_state.muted;
-    let voice_state = get_voice_state(controller_state, voice_id);
+    match voice_id {
+        1 => write_capture_sample(state, controller_state, CAPTURE_BUFFER_VOICE1_ADDRESS, sample),
+        3 => write_capture_sample(state, controller_state, CAPTURE_BUFFER_VOICE3_ADDRESS, sample),
+        _ => {},
+    }
 
-    if voice_state.sample_buffer.len() == BUFFER_SIZE {
-        if !muted {
-            let _ = backend_dispatch::play_pcm_samples(audio_backend, &voice_state.sample_buffer, voice_id)?;
-        }
+    let pcm_frame = apply_voice_volume(state, controller_state, voice_id, sample);
 
-        voice_state.sample_buffer.clear();
+    if state.spu.voice_channel_reverb.read_bitfield(Bitfield::new(voice_id, 1)) > 0 {
+        add_reverb_input(controller_state, pcm_frame);
     }
 
-    Ok(())
+    add_mixer_input(controller_state, pcm_frame);
 }
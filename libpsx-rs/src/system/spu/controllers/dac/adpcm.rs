@@ -2,7 +2,10 @@ use crate::{
     system::{
         spu::{
             constants::*,
-            controllers::dac::voice::*,
+            controllers::{
+                dac::voice::*,
+                interrupt::*,
+            },
             types::*,
         },
         types::State,
@@ -30,6 +33,8 @@ pub(crate) fn handle_adpcm_block(state: &State, controller_state: &mut Controlle
         current_address
     };
 
+    handle_irq_check(state, controller_state, decoding_address, 16);
+
     let block = read_block(&controller_state.memory, decoding_address);
     let voice_state = get_voice_state(controller_state, voice_id);
     let params = decode_header(block.header);
@@ -119,7 +119,7 @@ fn extract_phase_params(adsr_value: u32, phase: AdsrPhase) -> AdsrPhaseParams {
     }
 }
 
-fn calculate_envelope_delta(params: AdsrPhaseParams, current_level: i16) -> (i16, usize) {
+pub(crate) fn calculate_envelope_delta(params: AdsrPhaseParams, current_level: i16) -> (i16, usize) {
     let mut wait_cycles = 1 << (max(0, params.shift as isize - 11) as usize);
 
     let base_step = match params.direction {
@@ -0,0 +1,30 @@
+use crate::{
+    system::{
+        spu::{
+            constants::*,
+            controllers::interrupt::*,
+            types::*,
+        },
+        types::State,
+    },
+    utilities::bool_to_flag,
+};
+
+/// Writes a sample to a capture buffer, at the current capture position.
+pub(crate) fn write_capture_sample(state: &State, controller_state: &mut ControllerState, buffer_address: usize, sample: i16) {
+    let address = buffer_address + controller_state.dac_state.capture_offset;
+    let bytes = sample.to_le_bytes();
+    controller_state.memory[address] = bytes[0];
+    controller_state.memory[address + 1] = bytes[1];
+
+    handle_irq_check(state, controller_state, address, 2);
+}
+
+/// Advances the capture position, after all the capture buffers have been written for the current tick.
+pub(crate) fn handle_capture_position(state: &State, controller_state: &mut ControllerState) {
+    let capture_offset = &mut controller_state.dac_state.capture_offset;
+    *capture_offset = (*capture_offset + 2) % CAPTURE_BUFFER_SIZE;
+
+    let second_half = *capture_offset >= (CAPTURE_BUFFER_SIZE / 2);
+    state.spu.stat.write_bitfield(STAT_WRITING_BUFFER_HALF, bool_to_flag(second_half) as u16);
+}
@@ -0,0 +1,38 @@
+use crate::{
+    system::{
+        spu::{
+            constants::*,
+            controllers::dac::{
+                capture::*,
+                mixer::*,
+                reverb::*,
+                volume::*,
+            },
+            types::*,
+        },
+        types::State,
+    },
+    types::stereo::Stereo,
+};
+
+pub(crate) fn handle_cd_audio(state: &State, controller_state: &mut ControllerState) {
+    // The CD audio is consumed at 44.1 kHz regardless of whether it is enabled.
+    let pcm_frame = state.cdrom.audio.read_one().unwrap_or_default();
+
+    // The input is captured before the CD volume is applied.
+    write_capture_sample(state, controller_state, CAPTURE_BUFFER_CD_LEFT_ADDRESS, pcm_frame.left);
+    write_capture_sample(state, controller_state, CAPTURE_BUFFER_CD_RIGHT_ADDRESS, pcm_frame.right);
+
+    // The control register's enable bits are mirrored in the status register.
+    let pcm_frame = if state.spu.stat.read_bitfield(STAT_CD_AUDIO_ENABLE) > 0 {
+        apply_cd_volume(state, pcm_frame)
+    } else {
+        Stereo::new(0, 0)
+    };
+
+    if state.spu.stat.read_bitfield(STAT_CD_AUDIO_REVERB) > 0 {
+        add_reverb_input(controller_state, pcm_frame);
+    }
+
+    add_mixer_cd_input(controller_state, pcm_frame);
+}
@@ -0,0 +1,130 @@
+use crate::{
+    backends::audio::AudioBackend,
+    system::{
+        spu::{
+            constants::*,
+            controllers::{
+                backend_dispatch,
+                dac::volume::*,
+            },
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
+    },
+    types::stereo::Stereo,
+};
+use num_traits::clamp;
+
+/// Adds a voice or reverb frame to the mix for the current tick.
+pub(crate) fn add_mixer_input(controller_state: &mut ControllerState, pcm_frame: Stereo) {
+    let mixer_state = &mut controller_state.dac_state.mixer_state;
+    mixer_state.input_left += pcm_frame.left as i32;
+    mixer_state.input_right += pcm_frame.right as i32;
+}
+
+pub(crate) fn add_mixer_cd_input(controller_state: &mut ControllerState, pcm_frame: Stereo) {
+    controller_state.dac_state.mixer_state.cd_input = pcm_frame;
+}
+
+/// Mixes the frame for the current tick into the output ring buffer, and feeds the audio backend from it.
+pub(crate) fn handle_mixer(state: &State, controller_state: &mut ControllerState, audio_backend: &AudioBackend) -> ControllerResult<()> {
+    let muted = controller_state.muted;
+
+    let pcm_frame = {
+        let mixer_state = &mut controller_state.dac_state.mixer_state;
+
+        // The SPU mute doesn't affect the CD audio input.
+        let (voices_left, voices_right) = if muted {
+            (0, 0)
+        } else {
+            (mixer_state.input_left, mixer_state.input_right)
+        };
+
+        let left = saturate(voices_left + mixer_state.cd_input.left as i32);
+        let right = saturate(voices_right + mixer_state.cd_input.right as i32);
+
+        mixer_state.input_left = 0;
+        mixer_state.input_right = 0;
+        mixer_state.cd_input = Stereo::new(0, 0);
+
+        Stereo::new(left, right)
+    };
+
+    let pcm_frame = apply_main_volume(state, controller_state, pcm_frame);
+
+    // The ring buffer only overflows if the emulation runs ahead of the audio output for longer than the ratio adjustment
+    // can absorb; drop the frame if so.
+    let _ = controller_state.dac_state.mixer_state.output.write_one(pcm_frame);
+
+    handle_resampler(controller_state, audio_backend)
+}
+
+/// Drains the output ring buffer through the resampler whenever the audio backend's queue is below its target, a buffer
+/// at a time.
+fn handle_resampler(controller_state: &mut ControllerState, audio_backend: &AudioBackend) -> ControllerResult<()> {
+    let mixer_state = &mut controller_state.dac_state.mixer_state;
+
+    if mixer_state.backend_poll_countdown > 0 {
+        mixer_state.backend_poll_countdown -= 1;
+        return Ok(());
+    }
+    mixer_state.backend_poll_countdown = BACKEND_POLL_INTERVAL;
+
+    loop {
+        match backend_dispatch::queued_frames(audio_backend)? {
+            Ok(queued_frames) if queued_frames >= TARGET_QUEUED_FRAMES => return Ok(()),
+            Ok(_) => {},
+            Err(()) => {
+                // No audio output.
+                mixer_state.output.clear();
+                return Ok(());
+            },
+        }
+
+        // Speed up or slow down the output slightly, depending on how far the ring buffer is from its target level.
+        let error = (mixer_state.output.read_available() as f64 - MIXER_TARGET_FRAMES as f64) / MIXER_TARGET_FRAMES as f64;
+        mixer_state.resampler_state.ratio = 1.0 + clamp(error, -1.0, 1.0) * MAX_RESAMPLER_ADJUSTMENT;
+
+        if !resample(mixer_state) {
+            return Ok(());
+        }
+
+        let _ = backend_dispatch::play_pcm_samples(audio_backend, &mixer_state.sample_buffer)?;
+        mixer_state.sample_buffer.clear();
+    }
+}
+
+/// Resamples frames from the ring buffer until the sample buffer is full; returns false if the ring buffer ran out
+/// first.
+fn resample(mixer_state: &mut MixerState) -> bool {
+    let resampler_state = &mut mixer_state.resampler_state;
+
+    while mixer_state.sample_buffer.len() < BUFFER_SIZE {
+        while resampler_state.position >= 1.0 {
+            let pcm_frame = match mixer_state.output.read_one() {
+                Ok(pcm_frame) => pcm_frame,
+                Err(()) => return false,
+            };
+
+            resampler_state.previous = resampler_state.current;
+            resampler_state.current = pcm_frame;
+            resampler_state.position -= 1.0;
+        }
+
+        let position = resampler_state.position;
+        let interpolate = |previous: i16, current: i16| (previous as f64 + (current as f64 - previous as f64) * position) as i16;
+        let left = interpolate(resampler_state.previous.left, resampler_state.current.left);
+        let right = interpolate(resampler_state.previous.right, resampler_state.current.right);
+        mixer_state.sample_buffer.push(Stereo::new(left, right));
+        resampler_state.position += resampler_state.ratio;
+    }
+
+    true
+}
+
+fn saturate(value: i32) -> i16 {
+    clamp(value, std::i16::MIN as i32, std::i16::MAX as i32) as i16
+}
@@ -0,0 +1,23 @@
+use crate::system::spu::types::*;
+
+/// Advances the noise generator; the noise level is updated whenever the timer expires, at a rate set by the noise step
+/// and shift.
+pub(crate) fn handle_noise(controller_state: &mut ControllerState) {
+    let step = controller_state.noise_step as isize + 4;
+    let reload = 0x20000 >> controller_state.noise_shift;
+    let noise_state = &mut controller_state.dac_state.noise_state;
+
+    noise_state.timer -= step;
+
+    if noise_state.timer < 0 {
+        let level = noise_state.level;
+        let parity = ((level >> 15) ^ (level >> 12) ^ (level >> 11) ^ (level >> 10) ^ 1) & 1;
+        noise_state.level = (level << 1) | parity;
+
+        // The timer is reloaded at most twice (the step can exceed the reload value at the highest frequencies).
+        noise_state.timer += reload;
+        if noise_state.timer < 0 {
+            noise_state.timer += reload;
+        }
+    }
+}
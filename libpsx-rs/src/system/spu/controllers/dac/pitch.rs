@@ -13,10 +13,9 @@ pub(crate) fn handle_pitch_counter(state: &State, controller_state: &mut Control
     const PITCH_COUNTER_INTERP: Bitfield = Bitfield::new(0, 12);
     const PITCH_COUNTER_SAMPLE: Bitfield = Bitfield::new(12, 4);
 
+    let sample_rate_value = calculate_pitch_step(state, controller_state, voice_id);
     let voice_state = get_voice_state(controller_state, voice_id);
-    let sample_rate = get_srate(state, voice_id);
 
-    let sample_rate_value = sample_rate.read_u16() as usize;
     let partial_value = PITCH_COUNTER_INTERP.extract_from(sample_rate_value);
     let index_value = PITCH_COUNTER_SAMPLE.extract_from(sample_rate_value);
 
@@ -32,3 +31,20 @@ pub(crate) fn handle_pitch_counter(state: &State, controller_state: &mut Control
     voice_state.current_address += current_address_delta;
     voice_state.current_address &= 0x7FFFF;
 }
+
+/// The sample rate, modulated by the previous voice's output when pitch modulation is enabled, and limited to 0x4000
+/// (176.4 kHz).
+fn calculate_pitch_step(state: &State, controller_state: &mut ControllerState, voice_id: usize) -> usize {
+    let mut step = get_srate(state, voice_id).read_u16() as usize;
+
+    // Voice 0 can't be modulated.
+    let modulated = (voice_id > 0) && (state.spu.voice_channel_fm.read_bitfield(Bitfield::new(voice_id, 1)) > 0);
+
+    if modulated {
+        // The factor ranges from 0.0 to ~2.0; the sample rate is treated as signed (a hardware glitch for values above 0x7FFF).
+        let factor = get_voice_state(controller_state, voice_id - 1).output_sample as isize + 0x8000;
+        step = (((step as u16 as i16 as isize * factor) >> 15) as u16) as usize;
+    }
+
+    step.min(0x4000)
+}
@@ -0,0 +1,162 @@
+use crate::{
+    system::{
+        spu::{
+            controllers::dac::{
+                mixer::*,
+                volume::*,
+            },
+            types::*,
+        },
+        types::State,
+    },
+    types::{
+        memory::*,
+        stereo::Stereo,
+    },
+};
+use num_traits::clamp;
+
+/// Adds a (volume transformed) voice or CD audio frame to the reverb input for the current tick.
+pub(crate) fn add_reverb_input(controller_state: &mut ControllerState, pcm_frame: Stereo) {
+    let reverb_state = &mut controller_state.dac_state.reverb_state;
+    reverb_state.input_left += pcm_frame.left as i32;
+    reverb_state.input_right += pcm_frame.right as i32;
+}
+
+pub(crate) fn handle_reverb(state: &State, controller_state: &mut ControllerState) {
+    let odd_tick = {
+        let reverb_state = &mut controller_state.dac_state.reverb_state;
+        reverb_state.odd_tick = !reverb_state.odd_tick;
+        reverb_state.odd_tick
+    };
+
+    if odd_tick {
+        // The input is downsampled to 22.05 kHz by averaging the 2 ticks.
+        let input = {
+            let reverb_state = &mut controller_state.dac_state.reverb_state;
+            let input = Stereo::new(saturate(reverb_state.input_left / 2), saturate(reverb_state.input_right / 2));
+            reverb_state.input_left = 0;
+            reverb_state.input_right = 0;
+            input
+        };
+
+        process_reverb(state, controller_state, input);
+    }
+
+    let pcm_frame = apply_reverb_volume(state, controller_state.dac_state.reverb_state.output);
+    add_mixer_input(controller_state, pcm_frame);
+}
+
+/// Processes one reverb sample (both channels), as per the hardware algorithm (see the "SPU Reverb Formula" section of
+/// the nocash docs).
+fn process_reverb(state: &State, controller_state: &mut ControllerState, input: Stereo) {
+    let base_address = state.spu.reverb_start_address.read_u16() as usize * 8;
+    let write_enabled = controller_state.reverb_enabled;
+    let reverb_state = &mut controller_state.dac_state.reverb_state;
+    let memory = &mut controller_state.memory;
+
+    // The work area may have been moved since the last sample.
+    let current_address = reverb_state.current_address.max(base_address);
+    let memory_size = memory.len();
+    let address = |offset: usize, delta: isize| work_area_address(memory_size, base_address, current_address, offset as isize + delta);
+
+    let dapf1 = state.spu.dapf1.read_u16() as usize * 8;
+    let dapf2 = state.spu.dapf2.read_u16() as usize * 8;
+    let viir = state.spu.viir.read_u16() as i16;
+    let vcomb1 = state.spu.vcomb1.read_u16() as i16;
+    let vcomb2 = state.spu.vcomb2.read_u16() as i16;
+    let vcomb3 = state.spu.vcomb3.read_u16() as i16;
+    let vcomb4 = state.spu.vcomb4.read_u16() as i16;
+    let vwall = state.spu.vwall.read_u16() as i16;
+    let vapf1 = state.spu.vapf1.read_u16() as i16;
+    let vapf2 = state.spu.vapf2.read_u16() as i16;
+    let vin = volume_pair(&state.spu.vin);
+    let msame = offset_pair(&state.spu.msame);
+    let mcomb1 = offset_pair(&state.spu.mcomb1);
+    let mcomb2 = offset_pair(&state.spu.mcomb2);
+    let dsame = offset_pair(&state.spu.dsame);
+    let mdiff = offset_pair(&state.spu.mdiff);
+    let mcomb3 = offset_pair(&state.spu.mcomb3);
+    let mcomb4 = offset_pair(&state.spu.mcomb4);
+    let ddiff = offset_pair(&state.spu.ddiff);
+    let mapf1 = offset_pair(&state.spu.mapf1);
+    let mapf2 = offset_pair(&state.spu.mapf2);
+
+    let input = [input.left as i32, input.right as i32];
+    let mut output = [0; 2];
+
+    for side in 0..2 {
+        let other_side = 1 - side;
+        let input = multiply(input[side], vin[side]);
+
+        if write_enabled {
+            // Same side reflection (L-to-L, R-to-R).
+            let previous = read_sample(memory, address(msame[side], -2));
+            let reflection = read_sample(memory, address(dsame[side], 0));
+            let value = multiply(input + multiply(reflection, vwall) - previous, viir) + previous;
+            write_sample(memory, address(msame[side], 0), value);
+
+            // Different side reflection (R-to-L, L-to-R).
+            let previous = read_sample(memory, address(mdiff[side], -2));
+            let reflection = read_sample(memory, address(ddiff[other_side], 0));
+            let value = multiply(input + multiply(reflection, vwall) - previous, viir) + previous;
+            write_sample(memory, address(mdiff[side], 0), value);
+        }
+
+        // Early echo (comb filter).
+        let mut value = multiply(read_sample(memory, address(mcomb1[side], 0)), vcomb1)
+            + multiply(read_sample(memory, address(mcomb2[side], 0)), vcomb2)
+            + multiply(read_sample(memory, address(mcomb3[side], 0)), vcomb3)
+            + multiply(read_sample(memory, address(mcomb4[side], 0)), vcomb4);
+
+        // Late reverb (all pass filters 1 and 2).
+        for &(mapf, dapf, vapf) in [(mapf1[side], dapf1, vapf1), (mapf2[side], dapf2, vapf2)].iter() {
+            let delayed = read_sample(memory, address(mapf, -(dapf as isize)));
+            value = saturate(value - multiply(delayed, vapf)) as i32;
+            if write_enabled {
+                write_sample(memory, address(mapf, 0), value);
+            }
+            value = multiply(value, vapf) + delayed;
+        }
+
+        output[side] = value;
+    }
+
+    reverb_state.current_address = work_area_address(memory_size, base_address, current_address, 2);
+    reverb_state.output = Stereo::new(saturate(output[0]), saturate(output[1]));
+}
+
+/// Work area offsets are relative to the current buffer address, and wrap around within the work area (reverb start
+/// address to the end of SPU RAM).
+fn work_area_address(memory_size: usize, base_address: usize, current_address: usize, offset: isize) -> usize {
+    let work_area_size = (memory_size - base_address) as isize;
+    let relative_address = (current_address - base_address) as isize + offset;
+    base_address + (relative_address.rem_euclid(work_area_size) as usize & !1)
+}
+
+/// Left value in the lower half, right value in the upper half.
+fn offset_pair(register: &B32LevelRegister) -> [usize; 2] {
+    [register.read_u16(0) as usize * 8, register.read_u16(1) as usize * 8]
+}
+
+fn volume_pair(register: &B32LevelRegister) -> [i16; 2] {
+    [register.read_u16(0) as i16, register.read_u16(1) as i16]
+}
+
+fn multiply(sample: i32, volume: i16) -> i32 {
+    (sample * volume as i32) >> 15
+}
+
+fn saturate(value: i32) -> i16 {
+    clamp(value, std::i16::MIN as i32, std::i16::MAX as i32) as i16
+}
+
+fn read_sample(memory: &[u8], address: usize) -> i32 {
+    i16::from_le_bytes([memory[address], memory[address + 1]]) as i32
+}
+
+fn write_sample(memory: &mut [u8], address: usize, value: i32) {
+    let bytes = saturate(value).to_le_bytes();
+    memory[address] = bytes[0];
+    memory[address + 1] = bytes[1];
+}
@@ -2,7 +2,10 @@ use crate::{
     system::{
         spu::{
             constants::*,
-            controllers::dac::voice::*,
+            controllers::dac::{
+                adsr::calculate_envelope_delta,
+                voice::*,
+            },
             types::*,
         },
         types::State,
@@ -10,46 +13,65 @@ use crate::{
     types::stereo::*,
 };
 use num_traits::clamp;
+use std::cmp::max;
 
-pub(crate) fn apply_sample_volume(state: &State, controller_state: &mut ControllerState, voice_id: usize, adpcm_sample: i16) -> Stereo {
-    // The incoming ADPCM sample (mono) is volume transformed 3 times, and turned into stereo.
-    let adpcm_sample = transform_voice_adsr_volume(controller_state, voice_id, adpcm_sample);
-    let pcm_frame = transform_voice_volume(state, voice_id, adpcm_sample);
-    transform_main_volume(state, pcm_frame)
-}
-
-fn transform_voice_adsr_volume(controller_state: &mut ControllerState, voice_id: usize, adpcm_sample: i16) -> i16 {
+/// The incoming sample (mono) is scaled by the ADSR envelope volume.
+pub(crate) fn apply_adsr_volume(controller_state: &mut ControllerState, voice_id: usize, sample: i16) -> i16 {
     let voice_state = get_voice_state(controller_state, voice_id);
     let adsr_volume_normalized = voice_state.adsr_state.current_volume as f32 / std::i16::MAX as f32;
-    (adpcm_sample as f32 * adsr_volume_normalized) as i16
+    (sample as f32 * adsr_volume_normalized) as i16
 }
 
-fn transform_voice_volume(state: &State, voice_id: usize, adpcm_sample: i16) -> Stereo {
+/// The sample is scaled by the voice volume, and turned into stereo.
+/// The main volume is applied in the mixer, as the reverb unit takes its input before it.
+pub(crate) fn apply_voice_volume(state: &State, controller_state: &mut ControllerState, voice_id: usize, sample: i16) -> Stereo {
     let vol_left = get_voll(state, voice_id);
     let vol_right = get_volr(state, voice_id);
+    let sweep_states = &mut get_voice_state(controller_state, voice_id).volume_sweep_states;
+
+    transform_sample(vol_left.read_u16(), vol_right.read_u16(), sweep_states, Stereo::new(sample, sample))
+}
 
-    transform_sample(vol_left.read_u16(), vol_right.read_u16(), Stereo::new(adpcm_sample, adpcm_sample))
+/// The CD audio input (already stereo) is scaled by the CD volume.
+pub(crate) fn apply_cd_volume(state: &State, pcm_frame: Stereo) -> Stereo {
+    scale_frame(state.spu.cd_volume.read_u32(), pcm_frame)
 }
 
-fn transform_main_volume(state: &State, pcm_frame: Stereo) -> Stereo {
+/// The reverb unit output is scaled by the reverb output volume (vLOUT / vROUT).
+pub(crate) fn apply_reverb_volume(state: &State, pcm_frame: Stereo) -> Stereo {
+    scale_frame(state.spu.reverb_volume.read_u32(), pcm_frame)
+}
+
+pub(crate) fn apply_main_volume(state: &State, controller_state: &mut ControllerState, pcm_frame: Stereo) -> Stereo {
     let mvol_left = &state.spu.main_volume_left;
     let mvol_right = &state.spu.main_volume_right;
+    let sweep_states = &mut controller_state.dac_state.mixer_state.main_volume_sweep_states;
 
-    transform_sample(mvol_left.read_u16(), mvol_right.read_u16(), pcm_frame)
+    let pcm_frame = transform_sample(mvol_left.read_u16(), mvol_right.read_u16(), sweep_states, pcm_frame);
+    state.spu.current_volume_left.write_u16(sweep_states[0].current_volume as u16);
+    state.spu.current_volume_right.write_u16(sweep_states[1].current_volume as u16);
+    pcm_frame
 }
 
-fn transform_sample(left_volume_value: u16, right_volume_value: u16, pcm_frame: Stereo) -> Stereo {
-    let process_sample = |volume_value, sample| -> i16 {
+/// Volume pair registers hold the left volume in the lower half, and the right volume in the upper half.
+fn scale_frame(volume: u32, pcm_frame: Stereo) -> Stereo {
+    let scale = |sample: i16, volume: u16| ((sample as i32 * volume as i16 as i32) >> 15) as i16;
+    Stereo::new(scale(pcm_frame.left, volume as u16), scale(pcm_frame.right, (volume >> 16) as u16))
+}
+
+/// Sweep volumes are stepped once per call, so this must be called once per tick for each volume register pair.
+fn transform_sample(left_volume_value: u16, right_volume_value: u16, sweep_states: &mut [SweepState; 2], pcm_frame: Stereo) -> Stereo {
+    let process_sample = |volume_value, sweep_state: &mut SweepState, sample| -> i16 {
         if VOLUME_MODE.extract_from(volume_value) > 0 {
-            transform_sample_sweep(sample, extract_sweep_params(volume_value))
+            transform_sample_sweep(sample, extract_sweep_params(volume_value), sweep_state)
         } else {
-            transform_sample_fixed(sample, volume_value)
+            transform_sample_fixed(sample, volume_value, sweep_state)
         }
     };
 
     Stereo {
-        left: process_sample(left_volume_value, pcm_frame.left),
-        right: process_sample(right_volume_value, pcm_frame.right),
+        left: process_sample(left_volume_value, &mut sweep_states[0], pcm_frame.left),
+        right: process_sample(right_volume_value, &mut sweep_states[1], pcm_frame.right),
     }
 }
 
@@ -75,14 +97,44 @@ fn extract_sweep_params(sweep_value: u16) -> SweepParams {
     }
 }
 
-fn transform_sample_fixed(sample: i16, volume15: u16) -> i16 {
+fn transform_sample_fixed(sample: i16, volume15: u16, sweep_state: &mut SweepState) -> i16 {
     // volume15 is a 15-bit signed integer (fixed mode volume level).
     // Sign-extend it to 16-bit and double it as per docs.
     let signed_volume = (((volume15 << 1) as i16) >> 1) * 2;
+    sweep_state.current_volume = max(signed_volume, 0);
+    sweep_state.wait_cycles = 0;
     let scale_factor = clamp(signed_volume as f32 / std::i16::MAX as f32, -1.0, 1.0);
     (sample as f32 * scale_factor) as i16
 }
 
-fn transform_sample_sweep(sample: i16, sweep_params: SweepParams) -> i16 {
-    unimplemented!("sample: {}, sweep_params: {:?}", sample, sweep_params);
+fn transform_sample_sweep(sample: i16, sweep_params: SweepParams, sweep_state: &mut SweepState) -> i16 {
+    // The volume level is stepped in the same way as an ADSR envelope phase.
+    if sweep_state.wait_cycles > 0 {
+        sweep_state.wait_cycles -= 1;
+    } else {
+        let params = AdsrPhaseParams {
+            step: sweep_params.step,
+            shift: sweep_params.shift,
+            direction: match sweep_params.direction {
+                SweepDirection::Increase => AdsrDirection::Increase,
+                SweepDirection::Decrease => AdsrDirection::Decrease,
+            },
+            mode: match sweep_params.mode {
+                SweepMode::Linear => AdsrMode::Linear,
+                SweepMode::Exponential => AdsrMode::Exponential,
+            },
+        };
+
+        let (delta_volume, wait_cycles) = calculate_envelope_delta(params, sweep_state.current_volume);
+        sweep_state.current_volume = clamp(sweep_state.current_volume as isize + delta_volume as isize, 0, std::i16::MAX as isize) as i16;
+        sweep_state.wait_cycles = wait_cycles;
+    }
+
+    // A negative phase inverts the output.
+    let volume = match sweep_params.phase {
+        SweepPhase::Positive => sweep_state.current_volume as i32,
+        SweepPhase::Negative => -(sweep_state.current_volume as i32),
+    };
+
+    ((sample as i32 * volume) >> 15) as i16
 }
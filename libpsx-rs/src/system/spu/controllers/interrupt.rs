@@ -0,0 +1,30 @@
+use crate::system::{
+    intc::types::Line,
+    spu::{
+        constants::*,
+        types::*,
+    },
+    types::State,
+};
+
+/// Raises the IRQ if the SPU RAM range accessed (voice fetch, data transfer or capture buffer write) contains the IRQ
+/// address.
+pub(crate) fn handle_irq_check(state: &State, controller_state: &ControllerState, address: usize, size: usize) {
+    if !controller_state.irq_enabled {
+        return;
+    }
+
+    let irq_address = state.spu.irq_address.read_u16() as usize * 8;
+    if (address..address + size).contains(&irq_address) {
+        handle_irq_raise(state);
+    }
+}
+
+/// Raises the IRQ flag; the interrupt line is only asserted if the flag was not already set (it needs to be
+/// acknowledged through the control register).
+fn handle_irq_raise(state: &State) {
+    if state.spu.stat.read_bitfield(STAT_IRQ_FLAG) == 0 {
+        state.spu.stat.write_bitfield(STAT_IRQ_FLAG, 1);
+        state.intc.stat.assert_line(Line::Spu);
+    }
+}
@@ -14,12 +14,19 @@ use crate::{
         bitfield::Bitfield,
         memory::*,
     },
-    utilities::bool_to_flag,
 };
 
 pub(crate) fn handle_control(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
     let mut write_fn = |value| {
         controller_state.muted = CONTROL_UNMUTE.extract_from(value) == 0;
+        controller_state.reverb_enabled = CONTROL_REVERB_ENABLE.extract_from(value) > 0;
+
+        controller_state.irq_enabled = CONTROL_IRQ_ENABLE.extract_from(value) > 0;
+        if !controller_state.irq_enabled {
+            state.spu.stat.write_bitfield(STAT_IRQ_FLAG, 0);
+        }
+        controller_state.noise_step = CONTROL_NOISE_STEP.extract_from(value) as usize;
+        controller_state.noise_shift = CONTROL_NOISE_SHIFT.extract_from(value) as usize;
 
         let transfer_mode = match CONTROL_TRANSFER_MODE.extract_from(value) {
             0 => TransferMode::Stop,
@@ -32,11 +39,14 @@ pub(crate) fn handle_control(state: &State, controller_state: &mut ControllerSta
             _ => unreachable!("Invalid transfer mode"),
         };
 
-        controller_state.transfer_state.current_mode = transfer_mode;
-        controller_state.transfer_count = 0;
+        // The FIFO changes direction when entering or leaving DMA read mode, so any data left in it is stale.
+        let previous_mode = controller_state.transfer_state.current_mode;
+        if (previous_mode == TransferMode::DmaRead) != (transfer_mode == TransferMode::DmaRead) {
+            state.spu.data_fifo.clear();
+        }
 
-        let data_busy = transfer_mode != TransferMode::Stop;
-        state.spu.stat.write_bitfield(STAT_DATA_BUSY_FLAG, bool_to_flag(data_busy) as u16);
+        controller_state.transfer_state.current_mode = transfer_mode;
+        controller_state.transfer_state.repeat_count = 0;
 
         let header = Bitfield::new(0, 5);
         state.spu.stat.write_bitfield(header, header.extract_from(value));
@@ -83,6 +93,8 @@ pub(crate) fn handle_key_on(state: &State, controller_state: &mut ControllerStat
                 let mut voice_state = VoiceState::new();
                 let start_address = get_saddr(state, voice_id).read_u16() as usize * 8;
                 voice_state.current_address = start_address;
+                // The voice volume isn't affected by key on.
+                voice_state.volume_sweep_states = get_voice_state(controller_state, voice_id).volume_sweep_states;
                 *get_voice_state(controller_state, voice_id) = voice_state;
                 state.spu.voice_channel_status.write_bitfield(voice_bitfield, 0);
             }
@@ -1,82 +1,101 @@
-use crate::system::{
-    spu::{
-        constants::*,
-        types::*,
-    },
-    types::{
-        ControllerResult,
-        State,
+use crate::{
+    system::{
+        spu::{
+            constants::*,
+            controllers::interrupt::*,
+            types::*,
+        },
+        types::{
+            ControllerResult,
+            State,
+        },
     },
+    utilities::bool_to_flag,
 };
 
 pub(crate) fn handle_transfer(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
     match controller_state.transfer_state.current_mode {
-        TransferMode::Stop => Ok(()),
-        TransferMode::ManualWrite => handle_manual_write_transfer(state, controller_state),
-        TransferMode::DmaWrite => handle_dma_write_transfer(state, controller_state),
-        TransferMode::DmaRead => return Err(format!("DmaRead transfer mode not implemented")),
+        TransferMode::Stop => {},
+        TransferMode::ManualWrite | TransferMode::DmaWrite => handle_write_transfer(state, controller_state),
+        TransferMode::DmaRead => handle_read_transfer(state, controller_state),
     }
+
+    handle_transfer_status(state, controller_state);
+
+    Ok(())
 }
 
-fn handle_manual_write_transfer(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
-    if state.spu.data_transfer_control.read_u16() != 0x4 {
-        return Err(format!("Data transfer control not set to normal mode"));
-    }
+/// Moves one halfword from the FIFO into SPU RAM.
+/// Manual writes drain whatever the CPU has written to the FIFO; DMA writes are fed by DMAC channel 4 while the write
+/// request is set.
+fn handle_write_transfer(state: &State, controller_state: &mut ControllerState) {
+    let value = match state.spu.data_fifo.read_one() {
+        Ok(value) => value,
+        Err(_) => return,
+    };
 
-    let fifo = &state.spu.data_fifo;
-    let memory = &mut controller_state.memory;
-    let current_transfer_mode = &mut controller_state.transfer_state.current_mode;
-    let current_transfer_address = &mut controller_state.transfer_state.current_address;
+    // Other transfer types only write out the last halfword of each group, repeated over the whole group.
+    let group_size = match DATA_TRANSFER_TYPE.extract_from(state.spu.data_transfer_control.read_u16()) {
+        2 => 1,
+        3 => 2,
+        4 => 4,
+        // Fill (0, 1, 6, 7) and Rep8 (5).
+        _ => 8,
+    };
 
-    match fifo.read_one() {
-        Ok(value) => {
-            let bytes = u16::to_le_bytes(value);
-            memory[*current_transfer_address as usize] = bytes[0];
-            memory[*current_transfer_address as usize + 1] = bytes[1];
-            *current_transfer_address += 2;
-            *current_transfer_address &= 0x7FFFF;
-            controller_state.transfer_count += 1;
-        },
-        Err(_) => {
-            *current_transfer_mode = TransferMode::Stop;
-            state.spu.stat.write_bitfield(STAT_DATA_BUSY_FLAG, 0);
-        },
+    let transfer_state = &mut controller_state.transfer_state;
+    transfer_state.repeat_count += 1;
+    if transfer_state.repeat_count < group_size {
+        return;
     }
+    transfer_state.repeat_count = 0;
 
-    Ok(())
+    let start_address = transfer_state.current_address;
+    let bytes = u16::to_le_bytes(value);
+    for _ in 0..group_size {
+        let address = transfer_state.current_address;
+        controller_state.memory[address] = bytes[0];
+        controller_state.memory[address + 1] = bytes[1];
+        transfer_state.current_address = (address + 2) & 0x7FFFF;
+    }
+
+    handle_irq_check(state, controller_state, start_address, group_size * 2);
 }
 
-fn handle_dma_write_transfer(state: &State, controller_state: &mut ControllerState) -> ControllerResult<()> {
-    // TODO: not properly implemented.
+/// Buffers one halfword from SPU RAM into the FIFO, to be read out by DMAC channel 4 (or the CPU).
+fn handle_read_transfer(state: &State, controller_state: &mut ControllerState) {
+    let fifo = &state.spu.data_fifo;
 
-    if state.spu.data_transfer_control.read_u16() != 0x4 {
-        return Err(format!("Data transfer control not set to normal mode"));
+    if fifo.read_available() >= DATA_FIFO_READ_SIZE {
+        return;
     }
 
-    let fifo = &state.spu.data_fifo;
-    let memory = &mut controller_state.memory;
-    let current_transfer_mode = &mut controller_state.transfer_state.current_mode;
-    let current_transfer_address = &mut controller_state.transfer_state.current_address;
+    let address = controller_state.transfer_state.current_address;
+    let value = u16::from_le_bytes([controller_state.memory[address], controller_state.memory[address + 1]]);
+    fifo.write_one(value).unwrap();
+    controller_state.transfer_state.current_address = (address + 2) & 0x7FFFF;
 
-    match fifo.read_one() {
-        Ok(value) => {
-            let bytes = u16::to_le_bytes(value);
-            memory[*current_transfer_address as usize] = bytes[0];
-            memory[*current_transfer_address as usize + 1] = bytes[1];
-            *current_transfer_address += 2;
-            *current_transfer_address &= 0x7FFFF;
-            controller_state.transfer_count += 1;
-        },
-        Err(_) => {
-            let dma_finished = !state.dmac.spu_transfer_flag.load();
-            let dma_started = controller_state.transfer_count != 0;
+    handle_irq_check(state, controller_state, address, 2);
+}
 
-            if dma_started && dma_finished {
-                log::debug!("SPU Control DmaWrite finished");
-                *current_transfer_mode = TransferMode::Stop;
-            }
-        },
-    }
+/// Updates the busy and DMA request flags, which DMAC channel 4 waits on before moving data.
+fn handle_transfer_status(state: &State, controller_state: &ControllerState) {
+    let fifo = &state.spu.data_fifo;
+    let dma_active = state.dmac.spu_transfer_flag.load();
+    let mode = controller_state.transfer_state.current_mode;
 
-    Ok(())
+    let dma_mode = mode == TransferMode::DmaWrite || mode == TransferMode::DmaRead;
+    let write_request = mode == TransferMode::DmaWrite && !fifo.is_full();
+    let read_request = mode == TransferMode::DmaRead && !fifo.is_empty();
+    let busy = match mode {
+        TransferMode::Stop => false,
+        TransferMode::ManualWrite => !fifo.is_empty(),
+        TransferMode::DmaWrite => !fifo.is_empty() || dma_active,
+        TransferMode::DmaRead => dma_active,
+    };
+
+    state.spu.stat.write_bitfield(STAT_DMA_RW_REQUEST, bool_to_flag(dma_mode) as u16);
+    state.spu.stat.write_bitfield(STAT_DMA_W_REQUEST, bool_to_flag(write_request) as u16);
+    state.spu.stat.write_bitfield(STAT_DMA_R_REQUEST, bool_to_flag(read_request) as u16);
+    state.spu.stat.write_bitfield(STAT_DATA_BUSY_FLAG, bool_to_flag(busy) as u16);
 }
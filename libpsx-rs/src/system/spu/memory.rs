@@ -1,6 +1,9 @@
 use crate::system::{
     bus::types::*,
-    spu::controllers::dac::voice::*,
+    spu::{
+        constants::*,
+        controllers::dac::voice::*,
+    },
     types::State,
 };
 
@@ -156,9 +159,13 @@ pub(crate) fn data_transfer_address_write_u16(state: &State, offset: u32, value:
     state.spu.data_transfer_address.write_u16(value).map_err(|_| WriteErrorKind::NotReady)
 }
 
-pub(crate) fn data_fifo_read_u16(_state: &State, offset: u32) -> ReadResult<u16> {
+pub(crate) fn data_fifo_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
     assert_eq!(offset, 0);
-    unimplemented!();
+    // The FIFO only holds data to read out in DMA read mode (buffered from SPU RAM).
+    if state.spu.stat.read_bitfield(STAT_DMA_R_REQUEST) == 0 {
+        return Ok(0);
+    }
+    Ok(state.spu.data_fifo.read_one().unwrap_or(0))
 }
 
 pub(crate) fn data_fifo_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
@@ -334,26 +341,24 @@ pub(crate) fn extern_volume_write_u32(state: &State, offset: u32, value: u32) ->
     Ok(state.spu.extern_volume.write_u32(value))
 }
 
-pub(crate) fn current_volume_left_read_u16(_state: &State, offset: u32) -> ReadResult<u16> {
+pub(crate) fn current_volume_left_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
     assert_eq!(offset, 0);
-    log::warn!("Read from current volume left - returning max volume (0x7FFF)");
-    Ok(0x7FFF)
+    Ok(state.spu.current_volume_left.read_u16())
 }
 
-pub(crate) fn current_volume_left_write_u16(_state: &State, offset: u32, _value: u16) -> WriteResult {
+pub(crate) fn current_volume_left_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
     assert_eq!(offset, 0);
-    unimplemented!();
+    Ok(state.spu.current_volume_left.write_u16(value))
 }
 
-pub(crate) fn current_volume_right_read_u16(_state: &State, offset: u32) -> ReadResult<u16> {
+pub(crate) fn current_volume_right_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
     assert_eq!(offset, 0);
-    log::warn!("Read from current volume right - returning max volume (0x7FFF)");
-    Ok(0x7FFF)
+    Ok(state.spu.current_volume_right.read_u16())
 }
 
-pub(crate) fn current_volume_right_write_u16(_state: &State, offset: u32, _value: u16) -> WriteResult {
+pub(crate) fn current_volume_right_write_u16(state: &State, offset: u32, value: u16) -> WriteResult {
     assert_eq!(offset, 0);
-    unimplemented!();
+    Ok(state.spu.current_volume_right.write_u16(value))
 }
 
 pub(crate) fn unknown_1_read_u16(state: &State, offset: u32) -> ReadResult<u16> {
@@ -19,8 +19,14 @@ pub(crate) use transfer::*;
 pub(crate) struct ControllerState {
     pub(crate) clock: f32,
     pub(crate) muted: bool,
+    /// IRQ enable; clearing it acknowledges the IRQ.
+    pub(crate) irq_enabled: bool,
+    /// Reverb master enable; the work area is only written to while set.
+    pub(crate) reverb_enabled: bool,
+    /// Noise generator frequency, as set in the control register.
+    pub(crate) noise_step: usize,
+    pub(crate) noise_shift: usize,
     pub(crate) transfer_state: TransferState,
-    pub(crate) transfer_count: usize,
     pub(crate) dac_state: DacState,
     pub(crate) memory: Vec<u8>,
 }
@@ -30,8 +36,11 @@ impl ControllerState {
         ControllerState {
             clock: 0.0,
             muted: false,
+            irq_enabled: false,
+            reverb_enabled: false,
+            noise_step: 0,
+            noise_shift: 0,
             transfer_state: TransferState::new(),
-            transfer_count: 0,
             dac_state: DacState::new(),
             memory: vec![0; 0x8_0000],
         }
@@ -43,6 +52,9 @@ impl ControllerState {
 pub(crate) struct State {
     pub(crate) main_volume_left: B16LevelRegister,
     pub(crate) main_volume_right: B16LevelRegister,
+    /// Main volume levels, as currently applied by the mixer.
+    pub(crate) current_volume_left: B16LevelRegister,
+    pub(crate) current_volume_right: B16LevelRegister,
     pub(crate) reverb_volume: B32LevelRegister,
 
     pub(crate) voice_key_on: B32EdgeRegister,
@@ -286,6 +298,8 @@ impl State {
         State {
             main_volume_left: B16LevelRegister::new(),
             main_volume_right: B16LevelRegister::new(),
+            current_volume_left: B16LevelRegister::new(),
+            current_volume_right: B16LevelRegister::new(),
             reverb_volume: B32LevelRegister::new(),
             voice_key_on: B32EdgeRegister::new(),
             voice_key_off: B32EdgeRegister::new(),
@@ -1,12 +1,17 @@
 mod adpcm;
 mod adsr;
 mod interpolation;
+mod mixer;
+mod noise;
+mod reverb;
 mod sweep;
 
-use crate::types::stereo::*;
 pub(crate) use adpcm::*;
 pub(crate) use adsr::*;
 pub(crate) use interpolation::*;
+pub(crate) use mixer::*;
+pub(crate) use noise::*;
+pub(crate) use reverb::*;
 #[cfg(feature = "serialization")]
 use serde::{
     Deserialize,
@@ -37,9 +42,11 @@ pub(crate) struct VoiceState {
     /// tick 0 and 1, with interpolation being used on tick 1.
     pub(crate) sample_counter_index: usize,
     pub(crate) sample_counter_partial: usize,
-    /// PCM sample buffer.
-    /// This is filled with the output of the SPU after all processing is done.
-    pub(crate) sample_buffer: Vec<Stereo>,
+    /// Output sample after the ADSR envelope (before the voice volume).
+    /// Used as the pitch modulation source for the next voice.
+    pub(crate) output_sample: i16,
+    /// Voice volume sweep state (left, right).
+    pub(crate) volume_sweep_states: [SweepState; 2],
 }
 
 impl VoiceState {
@@ -51,7 +58,8 @@ impl VoiceState {
             current_address: 0,
             sample_counter_index: 0,
             sample_counter_partial: 0,
-            sample_buffer: Vec::new(),
+            output_sample: 0,
+            volume_sweep_states: [SweepState::new(); 2],
         }
     }
 }
@@ -84,6 +92,11 @@ pub(crate) struct DacState {
     pub(crate) voice21_state: VoiceState,
     pub(crate) voice22_state: VoiceState,
     pub(crate) voice23_state: VoiceState,
+    /// Capture buffers write position (byte offset within each buffer).
+    pub(crate) capture_offset: usize,
+    pub(crate) noise_state: NoiseState,
+    pub(crate) reverb_state: ReverbState,
+    pub(crate) mixer_state: MixerState,
 }
 
 impl DacState {
@@ -114,6 +127,10 @@ impl DacState {
             voice21_state: VoiceState::new(),
             voice22_state: VoiceState::new(),
             voice23_state: VoiceState::new(),
+            capture_offset: 0,
+            noise_state: NoiseState::new(),
+            reverb_state: ReverbState::new(),
+            mixer_state: MixerState::new(),
         }
     }
 }
@@ -0,0 +1,73 @@
+use crate::{
+    system::spu::{
+        constants::*,
+        types::SweepState,
+    },
+    types::{
+        fifo::Fifo,
+        stereo::*,
+    },
+};
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Linear resampler from the SPU output to the audio backend.
+/// The ratio (input frames per output frame) is adjusted around 1.0 to keep the mixer ring buffer at its target level,
+/// compensating for drift between emulated and real time.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) struct ResamplerState {
+    pub(crate) previous: Stereo,
+    pub(crate) current: Stereo,
+    /// Position of the next output frame between the previous and current input frames.
+    pub(crate) position: f64,
+    pub(crate) ratio: f64,
+}
+
+impl ResamplerState {
+    pub(crate) fn new() -> ResamplerState {
+        ResamplerState {
+            previous: Stereo::new(0, 0),
+            current: Stereo::new(0, 0),
+            position: 0.0,
+            ratio: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) struct MixerState {
+    /// Sum of the voice and reverb frames for the current tick.
+    pub(crate) input_left: i32,
+    pub(crate) input_right: i32,
+    /// CD audio frame for the current tick; kept separate as it isn't affected by the SPU mute.
+    pub(crate) cd_input: Stereo,
+    /// Main volume sweep state (left, right).
+    pub(crate) main_volume_sweep_states: [SweepState; 2],
+    /// Mixed output (44.1 kHz), drained by the resampler at the pace the audio backend plays it.
+    pub(crate) output: Fifo<Stereo>,
+    /// Ticks until the audio backend's queue is next checked.
+    pub(crate) backend_poll_countdown: usize,
+    pub(crate) resampler_state: ResamplerState,
+    /// PCM sample buffer for the audio backend.
+    pub(crate) sample_buffer: Vec<Stereo>,
+}
+
+impl MixerState {
+    pub(crate) fn new() -> MixerState {
+        MixerState {
+            input_left: 0,
+            input_right: 0,
+            cd_input: Stereo::new(0, 0),
+            main_volume_sweep_states: [SweepState::new(); 2],
+            output: Fifo::new(MIXER_BUFFER_SIZE),
+            backend_poll_countdown: 0,
+            resampler_state: ResamplerState::new(),
+            sample_buffer: Vec::new(),
+        }
+    }
+}
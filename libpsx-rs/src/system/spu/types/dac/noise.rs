@@ -0,0 +1,23 @@
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) struct NoiseState {
+    /// Countdown until the next noise level update.
+    pub(crate) timer: isize,
+    /// Noise level (LFSR), shared by all voices with noise enabled.
+    pub(crate) level: u16,
+}
+
+impl NoiseState {
+    pub(crate) fn new() -> NoiseState {
+        NoiseState {
+            timer: 0,
+            level: 0,
+        }
+    }
+}
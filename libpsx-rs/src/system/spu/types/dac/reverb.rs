@@ -0,0 +1,31 @@
+use crate::types::stereo::*;
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) struct ReverbState {
+    /// Current work area (buffer) address, advanced by one sample for every reverb sample processed.
+    pub(crate) current_address: usize,
+    /// Input from the voices (and CD audio) with reverb enabled, summed over the ticks of the current reverb sample.
+    pub(crate) input_left: i32,
+    pub(crate) input_right: i32,
+    /// The reverb unit runs at 22.05 kHz; a sample is processed on every odd tick, and its output held for 2 ticks.
+    pub(crate) odd_tick: bool,
+    pub(crate) output: Stereo,
+}
+
+impl ReverbState {
+    pub(crate) fn new() -> ReverbState {
+        ReverbState {
+            current_address: 0,
+            input_left: 0,
+            input_right: 0,
+            odd_tick: false,
+            output: Stereo::new(0, 0),
+        }
+    }
+}
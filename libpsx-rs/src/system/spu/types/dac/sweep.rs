@@ -1,3 +1,9 @@
+#[cfg(feature = "serialization")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) enum SweepMode {
     Linear,
@@ -24,3 +30,23 @@ pub(crate) struct SweepParams {
     pub(crate) direction: SweepDirection,
     pub(crate) mode: SweepMode,
 }
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub(crate) struct SweepState {
+    /// Current volume level (0 ..= 0x7FFF); follows the fixed volume while not in sweep mode, so a sweep starts from
+    /// it.
+    pub(crate) current_volume: i16,
+    /// Sweep wait cycles.
+    /// The number of cycles to wait before stepping the volume again.
+    pub(crate) wait_cycles: usize,
+}
+
+impl SweepState {
+    pub(crate) fn new() -> SweepState {
+        SweepState {
+            current_volume: 0,
+            wait_cycles: 0,
+        }
+    }
+}
@@ -18,6 +18,8 @@ pub(crate) enum TransferMode {
 pub(crate) struct TransferState {
     pub(crate) current_mode: TransferMode,
     pub(crate) current_address: usize,
+    /// Halfwords received so far in the current repeat group (see the data transfer type).
+    pub(crate) repeat_count: usize,
 }
 
 impl TransferState {
@@ -25,6 +27,7 @@ impl TransferState {
         TransferState {
             current_mode: TransferMode::Stop,
             current_address: 0,
+            repeat_count: 0,
         }
     }
 }
@@ -2,23 +2,38 @@ use crate::{
     backends::{
         audio::AudioBackend,
         cdrom::CdromBackend,
+        sio::SioBackend,
         video::VideoBackend,
     },
+    input::PortConfig,
     system::{
         cdrom::types::State as CdromState,
         dmac::types::State as DmacState,
         gpu::types::State as GpuState,
         intc::types::State as IntcState,
+        mdec::types::State as MdecState,
         memory::types::State as MemoryState,
-        padmc::types::State as PadmcState,
+        padmc::{
+            constants::{
+                MEMORY_CARD_FLUSH_DELAY,
+                MEMORY_CARD_SIZE,
+            },
+            types::{
+                PortState,
+                State as PadmcState,
+            },
+        },
         r3000::types::{
             initialize as r3000_initialize,
             State as R3000State,
         },
+        sio1::types::State as Sio1State,
         spu::types::State as SpuState,
         timers::types::State as TimersState,
     },
     types::flag::Flag,
+    CdromConfig,
+    ConsoleRegion,
 };
 use log::info;
 #[cfg(feature = "serialization")]
@@ -27,12 +42,22 @@ use serde::{
     Serialize,
 };
 use std::{
-    fs::File,
+    fs::{
+        create_dir_all,
+        rename,
+        File,
+    },
     io::{
+        Error as IoError,
+        ErrorKind as IoErrorKind,
         Read,
         Result as IoResult,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
     },
-    path::Path,
 };
 
 pub(crate) type ControllerHandler = fn(&ControllerContext, Event) -> ControllerResult<()>;
@@ -49,6 +74,7 @@ pub(crate) struct ControllerContext<'a: 'b, 'b> {
     pub(crate) video_backend: &'b VideoBackend<'a>,
     pub(crate) audio_backend: &'b AudioBackend<'a>,
     pub(crate) cdrom_backend: &'b CdromBackend<'a>,
+    pub(crate) sio_backend: &'b SioBackend<'a>,
 }
 
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -63,6 +89,8 @@ pub(crate) struct State {
     pub(crate) gpu: GpuState,
     pub(crate) cdrom: CdromState,
     pub(crate) padmc: PadmcState,
+    pub(crate) sio1: Sio1State,
+    pub(crate) mdec: MdecState,
 
     /// Bus lock status
     /// Needed in order to emulate the fact that the CPU is (almost) stopped when DMA transfers are happening.
@@ -83,6 +111,8 @@ impl State {
             gpu: GpuState::new(),
             cdrom: CdromState::new(),
             padmc: PadmcState::new(),
+            sio1: Sio1State::new(),
+            mdec: MdecState::new(),
             bus_locked: Flag::new(),
         })
     }
@@ -100,6 +130,103 @@ impl State {
         Ok(())
     }
 
+    pub(crate) fn setup_ports(state: &mut State, port1_config: &PortConfig, port2_config: &PortConfig) {
+        let controller_state = state.padmc.controller_state.get_mut();
+        controller_state.port_states = [PortState::from_config(port1_config), PortState::from_config(port2_config)];
+    }
+
+    pub(crate) fn setup_cdrom(state: &mut State, cdrom_config: &CdromConfig) {
+        let controller_state = state.cdrom.controller_state.get_mut();
+        controller_state.fast_loading = cdrom_config.fast_loading;
+        controller_state.modchip = cdrom_config.modchip;
+        controller_state.region_code = match cdrom_config.region {
+            ConsoleRegion::NtscU => b'A',
+            ConsoleRegion::NtscJ => b'I',
+            ConsoleRegion::Pal => b'E',
+        };
+    }
+
+    pub(crate) fn set_lid_open(state: &mut State, open: bool) {
+        let controller_state = state.cdrom.controller_state.get_mut();
+        controller_state.lid_open = open;
+    }
+
+    pub(crate) fn lid_open(state: &State) -> bool {
+        state.cdrom.controller_state.lock().lid_open
+    }
+
+    pub(crate) fn load_memory_cards(state: &mut State, prefix: &Path) -> IoResult<()> {
+        let controller_state = state.padmc.controller_state.get_mut();
+
+        for (port_index, port_state) in controller_state.port_states.iter_mut().enumerate() {
+            for (slot_index, memory_card_state) in port_state.memory_card_states.iter_mut().enumerate() {
+                let memory_card_state = match memory_card_state {
+                    Some(memory_card_state) => memory_card_state,
+                    None => continue,
+                };
+
+                let path = memory_card_path(prefix, port_index, slot_index);
+                if !path.exists() {
+                    info!("No memory card image at {}, using a blank card", path.to_str().unwrap());
+                    continue;
+                }
+
+                info!("Loading memory card from {}", path.to_str().unwrap());
+                let mut file = File::open(&path)?;
+                let mut buffer = Vec::new();
+                file.read_to_end(&mut buffer)?;
+
+                if buffer.len() != MEMORY_CARD_SIZE {
+                    return Err(IoError::new(IoErrorKind::InvalidData, format!("Memory card image {} is not {} bytes", path.to_str().unwrap(), MEMORY_CARD_SIZE)));
+                }
+
+                memory_card_state.data = buffer;
+                memory_card_state.dirty = false;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes modified memory cards back to disk, once no write command has completed for MEMORY_CARD_FLUSH_DELAY.
+    /// elapsed is the emulated time since the last call; None flushes every modified card straight away.
+    /// The image is written to a temporary file first and then renamed over the old one, so an interrupted write never
+    /// leaves a corrupted card behind.
+    pub(crate) fn flush_memory_cards(state: &mut State, prefix: &Path, elapsed: Option<f32>) -> IoResult<()> {
+        let controller_state = state.padmc.controller_state.get_mut();
+
+        for (port_index, port_state) in controller_state.port_states.iter_mut().enumerate() {
+            for (slot_index, memory_card_state) in port_state.memory_card_states.iter_mut().enumerate() {
+                let memory_card_state = match memory_card_state {
+                    Some(memory_card_state) if memory_card_state.dirty => memory_card_state,
+                    _ => continue,
+                };
+
+                if let Some(elapsed) = elapsed {
+                    memory_card_state.idle_time += elapsed;
+                    if memory_card_state.idle_time < MEMORY_CARD_FLUSH_DELAY {
+                        continue;
+                    }
+                }
+
+                create_dir_all(prefix)?;
+                let path = memory_card_path(prefix, port_index, slot_index);
+                let temp_path = path.with_extension("mcd.tmp");
+
+                {
+                    let mut file = File::create(&temp_path)?;
+                    file.write_all(&memory_card_state.data)?;
+                    file.sync_all()?;
+                }
+
+                rename(&temp_path, &path)?;
+                memory_card_state.dirty = false;
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn with_bios(prefix: &Path, name: &str) -> IoResult<Box<State>> {
         let mut state = State::new();
         State::initialize(&mut state);
@@ -107,3 +234,49 @@ impl State {
         Ok(state)
     }
 }
+
+/// Slot A cards use "card<port>.mcd", multitap slots B-D use "card<port><slot>.mcd".
+fn memory_card_path(prefix: &Path, port_index: usize, slot_index: usize) -> PathBuf {
+    let slot_suffix = ["", "b", "c", "d"][slot_index];
+    prefix.join(format!("card{}{}.mcd", port_index + 1, slot_suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::padmc::types::MemoryCardState;
+
+    #[test]
+    fn test_flush_memory_cards_delay() {
+        let prefix = std::env::temp_dir().join(format!("psx-rs-test-{}-memcards", std::process::id()));
+        let path = memory_card_path(&prefix, 0, 0);
+        let mut state = State::new();
+
+        {
+            let mut memory_card_state = MemoryCardState::new();
+            memory_card_state.data[0] = 0x4D;
+            memory_card_state.dirty = true;
+            state.padmc.controller_state.get_mut().port_states[0].memory_card_states[0] = Some(memory_card_state);
+        }
+
+        // Not written until the card has gone unwritten for the flush delay.
+        State::flush_memory_cards(&mut state, &prefix, Some(MEMORY_CARD_FLUSH_DELAY / 2.0)).unwrap();
+        assert!(!path.exists());
+
+        State::flush_memory_cards(&mut state, &prefix, Some(MEMORY_CARD_FLUSH_DELAY / 2.0)).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert_eq!(data.len(), MEMORY_CARD_SIZE);
+        assert_eq!(data[0], 0x4D);
+        std::fs::remove_file(&path).unwrap();
+
+        // Nothing to write once flushed.
+        State::flush_memory_cards(&mut state, &prefix, None).unwrap();
+        assert!(!path.exists());
+
+        // Written straight away when forced (on shutdown).
+        state.padmc.controller_state.get_mut().port_states[0].memory_card_states[0].as_mut().unwrap().dirty = true;
+        State::flush_memory_cards(&mut state, &prefix, None).unwrap();
+        assert!(path.exists());
+        std::fs::remove_dir_all(&prefix).unwrap();
+    }
+}
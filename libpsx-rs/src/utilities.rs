@@ -32,3 +32,11 @@ pub(crate) fn binary_to_ascii_escaped(data: &[u8]) -> String {
 
     binary_ascii_str
 }
+
+pub(crate) fn bcd_to_binary(value: u8) -> u8 {
+    ((value >> 4) * 10) + (value & 0xF)
+}
+
+pub(crate) fn binary_to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
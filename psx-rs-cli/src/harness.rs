@@ -0,0 +1,290 @@
+//! Headless STR movie / XA audio decoding harness.
+//! The file is read through the emulated CDROM drive (and its image backend) as a game would: the video sectors are
+//! transferred by DMA3 and decoded through the emulated MDEC over DMA0 / DMA1, then dumped as PNG files. The selected
+//! XA channel is decoded by the drive's XA-ADPCM decoder, captured from its output to the SPU CD audio input and dumped
+//! as a WAV file.
+
+mod bitstream;
+mod compare;
+mod output;
+mod sector;
+
+use bitstream::*;
+use compare::*;
+use libpsx_rs::media::{
+    MediaSystem,
+    MACROBLOCK_RGB_SIZE,
+};
+use output::*;
+use sector::*;
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
+
+const COMMAND_SETLOC: u8 = 0x02;
+const COMMAND_PAUSE: u8 = 0x09;
+const COMMAND_SETFILTER: u8 = 0x0D;
+const COMMAND_SETMODE: u8 = 0x0E;
+const COMMAND_READS: u8 = 0x1B;
+
+const MODE_XA_FILTER: u8 = 0x08;
+const MODE_WHOLE_SECTOR: u8 = 0x20;
+const MODE_XA_ADPCM: u8 = 0x40;
+const MODE_DOUBLE_SPEED: u8 = 0x80;
+
+const INT_DATA_READY: u8 = 1;
+const INT_COMPLETE: u8 = 2;
+const INT_ACKNOWLEDGE: u8 = 3;
+const INT_DATA_END: u8 = 4;
+
+/// Stream files are opened as a single track, starting at 00:02:00 (BCD).
+const STREAM_START: [u8; 3] = [0x00, 0x02, 0x00];
+/// Number of sectors searched for the XA channel at the start of the stream.
+const AUDIO_SCAN_SECTORS: usize = 0x100;
+/// Sample rate of the CD audio sent to the SPU.
+const CD_AUDIO_SAMPLE_RATE: usize = 44100;
+
+const MDEC_CONTROL_RESET: u32 = 0x8000_0000;
+const MDEC_CONTROL_DMA_ENABLE: u32 = 0x6000_0000;
+const MDEC_SET_QUANT_TABLES: u32 = 0x4000_0001;
+const MDEC_SET_SCALE_TABLE: u32 = 0x6000_0000;
+const MDEC_DECODE_24BIT: u32 = 0x3000_0000;
+const MDEC_MAX_PARAMETER_WORDS: usize = 0xFFFF;
+
+/// Standard IDCT scale table, as uploaded by the BIOS/libpress.
+const SCALE_TABLE: [i16; 64] = [
+    0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x5A82, 0x7D8A, 0x6A6D, 0x471C, 0x18F8, -0x18F9, -0x471D, -0x6A6E, -0x7D8B, 0x7641, 0x30FB, -0x30FC, -0x7642, -0x7642,
+    -0x30FC, 0x30FB, 0x7641, 0x6A6D, -0x18F9, -0x7D8B, -0x471D, 0x471C, 0x7D8A, 0x18F8, -0x6A6E, 0x5A82, -0x5A83, -0x5A83, 0x5A82, 0x5A82, -0x5A83, -0x5A83, 0x5A82, 0x471C,
+    -0x7D8B, 0x18F8, 0x6A6D, -0x6A6E, -0x18F9, 0x7D8A, -0x471D, 0x30FB, -0x7642, 0x7641, -0x30FC, -0x30FC, 0x7641, -0x7642, 0x30FB, 0x18F8, -0x471D, 0x6A6D, -0x7D8B, 0x7D8A,
+    -0x6A6E, 0x471C, -0x18F9,
+];
+
+/// Standard (MPEG-1 intra) quantization table in zigzag order, used by STR movies for both luminance and color.
+const QUANT_TABLE: [u8; 64] = [
+    2, 16, 16, 19, 16, 19, 22, 22, 22, 22, 22, 22, 26, 24, 26, 27, 27, 27, 26, 26, 26, 26, 27, 27, 27, 29, 29, 29, 34, 34, 34, 29, 29, 29, 27, 27, 29, 29, 32, 32, 34, 34, 37, 38,
+    37, 35, 35, 34, 35, 38, 38, 40, 40, 40, 48, 48, 46, 46, 56, 56, 58, 69, 69, 83,
+];
+
+struct FrameAssembly {
+    width: usize,
+    height: usize,
+    frame_size: usize,
+    chunks: Vec<Option<Vec<u8>>>,
+}
+
+/// Decodes the STR file at input_path, writing frame_NNNNN.png and audio.wav files into output_path.
+/// If no XA channel is specified, the first channel found is used.
+/// If a reference path is given, the output is then compared against the files in it.
+pub(crate) fn run(input_path: &Path, output_path: &Path, xa_channel: Option<u8>, reference_path: Option<&Path>) -> Result<(), String> {
+    std::fs::create_dir_all(output_path).map_err(|e| format!("Unable to create {}: {}", output_path.display(), e))?;
+
+    let mut system = MediaSystem::open(input_path)?;
+    log::info!("Decoding {}", input_path.display());
+
+    setup_mdec(&mut system)?;
+
+    let audio_stream = find_audio_stream(&mut system, xa_channel)?;
+    let mode = match audio_stream {
+        Some((file, channel)) => {
+            log::info!("Decoding XA file {} channel {}", file, channel);
+            command(&mut system, COMMAND_SETFILTER, &[file, channel])?;
+            MODE_DOUBLE_SPEED | MODE_XA_ADPCM | MODE_XA_FILTER | MODE_WHOLE_SECTOR
+        },
+        None => {
+            log::info!("No XA audio found");
+            MODE_DOUBLE_SPEED | MODE_WHOLE_SECTOR
+        },
+    };
+
+    command(&mut system, COMMAND_SETMODE, &[mode])?;
+    command(&mut system, COMMAND_SETLOC, &STREAM_START)?;
+    command(&mut system, COMMAND_READS, &[])?;
+
+    let tables = CodeTables::new();
+    let mut frames: BTreeMap<u32, FrameAssembly> = BTreeMap::new();
+    let mut frame_count = 0;
+    let mut audio = Vec::new();
+
+    loop {
+        let interrupt = system.wait_interrupt()?;
+        audio.extend(system.take_cd_audio());
+
+        match interrupt.index {
+            INT_DATA_READY => {},
+            INT_DATA_END => break,
+            index => return Err(format!("Unexpected INT{} while reading (response {:02X?})", index, interrupt.response)),
+        }
+
+        let sector = system.read_sector(WHOLE_SECTOR_SIZE)?;
+        let chunk = match parse_sector(&sector) {
+            Sector::Video(chunk) => chunk,
+            _ => continue,
+        };
+
+        if chunk.chunk_count == 0 || chunk.chunk_index >= chunk.chunk_count {
+            log::warn!("Skipping invalid video chunk {}/{}", chunk.chunk_index, chunk.chunk_count);
+            continue;
+        }
+
+        let assembly = frames.entry(chunk.frame_number).or_insert_with(|| {
+            FrameAssembly {
+                width: chunk.width,
+                height: chunk.height,
+                frame_size: chunk.frame_size,
+                chunks: vec![None; chunk.chunk_count],
+            }
+        });
+
+        if chunk.chunk_index < assembly.chunks.len() {
+            assembly.chunks[chunk.chunk_index] = Some(chunk.payload.to_vec());
+        }
+
+        if assembly.chunks.iter().all(Option::is_some) {
+            let assembly = frames.remove(&chunk.frame_number).unwrap();
+            handle_frame(&tables, &mut system, output_path, frame_count, assembly)?;
+            frame_count += 1;
+        }
+    }
+
+    audio.extend(system.take_cd_audio());
+
+    for (frame_number, assembly) in frames.iter() {
+        let received = assembly.chunks.iter().filter(|chunk| chunk.is_some()).count();
+        log::warn!("Frame {} incomplete ({}/{} chunks); skipped", frame_number, received, assembly.chunks.len());
+    }
+
+    log::info!("Decoded {} frames", frame_count);
+
+    if audio_stream.is_some() {
+        write_wav(&output_path.join("audio.wav"), CD_AUDIO_SAMPLE_RATE, 2, &audio)?;
+        log::info!("Decoded {} samples of XA audio ({} Hz, 2 channels)", audio.len() / 2, CD_AUDIO_SAMPLE_RATE);
+    }
+
+    match reference_path {
+        Some(reference_path) => compare_outputs(output_path, reference_path),
+        None => Ok(()),
+    }
+}
+
+/// Resets the MDEC, enables its DMA requests and uploads the tables.
+fn setup_mdec(system: &mut MediaSystem) -> Result<(), String> {
+    system.write_mdec_control(MDEC_CONTROL_RESET)?;
+    system.write_mdec_control(MDEC_CONTROL_DMA_ENABLE)?;
+
+    let mut words = vec![MDEC_SET_QUANT_TABLES];
+    words.extend(QUANT_TABLE.chunks_exact(4).chain(QUANT_TABLE.chunks_exact(4)).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])));
+    system.mdec_transfer(&words, 0)?;
+
+    let mut words = vec![MDEC_SET_SCALE_TABLE];
+    words.extend(SCALE_TABLE.chunks_exact(2).map(|values| (values[0] as u16 as u32) | ((values[1] as u16 as u32) << 16)));
+    system.mdec_transfer(&words, 0)?;
+
+    Ok(())
+}
+
+/// Finds the XA stream (file, channel) to decode: the selected channel, or the first one found.
+/// The start of the stream is read with XA-ADPCM decoding disabled, so the audio sectors are delivered as data.
+fn find_audio_stream(system: &mut MediaSystem, xa_channel: Option<u8>) -> Result<Option<(u8, u8)>, String> {
+    command(system, COMMAND_SETMODE, &[MODE_DOUBLE_SPEED | MODE_WHOLE_SECTOR])?;
+    command(system, COMMAND_SETLOC, &STREAM_START)?;
+    command(system, COMMAND_READS, &[])?;
+
+    let mut stream = None;
+    for _ in 0..AUDIO_SCAN_SECTORS {
+        let interrupt = system.wait_interrupt()?;
+        match interrupt.index {
+            INT_DATA_READY => {},
+            INT_DATA_END => break,
+            index => return Err(format!("Unexpected INT{} while reading (response {:02X?})", index, interrupt.response)),
+        }
+
+        if let Sector::Audio(sector) = parse_sector(&system.read_sector(WHOLE_SECTOR_SIZE)?) {
+            if xa_channel.map_or(true, |channel| channel == sector.channel) {
+                stream = Some((sector.file, sector.channel));
+                break;
+            }
+        }
+    }
+
+    command(system, COMMAND_PAUSE, &[])?;
+    wait_complete(system)?;
+    Ok(stream)
+}
+
+/// Sends a command and waits for its acknowledgement (INT3), discarding any sector read in the meantime.
+fn command(system: &mut MediaSystem, command: u8, parameters: &[u8]) -> Result<Vec<u8>, String> {
+    system.send_command(command, parameters)?;
+
+    loop {
+        let interrupt = system.wait_interrupt()?;
+        match interrupt.index {
+            INT_ACKNOWLEDGE => return Ok(interrupt.response),
+            INT_DATA_READY => {
+                system.read_sector(WHOLE_SECTOR_SIZE)?;
+            },
+            index => return Err(format!("Command 0x{:02X} failed with INT{} (response {:02X?})", command, index, interrupt.response)),
+        }
+    }
+}
+
+/// Waits for the second response (INT2) of a command, discarding any sector read in the meantime.
+fn wait_complete(system: &mut MediaSystem) -> Result<(), String> {
+    loop {
+        let interrupt = system.wait_interrupt()?;
+        match interrupt.index {
+            INT_COMPLETE => return Ok(()),
+            INT_DATA_READY => {
+                system.read_sector(WHOLE_SECTOR_SIZE)?;
+            },
+            index => return Err(format!("Unexpected INT{} waiting for a command to complete (response {:02X?})", index, interrupt.response)),
+        }
+    }
+}
+
+fn handle_frame(tables: &CodeTables, system: &mut MediaSystem, output_path: &Path, index: usize, assembly: FrameAssembly) -> Result<(), String> {
+    let mut frame: Vec<u8> = assembly.chunks.into_iter().flat_map(Option::unwrap).collect();
+    if assembly.frame_size > 0 && assembly.frame_size < frame.len() {
+        frame.truncate(assembly.frame_size);
+    }
+
+    let (width, height) = (assembly.width, assembly.height);
+    let macroblocks_x = (width + 15) / 16;
+    let macroblocks_y = (height + 15) / 16;
+
+    let mut rle_data = decode_frame(tables, &frame, macroblocks_x * macroblocks_y).map_err(|e| format!("Frame {}: {}", index, e))?;
+    if rle_data.len() % 2 != 0 {
+        rle_data.push(RLE_END_OF_BLOCK);
+    }
+
+    let word_count = rle_data.len() / 2;
+    if word_count > MDEC_MAX_PARAMETER_WORDS {
+        return Err(format!("Frame {}: too much MDEC RLE data ({} words)", index, word_count));
+    }
+
+    let mut words = vec![MDEC_DECODE_24BIT | word_count as u32];
+    words.extend(rle_data.chunks_exact(2).map(|values| (values[0] as u32) | ((values[1] as u32) << 16)));
+
+    let output = system.mdec_transfer(&words, (macroblocks_x * macroblocks_y * MACROBLOCK_RGB_SIZE) / 4)?;
+    let macroblocks: Vec<u8> = output.iter().flat_map(|value| value.to_le_bytes().to_vec()).collect();
+
+    // Macroblocks are stored in column-major order.
+    let mut image = vec![0; width * height * 3];
+    for (macroblock_index, macroblock) in macroblocks.chunks_exact(MACROBLOCK_RGB_SIZE).enumerate() {
+        let base_x = (macroblock_index / macroblocks_y) * 16;
+        let base_y = (macroblock_index % macroblocks_y) * 16;
+
+        for y in 0..16 {
+            if base_y + y >= height {
+                break;
+            }
+
+            let columns = 16.min(width.saturating_sub(base_x));
+            let source = &macroblock[(y * 48)..((y * 48) + (columns * 3))];
+            let destination = (((base_y + y) * width) + base_x) * 3;
+            image[destination..(destination + (columns * 3))].copy_from_slice(source);
+        }
+    }
+
+    write_png(&output_path.join(format!("frame_{:05}.png", index)), width, height, &image)
+}
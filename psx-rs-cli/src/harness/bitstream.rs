@@ -0,0 +1,304 @@
+//! Decompression of the STR frame bitstream (BS v1-v3) into MDEC RLE data, as done by the game software on the CPU.
+
+use std::collections::HashMap;
+
+pub(crate) const RLE_END_OF_BLOCK: u16 = 0xFE00;
+const FRAME_HEADER_SIZE: usize = 8;
+const ESCAPE_CODE: (usize, u32) = (6, 0b000001);
+const END_OF_BLOCK_CODE: (usize, u32) = (2, 0b10);
+
+/// MPEG-1 DC size codes (luminance, chrominance), indexed by the size.
+const DC_SIZE_CODES_LUMINANCE: [&str; 9] = ["100", "00", "01", "101", "110", "1110", "11110", "111110", "1111110"];
+const DC_SIZE_CODES_CHROMINANCE: [&str; 9] = ["00", "01", "10", "110", "1110", "11110", "111110", "1111110", "11111110"];
+
+/// MPEG-1 AC coefficient codes (table B.14, without the sign bit): (code, run, level).
+/// The short "1s" form for run 0 / level 1 is never used as the DC is coded separately.
+const AC_CODES: [(&str, u16, u16); 111] = [
+    ("11", 0, 1),
+    ("011", 1, 1),
+    ("0100", 0, 2),
+    ("0101", 2, 1),
+    ("00101", 0, 3),
+    ("00111", 3, 1),
+    ("00110", 4, 1),
+    ("000110", 1, 2),
+    ("000111", 5, 1),
+    ("000101", 6, 1),
+    ("000100", 7, 1),
+    ("0000110", 0, 4),
+    ("0000100", 2, 2),
+    ("0000111", 8, 1),
+    ("0000101", 9, 1),
+    ("00100110", 0, 5),
+    ("00100001", 0, 6),
+    ("00100101", 1, 3),
+    ("00100100", 3, 2),
+    ("00100111", 10, 1),
+    ("00100011", 11, 1),
+    ("00100010", 12, 1),
+    ("00100000", 13, 1),
+    ("0000001010", 0, 7),
+    ("0000001100", 1, 4),
+    ("0000001011", 2, 3),
+    ("0000001111", 4, 2),
+    ("0000001001", 5, 2),
+    ("0000001110", 14, 1),
+    ("0000001101", 15, 1),
+    ("0000001000", 16, 1),
+    ("000000011101", 0, 8),
+    ("000000011000", 0, 9),
+    ("000000010011", 0, 10),
+    ("000000010000", 0, 11),
+    ("000000011011", 1, 5),
+    ("000000010100", 2, 4),
+    ("000000011100", 3, 3),
+    ("000000010010", 4, 3),
+    ("000000011110", 6, 2),
+    ("000000010101", 7, 2),
+    ("000000010001", 8, 2),
+    ("000000011111", 17, 1),
+    ("000000011010", 18, 1),
+    ("000000011001", 19, 1),
+    ("000000010111", 20, 1),
+    ("000000010110", 21, 1),
+    ("0000000011010", 0, 12),
+    ("0000000011001", 0, 13),
+    ("0000000011000", 0, 14),
+    ("0000000010111", 0, 15),
+    ("0000000010110", 1, 6),
+    ("0000000010101", 1, 7),
+    ("0000000010100", 2, 5),
+    ("0000000010011", 3, 4),
+    ("0000000010010", 5, 3),
+    ("0000000010001", 9, 2),
+    ("0000000010000", 10, 2),
+    ("0000000011111", 22, 1),
+    ("0000000011110", 23, 1),
+    ("0000000011101", 24, 1),
+    ("0000000011100", 25, 1),
+    ("0000000011011", 26, 1),
+    ("00000000011111", 0, 16),
+    ("00000000011110", 0, 17),
+    ("00000000011101", 0, 18),
+    ("00000000011100", 0, 19),
+    ("00000000011011", 0, 20),
+    ("00000000011010", 0, 21),
+    ("00000000011001", 0, 22),
+    ("00000000011000", 0, 23),
+    ("00000000010111", 0, 24),
+    ("00000000010110", 0, 25),
+    ("00000000010101", 0, 26),
+    ("00000000010100", 0, 27),
+    ("00000000010011", 0, 28),
+    ("00000000010010", 0, 29),
+    ("00000000010001", 0, 30),
+    ("00000000010000", 0, 31),
+    ("000000000011000", 0, 32),
+    ("000000000010111", 0, 33),
+    ("000000000010110", 0, 34),
+    ("000000000010101", 0, 35),
+    ("000000000010100", 0, 36),
+    ("000000000010011", 0, 37),
+    ("000000000010010", 0, 38),
+    ("000000000010001", 0, 39),
+    ("000000000010000", 0, 40),
+    ("000000000011111", 1, 8),
+    ("000000000011110", 1, 9),
+    ("000000000011101", 1, 10),
+    ("000000000011100", 1, 11),
+    ("000000000011011", 1, 12),
+    ("000000000011010", 1, 13),
+    ("000000000011001", 1, 14),
+    ("0000000000010011", 1, 15),
+    ("0000000000010010", 1, 16),
+    ("0000000000010001", 1, 17),
+    ("0000000000010000", 1, 18),
+    ("0000000000010100", 6, 3),
+    ("0000000000011010", 11, 2),
+    ("0000000000011001", 12, 2),
+    ("0000000000011000", 13, 2),
+    ("0000000000010111", 14, 2),
+    ("0000000000010110", 15, 2),
+    ("0000000000010101", 16, 2),
+    ("0000000000011111", 27, 1),
+    ("0000000000011110", 28, 1),
+    ("0000000000011101", 29, 1),
+    ("0000000000011100", 30, 1),
+    ("0000000000011011", 31, 1),
+];
+
+const MAX_CODE_LENGTH: usize = 16;
+
+/// Lookup tables for the variable length codes, keyed by (length, code).
+pub(crate) struct CodeTables {
+    ac: HashMap<(usize, u32), (u16, u16)>,
+    dc_luminance: HashMap<(usize, u32), usize>,
+    dc_chrominance: HashMap<(usize, u32), usize>,
+}
+
+impl CodeTables {
+    pub(crate) fn new() -> CodeTables {
+        let key = |code: &str| (code.len(), u32::from_str_radix(code, 2).unwrap());
+
+        CodeTables {
+            ac: AC_CODES.iter().map(|(code, run, level)| (key(code), (*run, *level))).collect(),
+            dc_luminance: DC_SIZE_CODES_LUMINANCE.iter().enumerate().map(|(size, code)| (key(code), size)).collect(),
+            dc_chrominance: DC_SIZE_CODES_CHROMINANCE.iter().enumerate().map(|(size, code)| (key(code), size)).collect(),
+        }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            data,
+            position: 0,
+        }
+    }
+
+    /// Reads a bit; the stream is made of little endian 16-bit words, each read MSB first.
+    fn read_bit(&mut self) -> Result<u32, String> {
+        let word_index = (self.position / 16) * 2;
+        if word_index + 1 >= self.data.len() {
+            return Err("Unexpected end of frame bitstream".into());
+        }
+
+        let word = u16::from_le_bytes([self.data[word_index], self.data[word_index + 1]]);
+        let bit = (word >> (15 - (self.position % 16))) & 1;
+        self.position += 1;
+        Ok(bit as u32)
+    }
+
+    fn read_bits(&mut self, count: usize) -> Result<u32, String> {
+        let mut value = 0;
+        for _ in 0..count {
+            value = (value << 1) | self.read_bit()?;
+        }
+        Ok(value)
+    }
+
+    fn read_code<T: Copy>(&mut self, table: &HashMap<(usize, u32), T>) -> Result<T, String> {
+        let mut code = 0;
+        for length in 1..=MAX_CODE_LENGTH {
+            code = (code << 1) | self.read_bit()?;
+            if let Some(value) = table.get(&(length, code)) {
+                return Ok(*value);
+            }
+        }
+        Err(format!("Invalid variable length code at bit {}", self.position))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum BlockKind {
+    Cr,
+    Cb,
+    Y,
+}
+
+/// Block order within a macroblock, as expected by the MDEC.
+const BLOCK_KINDS: [BlockKind; 6] = [BlockKind::Cr, BlockKind::Cb, BlockKind::Y, BlockKind::Y, BlockKind::Y, BlockKind::Y];
+
+/// Decompresses a demuxed frame into MDEC RLE data for the given number of macroblocks.
+/// The result is padded to a whole number of 32-bit words.
+pub(crate) fn decode_frame(tables: &CodeTables, frame: &[u8], macroblock_count: usize) -> Result<Vec<u16>, String> {
+    if frame.len() < FRAME_HEADER_SIZE {
+        return Err("Frame too small".into());
+    }
+
+    let qscale = u16::from_le_bytes([frame[4], frame[5]]);
+    let version = u16::from_le_bytes([frame[6], frame[7]]);
+    if version < 1 || version > 3 {
+        return Err(format!("Unsupported frame bitstream version {}", version));
+    }
+
+    let mut reader = BitReader::new(&frame[FRAME_HEADER_SIZE..]);
+    let mut output = Vec::new();
+    let mut dc_predictors = [0i32; 3];
+
+    for _ in 0..macroblock_count {
+        for &kind in BLOCK_KINDS.iter() {
+            let dc = if version == 3 {
+                decode_dc_v3(tables, &mut reader, kind, &mut dc_predictors)?
+            } else {
+                reader.read_bits(10)? as u16
+            };
+
+            output.push((qscale << 10) | (dc & 0x3FF));
+            decode_ac(tables, &mut reader, &mut output)?;
+        }
+    }
+
+    if output.len() % 2 != 0 {
+        output.push(RLE_END_OF_BLOCK);
+    }
+
+    Ok(output)
+}
+
+fn decode_dc_v3(tables: &CodeTables, reader: &mut BitReader, kind: BlockKind, dc_predictors: &mut [i32; 3]) -> Result<u16, String> {
+    let (table, predictor) = match kind {
+        BlockKind::Y => (&tables.dc_luminance, &mut dc_predictors[0]),
+        BlockKind::Cr => (&tables.dc_chrominance, &mut dc_predictors[1]),
+        BlockKind::Cb => (&tables.dc_chrominance, &mut dc_predictors[2]),
+    };
+
+    let size = reader.read_code(table)?;
+    let difference = if size == 0 {
+        0
+    } else {
+        let value = reader.read_bits(size)? as i32;
+        if (value >> (size - 1)) == 0 {
+            value - (1 << size) + 1
+        } else {
+            value
+        }
+    };
+
+    // The MPEG-1 DC scale (8) is 4 times that of the MDEC DC quant value (2).
+    *predictor += difference * 4;
+    Ok(*predictor as u16)
+}
+
+fn decode_ac(tables: &CodeTables, reader: &mut BitReader, output: &mut Vec<u16>) -> Result<(), String> {
+    loop {
+        let mut code = 0;
+        let mut length = 0;
+        let (run, level) = loop {
+            code = (code << 1) | reader.read_bit()?;
+            length += 1;
+
+            if (length, code) == END_OF_BLOCK_CODE {
+                output.push(RLE_END_OF_BLOCK);
+                return Ok(());
+            }
+
+            if (length, code) == ESCAPE_CODE {
+                output.push(reader.read_bits(16)? as u16);
+                break (None, 0);
+            }
+
+            if let Some(&(run, level)) = tables.ac.get(&(length, code)) {
+                break (Some(run), level);
+            }
+
+            if length >= MAX_CODE_LENGTH {
+                return Err(format!("Invalid AC code at bit {}", reader.position));
+            }
+        };
+
+        if let Some(run) = run {
+            let level = if reader.read_bit()? > 0 {
+                (level as i16).wrapping_neg() as u16
+            } else {
+                level
+            };
+            output.push((run << 10) | (level & 0x3FF));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Regression comparison of the decoded output against a directory of reference files.
+//! Frames are compared by their decoded pixels and audio by its sample data, so the files don't need to be byte
+//! identical.
+
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    path::Path,
+};
+
+/// Compares every frame_NNNNN.png / audio.wav in either directory; files missing from one side count as differences.
+pub(crate) fn compare_outputs(output_path: &Path, reference_path: &Path) -> Result<(), String> {
+    let mut names = BTreeSet::new();
+    names.extend(output_names(output_path)?);
+    names.extend(output_names(reference_path)?);
+
+    let mut difference_count = 0;
+    for name in names.iter() {
+        let output_file = output_path.join(name);
+        let reference_file = reference_path.join(name);
+
+        let result = if !output_file.exists() {
+            Err("not produced by the decoder".to_owned())
+        } else if !reference_file.exists() {
+            Err("no reference file".to_owned())
+        } else if name.ends_with(".png") {
+            compare_png(&output_file, &reference_file)
+        } else {
+            compare_wav(&output_file, &reference_file)
+        };
+
+        if let Err(e) = result {
+            log::error!("{}: {}", name, e);
+            difference_count += 1;
+        }
+    }
+
+    if difference_count > 0 {
+        return Err(format!("{} of {} outputs differ from the reference", difference_count, names.len()));
+    }
+
+    log::info!("All {} outputs match the reference", names.len());
+    Ok(())
+}
+
+fn output_names(path: &Path) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let name = entry.map_err(|e| e.to_string())?.file_name().to_string_lossy().into_owned();
+        if (name.starts_with("frame_") && name.ends_with(".png")) || name == "audio.wav" {
+            names.push(name);
+        }
+    }
+
+    Ok(names)
+}
+
+fn compare_png(output_file: &Path, reference_file: &Path) -> Result<(), String> {
+    let (width, height, output) = read_png(output_file)?;
+    let (reference_width, reference_height, reference) = read_png(reference_file)?;
+
+    if (width, height) != (reference_width, reference_height) {
+        return Err(format!("size {}x{} differs from the reference {}x{}", width, height, reference_width, reference_height));
+    }
+
+    let differences = output.chunks_exact(3).zip(reference.chunks_exact(3)).filter(|(pixel, reference_pixel)| pixel != reference_pixel).count();
+    if differences > 0 {
+        let max_difference = output.iter().zip(reference.iter()).map(|(value, reference_value)| (*value as i32 - *reference_value as i32).abs()).max().unwrap();
+        return Err(format!("{} pixels differ (maximum channel difference {})", differences, max_difference));
+    }
+
+    Ok(())
+}
+
+/// Returns the width, height and 24-bit RGB pixels; an alpha channel is ignored.
+fn read_png(path: &Path) -> Result<(usize, usize, Vec<u8>), String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+    let mut reader = png::Decoder::new(file).read_info().map_err(|e| e.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+    buffer.truncate(info.buffer_size());
+
+    if info.bit_depth != png::BitDepth::Eight {
+        return Err(format!("{}: only 8-bit PNGs are supported", path.display()));
+    }
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgb => buffer,
+        png::ColorType::Rgba => buffer.chunks_exact(4).flat_map(|pixel| pixel[0..3].to_vec()).collect(),
+        _ => return Err(format!("{}: only RGB / RGBA PNGs are supported", path.display())),
+    };
+
+    Ok((info.width as usize, info.height as usize, pixels))
+}
+
+fn compare_wav(output_file: &Path, reference_file: &Path) -> Result<(), String> {
+    let (format, samples) = read_wav(output_file)?;
+    let (reference_format, reference_samples) = read_wav(reference_file)?;
+
+    if format != reference_format {
+        return Err("format (channels / sample rate / bits) differs from the reference".to_owned());
+    }
+
+    if samples.len() != reference_samples.len() {
+        return Err(format!("{} bytes of sample data, the reference has {}", samples.len(), reference_samples.len()));
+    }
+
+    if let Some(position) = samples.iter().zip(reference_samples.iter()).position(|(value, reference_value)| value != reference_value) {
+        return Err(format!("sample data differs from byte {}", position));
+    }
+
+    Ok(())
+}
+
+/// Returns the contents of the "fmt " and "data" chunks; other chunks are skipped.
+fn read_wav(path: &Path) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let data = std::fs::read(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(format!("{}: not a WAV file", path.display()));
+    }
+
+    let mut format = None;
+    let mut samples = None;
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let size = u32::from_le_bytes([data[offset + 4], data[offset + 5], data[offset + 6], data[offset + 7]]) as usize;
+        let body = &data[(offset + 8)..(offset + 8 + size).min(data.len())];
+
+        match &data[offset..(offset + 4)] {
+            b"fmt " => format = Some(body.to_vec()),
+            b"data" => samples = Some(body.to_vec()),
+            _ => {},
+        }
+
+        // Chunks are padded to an even size.
+        offset += 8 + size + (size & 1);
+    }
+
+    match (format, samples) {
+        (Some(format), Some(samples)) => Ok((format, samples)),
+        _ => Err(format!("{}: missing fmt or data chunk", path.display())),
+    }
+}
@@ -0,0 +1,46 @@
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+    path::Path,
+};
+
+pub(crate) fn write_png(path: &Path, width: usize, height: usize, rgb: &[u8]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Unable to create {}: {}", path.display(), e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(rgb).map_err(|e| e.to_string())
+}
+
+/// Writes 16-bit PCM samples (interleaved if stereo) as a WAV file.
+pub(crate) fn write_wav(path: &Path, sample_rate: usize, channels: usize, samples: &[i16]) -> Result<(), String> {
+    let block_align = channels * 2;
+    let data_size = samples.len() * 2;
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&((36 + data_size) as u32).to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&(channels as u16).to_le_bytes());
+    header.extend_from_slice(&(sample_rate as u32).to_le_bytes());
+    header.extend_from_slice(&((sample_rate * block_align) as u32).to_le_bytes());
+    header.extend_from_slice(&(block_align as u16).to_le_bytes());
+    header.extend_from_slice(&16u16.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(data_size as u32).to_le_bytes());
+
+    let file = File::create(path).map_err(|e| format!("Unable to create {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(&header).map_err(|e| e.to_string())?;
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes()).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
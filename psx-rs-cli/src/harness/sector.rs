@@ -0,0 +1,61 @@
+const VIDEO_MAGIC: [u8; 4] = [0x60, 0x01, 0x01, 0x80];
+const SUBMODE_AUDIO: u8 = 0x04;
+const VIDEO_HEADER_SIZE: usize = 32;
+const VIDEO_DATA_SIZE: usize = 2048;
+
+/// Size of a sector read with the Setmode whole sector bit: header (4), subheader (2 x 4), then the data.
+pub(crate) const WHOLE_SECTOR_SIZE: usize = 0x924;
+const SUBHEADER_OFFSET: usize = 4;
+const DATA_OFFSET: usize = 12;
+
+pub(crate) struct VideoChunk<'a> {
+    pub(crate) chunk_index: usize,
+    pub(crate) chunk_count: usize,
+    pub(crate) frame_number: u32,
+    pub(crate) frame_size: usize,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) payload: &'a [u8],
+}
+
+pub(crate) struct AudioSector {
+    pub(crate) file: u8,
+    pub(crate) channel: u8,
+}
+
+pub(crate) enum Sector<'a> {
+    Video(VideoChunk<'a>),
+    Audio(AudioSector),
+    Other,
+}
+
+/// Parses a whole sector, as transferred from the CDROM data FIFO.
+pub(crate) fn parse_sector(sector: &[u8]) -> Sector<'_> {
+    let subheader = &sector[SUBHEADER_OFFSET..DATA_OFFSET];
+    let data = &sector[DATA_OFFSET..];
+
+    if (subheader[2] & SUBMODE_AUDIO) > 0 {
+        return Sector::Audio(AudioSector {
+            file: subheader[0],
+            channel: subheader[1],
+        });
+    }
+
+    // Streams stored as user data only don't keep the video submode bit, so the chunk header magic identifies video.
+    if data.len() >= VIDEO_DATA_SIZE && data[0..4] == VIDEO_MAGIC {
+        let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]) as usize;
+        let read_u32 = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+
+        return Sector::Video(VideoChunk {
+            chunk_index: read_u16(4),
+            chunk_count: read_u16(6),
+            frame_number: read_u32(8),
+            frame_size: read_u32(12) as usize,
+            width: read_u16(16),
+            height: read_u16(18),
+            payload: &data[VIDEO_HEADER_SIZE..VIDEO_DATA_SIZE],
+        });
+    }
+
+    Sector::Other
+}
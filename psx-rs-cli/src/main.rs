@@ -1,9 +1,14 @@
+mod harness;
+
 use libpsx_rs::{
     backends::{
         audio::AudioBackend,
         cdrom::CdromBackend,
+        sio::SioBackend,
         video::VideoBackend,
     },
+    input::PortConfig,
+    CdromConfig,
     Config,
     Core,
     ThreadingKind,
@@ -37,6 +42,31 @@ fn main() {
     setup_logger(&log_file_path);
     log::info!("Logging initialized");
 
+    // STR / XA decoding harness: psx-rs-cli str <file> <output directory> [xa channel] [--compare <reference directory>]
+    if args().nth(1).as_deref() == Some("str") {
+        let usage_error = |message: &str| -> ! {
+            log::error!("{} (usage: psx-rs-cli str <file> <output directory> [xa channel] [--compare <reference directory>])", message);
+            std::process::exit(1);
+        };
+
+        let input_path = PathBuf::from(args().nth(2).unwrap_or_else(|| usage_error("STR file path required")));
+        let output_path = PathBuf::from(args().nth(3).unwrap_or_else(|| usage_error("Output directory required")));
+        let mut xa_channel = None;
+        let mut reference_path = None;
+        let mut options = args().skip(4);
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "--compare" => reference_path = Some(PathBuf::from(options.next().unwrap_or_else(|| usage_error("Reference directory required")))),
+                value => xa_channel = Some(value.parse::<u8>().unwrap_or_else(|_| usage_error(&format!("Invalid XA channel: {}", value)))),
+            }
+        }
+        if let Err(e) = harness::run(&input_path, &output_path, xa_channel, reference_path.as_deref()) {
+            log::error!("STR decoding failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Initialize psx_rs core
     let time_delta_us = args().nth(1).map_or(10, |v| v.parse::<usize>().unwrap());
     let worker_threads = args().nth(2).map_or(2, |v| v.parse::<usize>().unwrap());
@@ -46,6 +76,7 @@ fn main() {
         video_backend: VideoBackend::None,
         audio_backend: AudioBackend::None,
         cdrom_backend: CdromBackend::None,
+        sio_backend: SioBackend::None,
         time_delta: time_delta_us as f32 / 1e6,
         threading: ThreadingKind::Mutex(worker_threads),
         internal_scale_factor: 1,
@@ -58,7 +89,12 @@ fn main() {
         timers_bias: 1.0,
         cdrom_bias: 1.0,
         padmc_bias: 1.0,
+        sio1_bias: 1.0,
+        mdec_bias: 1.0,
         intc_bias: 1.0,
+        port1: PortConfig::new(),
+        port2: PortConfig::new(),
+        cdrom: CdromConfig::new(),
     };
 
     main_inner(config);
@@ -1,7 +1,9 @@
 mod audio;
 mod cdrom;
+mod sio;
 mod video;
 
 pub(crate) use audio::*;
 pub(crate) use cdrom::*;
+pub(crate) use sio::*;
 pub(crate) use video::*;
@@ -5,6 +5,7 @@ use libpsx_rs::backends::cdrom::*;
 #[derive(Copy, Clone, Debug)]
 pub(crate) enum CdromBackendKind {
     None,
+    Image,
     Libmirage,
     Libcdio,
 }
@@ -12,6 +13,7 @@ pub(crate) enum CdromBackendKind {
 pub(crate) fn initialize_cdrom_backend<'a>(kind: CdromBackendKind) -> CdromBackend<'a> {
     match kind {
         CdromBackendKind::None => CdromBackend::None,
+        CdromBackendKind::Image => initialize_cdrom_backend_image(),
         CdromBackendKind::Libmirage => initialize_cdrom_backend_libmirage(),
         CdromBackendKind::Libcdio => initialize_cdrom_backend_libcdio(),
     }
@@ -20,11 +22,19 @@ pub(crate) fn initialize_cdrom_backend<'a>(kind: CdromBackendKind) -> CdromBacke
 pub(crate) fn terminate_cdrom_backend(kind: CdromBackendKind) {
     match kind {
         CdromBackendKind::None => {},
+        CdromBackendKind::Image => {},
         CdromBackendKind::Libmirage => terminate_cdrom_backend_libmirage(),
         CdromBackendKind::Libcdio => terminate_cdrom_backend_libcdio(),
     }
 }
 
+/// Image
+
+pub(crate) fn initialize_cdrom_backend_image<'a>() -> CdromBackend<'a> {
+    log::info!("CDROM initialized: disc image (cue/bin, chd)");
+    CdromBackend::Image(image::BackendParams::new())
+}
+
 /// Libmirage
 
 #[cfg(libmirage)]
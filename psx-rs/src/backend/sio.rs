@@ -0,0 +1,21 @@
+use libpsx_rs::backends::sio::{
+    socket::SocketLink,
+    *,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum SioBackendKind {
+    None,
+    /// Wait for another instance to connect on the given localhost port.
+    Listen(u16),
+    /// Connect to another instance listening on the given localhost port.
+    Connect(u16),
+}
+
+pub(crate) fn initialize_sio_backend<'a>(kind: SioBackendKind) -> SioBackend<'a> {
+    match kind {
+        SioBackendKind::None => SioBackend::None,
+        SioBackendKind::Listen(port) => SioBackend::link(SocketLink::listen(("127.0.0.1", port)).unwrap()),
+        SioBackendKind::Connect(port) => SioBackend::link(SocketLink::connect(("127.0.0.1", port)).unwrap()),
+    }
+}
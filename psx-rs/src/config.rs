@@ -1,5 +1,13 @@
 use crate::backend::*;
-use libpsx_rs::ThreadingKind;
+use libpsx_rs::{
+    input::{
+        ControllerKind,
+        PortConfig,
+    },
+    CdromConfig,
+    ConsoleRegion,
+    ThreadingKind,
+};
 use serde::Deserialize;
 use std::{
     fs::File,
@@ -13,6 +21,8 @@ struct TomlConfig {
     audio_backend: String,
     cdrom_backend: String,
     video_backend: String,
+    sio_link: String,
+    sio_link_port: u16,
     threading: String,
     use_spinlocks: bool,
     time_delta: u64,
@@ -28,7 +38,16 @@ struct TomlConfig {
     timers_bias: f32,
     cdrom_bias: f32,
     padmc_bias: f32,
+    sio1_bias: f32,
+    mdec_bias: f32,
     intc_bias: f32,
+    port1_multitap: bool,
+    port1_controllers: Vec<String>,
+    port2_multitap: bool,
+    port2_controllers: Vec<String>,
+    cdrom_fast_loading: bool,
+    console_region: String,
+    cdrom_modchip: bool,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -37,6 +56,7 @@ pub(crate) struct Config {
     pub(crate) audio_backend_kind: AudioBackendKind,
     pub(crate) cdrom_backend_kind: CdromBackendKind,
     pub(crate) video_backend_kind: VideoBackendKind,
+    pub(crate) sio_backend_kind: SioBackendKind,
     pub(crate) threading: ThreadingKind,
     pub(crate) time_delta_secs: f32,
     pub(crate) pause_on_start: bool,
@@ -51,7 +71,12 @@ pub(crate) struct Config {
     pub(crate) timers_bias: f32,
     pub(crate) cdrom_bias: f32,
     pub(crate) padmc_bias: f32,
+    pub(crate) sio1_bias: f32,
+    pub(crate) mdec_bias: f32,
     pub(crate) intc_bias: f32,
+    pub(crate) port1: PortConfig,
+    pub(crate) port2: PortConfig,
+    pub(crate) cdrom: CdromConfig,
 }
 
 pub(crate) fn load(workspace_path: &Path) -> Config {
@@ -73,6 +98,7 @@ pub(crate) fn load(workspace_path: &Path) -> Config {
         cdrom_backend_kind: {
             match toml_config.cdrom_backend.as_ref() {
                 "none" => CdromBackendKind::None,
+                "image" => CdromBackendKind::Image,
                 "libmirage" => CdromBackendKind::Libmirage,
                 "libcdio" => CdromBackendKind::Libcdio,
                 _ => panic!("Unrecongnised config option for the cdrom backend"),
@@ -85,6 +111,14 @@ pub(crate) fn load(workspace_path: &Path) -> Config {
                 _ => panic!("Unrecongnised config option for the video backend"),
             }
         },
+        sio_backend_kind: {
+            match toml_config.sio_link.as_ref() {
+                "none" => SioBackendKind::None,
+                "listen" => SioBackendKind::Listen(toml_config.sio_link_port),
+                "connect" => SioBackendKind::Connect(toml_config.sio_link_port),
+                _ => panic!("Unrecongnised config option for the SIO link"),
+            }
+        },
         threading: {
             if toml_config.threading == "single" {
                 ThreadingKind::None
@@ -109,6 +143,44 @@ pub(crate) fn load(workspace_path: &Path) -> Config {
         timers_bias: toml_config.timers_bias,
         cdrom_bias: toml_config.cdrom_bias,
         padmc_bias: toml_config.padmc_bias,
+        sio1_bias: toml_config.sio1_bias,
+        mdec_bias: toml_config.mdec_bias,
         intc_bias: toml_config.intc_bias,
+        port1: port_config(toml_config.port1_multitap, &toml_config.port1_controllers),
+        port2: port_config(toml_config.port2_multitap, &toml_config.port2_controllers),
+        cdrom: CdromConfig {
+            fast_loading: toml_config.cdrom_fast_loading,
+            region: {
+                match toml_config.console_region.as_ref() {
+                    "ntsc-u" => ConsoleRegion::NtscU,
+                    "ntsc-j" => ConsoleRegion::NtscJ,
+                    "pal" => ConsoleRegion::Pal,
+                    _ => panic!("Unrecognised config option for the console region"),
+                }
+            },
+            modchip: toml_config.cdrom_modchip,
+        },
+    }
+}
+
+fn port_config(multitap: bool, controllers: &[String]) -> PortConfig {
+    let mut port_config = PortConfig::new();
+    port_config.multitap = multitap;
+
+    if controllers.len() > 4 {
+        panic!("Too many controllers specified for a port (max 4)");
     }
+
+    for (index, controller) in controllers.iter().enumerate() {
+        port_config.controllers[index] = match controller.as_ref() {
+            "none" => ControllerKind::None,
+            "digital" => ControllerKind::Digital,
+            "dualshock" => ControllerKind::DualShock,
+            "mouse" => ControllerKind::Mouse,
+            "guncon" => ControllerKind::GunCon,
+            _ => panic!("Unrecongnised config option for a controller"),
+        };
+    }
+
+    port_config
 }
@@ -69,6 +69,9 @@ fn main() {
     // Initialize CDROM.
     let cdrom_backend = backend::initialize_cdrom_backend(config.cdrom_backend_kind);
 
+    // Initialize SIO link cable.
+    let sio_backend = backend::initialize_sio_backend(config.sio_backend_kind);
+
     // Initialize psx-rs core.
     let core_config = CoreConfig {
         workspace_path: PathBuf::from(r"./workspace/"),
@@ -76,6 +79,7 @@ fn main() {
         video_backend,
         audio_backend,
         cdrom_backend,
+        sio_backend,
         time_delta: config.time_delta_secs,
         threading: config.threading,
         internal_scale_factor: config.internal_scale_factor,
@@ -88,7 +92,12 @@ fn main() {
         timers_bias: config.timers_bias,
         cdrom_bias: config.cdrom_bias,
         padmc_bias: config.padmc_bias,
+        sio1_bias: config.sio1_bias,
+        mdec_bias: config.mdec_bias,
         intc_bias: config.intc_bias,
+        port1: config.port1,
+        port2: config.port2,
+        cdrom: config.cdrom,
     };
 
     state::main_inner(&window, &mut event_pump, config, core_config);
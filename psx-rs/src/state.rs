@@ -1,10 +1,19 @@
 use crate::config::Config;
 use libpsx_rs::{
+    input::{
+        Axis,
+        Button,
+        PointerButton,
+        Port,
+        Slot,
+    },
     Config as CoreConfig,
     Core,
 };
 use sdl2::{
+    event::Event,
     keyboard::Keycode,
+    mouse::MouseButton,
     video::Window,
     EventPump,
 };
@@ -20,6 +29,14 @@ use std::{
     time::Duration,
 };
 
+#[derive(Debug, Copy, Clone)]
+enum InputEvent {
+    Key(Keycode, bool),
+    /// Absolute position (x, y) and relative motion (x, y) in window coordinates.
+    PointerMotion(i32, i32, i32, i32),
+    PointerButton(MouseButton, bool),
+}
+
 #[derive(Debug, Copy, Clone)]
 enum State {
     Paused,
@@ -28,7 +45,7 @@ enum State {
     Quit,
 }
 
-pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, config: Config, core_config: CoreConfig<'a>) {
+pub(crate) fn main_inner<'a>(window: &'a Window, event_pump: &mut EventPump, config: Config, core_config: CoreConfig<'a>) {
     let mut core = Core::new(&core_config).unwrap();
     handle_change_disc(&mut core);
     log::info!("Core initialized");
@@ -41,6 +58,8 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
 
     log::info!("{:?}", state.get());
 
+    let disc_index = Cell::new(0);
+
     let quit_fn = || {
         log::info!("Quit");
         state.set(State::Quit);
@@ -49,7 +68,16 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
     loop {
         match state.get() {
             State::Running => {
-                let keydown_fn = |key| {
+                let input_fn = |event| {
+                    let (key, pressed) = match event {
+                        InputEvent::Key(key, pressed) => (key, pressed),
+                        pointer_event => return handle_pointer(&mut core, window, pointer_event),
+                    };
+
+                    if !pressed {
+                        return handle_input(&mut core, key, false);
+                    }
+
                     match key {
                         Keycode::F1 => {
                             state.set(State::Paused);
@@ -64,18 +92,24 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
                         Keycode::F4 => {
                             reset(&mut core, true);
                         },
+                        Keycode::F5 => {
+                            next_disc(&mut core, &disc_index);
+                        },
+                        Keycode::F6 => {
+                            toggle_lid(&mut core);
+                        },
                         Keycode::F10 => {
                             save_state(&mut core);
                         },
                         Keycode::F11 => {
                             load_state(&mut core);
                         },
-                        _ => return false,
+                        _ => return handle_input(&mut core, key, true),
                     }
                     true
                 };
 
-                if !handle_events(event_pump, quit_fn, keydown_fn) {
+                if !handle_events(event_pump, quit_fn, input_fn) {
                     // Run for 1ms before checking for events again.
                     let iterations = ((1e-3 / config.time_delta_secs) as usize).max(1);
                     if let Err(()) = handle_core_step(&mut core, iterations) {
@@ -86,7 +120,16 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
                 }
             },
             State::Paused => {
-                let keydown_fn = |key| {
+                let input_fn = |event| {
+                    let (key, pressed) = match event {
+                        InputEvent::Key(key, pressed) => (key, pressed),
+                        pointer_event => return handle_pointer(&mut core, window, pointer_event),
+                    };
+
+                    if !pressed {
+                        return handle_input(&mut core, key, false);
+                    }
+
                     match key {
                         Keycode::F1 => {
                             state.set(State::Running);
@@ -101,18 +144,24 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
                         Keycode::F4 => {
                             reset(&mut core, true);
                         },
+                        Keycode::F5 => {
+                            next_disc(&mut core, &disc_index);
+                        },
+                        Keycode::F6 => {
+                            toggle_lid(&mut core);
+                        },
                         Keycode::F10 => {
                             save_state(&mut core);
                         },
                         Keycode::F11 => {
                             load_state(&mut core);
                         },
-                        _ => return false,
+                        _ => return handle_input(&mut core, key, true),
                     }
                     true
                 };
 
-                handle_events(event_pump, quit_fn, keydown_fn);
+                handle_events(event_pump, quit_fn, input_fn);
 
                 sleep(Duration::from_millis(16));
             },
@@ -121,7 +170,16 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
                     state.set(State::Quit);
                     log::error!("Quit");
                 } else {
-                    let keydown_fn = |key| {
+                    let input_fn = |event| {
+                        let (key, pressed) = match event {
+                            InputEvent::Key(key, pressed) => (key, pressed),
+                            _ => return false,
+                        };
+
+                        if !pressed {
+                            return false;
+                        }
+
                         match key {
                             Keycode::F1 => {
                                 log::error!("Cannot resume from an exception state");
@@ -155,7 +213,7 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
                         true
                     };
 
-                    handle_events(event_pump, quit_fn, keydown_fn);
+                    handle_events(event_pump, quit_fn, input_fn);
 
                     sleep(Duration::from_millis(16));
                 }
@@ -170,28 +228,55 @@ pub(crate) fn main_inner<'a>(_window: &'a Window, event_pump: &mut EventPump, co
     core.analyze().unwrap();
 }
 
-fn handle_events<F1, F2>(event_pump: &mut EventPump, mut quit_fn: F1, mut keydown_fn: F2) -> bool
+fn handle_events<F1, F2>(event_pump: &mut EventPump, mut quit_fn: F1, mut input_fn: F2) -> bool
 where
     F1: FnMut(),
-    F2: FnMut(Keycode) -> bool,
+    F2: FnMut(InputEvent) -> bool,
 {
     let mut handled_event = false;
 
     for event in event_pump.poll_iter() {
-        if let sdl2::event::Event::Quit {
-            ..
-        } = event
-        {
-            quit_fn();
-            handled_event = true
-        } else if let sdl2::event::Event::KeyDown {
-            keycode,
-            ..
-        } = event
-        {
-            if let Some(key) = keycode {
-                handled_event |= keydown_fn(key);
-            }
+        match event {
+            Event::Quit {
+                ..
+            } => {
+                quit_fn();
+                handled_event = true;
+            },
+            Event::KeyDown {
+                keycode: Some(key),
+                ..
+            } => {
+                handled_event |= input_fn(InputEvent::Key(key, true));
+            },
+            Event::KeyUp {
+                keycode: Some(key),
+                ..
+            } => {
+                handled_event |= input_fn(InputEvent::Key(key, false));
+            },
+            Event::MouseMotion {
+                x,
+                y,
+                xrel,
+                yrel,
+                ..
+            } => {
+                handled_event |= input_fn(InputEvent::PointerMotion(x, y, xrel, yrel));
+            },
+            Event::MouseButtonDown {
+                mouse_btn,
+                ..
+            } => {
+                handled_event |= input_fn(InputEvent::PointerButton(mouse_btn, true));
+            },
+            Event::MouseButtonUp {
+                mouse_btn,
+                ..
+            } => {
+                handled_event |= input_fn(InputEvent::PointerButton(mouse_btn, false));
+            },
+            _ => {},
         }
     }
 
@@ -207,6 +292,97 @@ fn handle_core_step(core: &mut Core, iterations: usize) -> Result<(), ()> {
     })
 }
 
+fn handle_input(core: &mut Core, key: Keycode, pressed: bool) -> bool {
+    let axis_value = |value| {
+        if pressed {
+            value
+        } else {
+            0x80
+        }
+    };
+
+    match key {
+        Keycode::M => {
+            if pressed {
+                let analog_mode = core.toggle_analog_mode(Port::Port1, Slot::A);
+                log::info!("Analog mode: {}", analog_mode);
+            }
+            return true;
+        },
+        Keycode::I => {
+            core.set_axis(Port::Port1, Slot::A, Axis::LeftY, axis_value(0x00));
+            return true;
+        },
+        Keycode::K => {
+            core.set_axis(Port::Port1, Slot::A, Axis::LeftY, axis_value(0xFF));
+            return true;
+        },
+        Keycode::J => {
+            core.set_axis(Port::Port1, Slot::A, Axis::LeftX, axis_value(0x00));
+            return true;
+        },
+        Keycode::L => {
+            core.set_axis(Port::Port1, Slot::A, Axis::LeftX, axis_value(0xFF));
+            return true;
+        },
+        _ => {},
+    }
+
+    let button = match key {
+        Keycode::Up => Button::Up,
+        Keycode::Down => Button::Down,
+        Keycode::Left => Button::Left,
+        Keycode::Right => Button::Right,
+        Keycode::Z => Button::Cross,
+        Keycode::X => Button::Circle,
+        Keycode::A => Button::Square,
+        Keycode::S => Button::Triangle,
+        Keycode::Q => Button::L1,
+        Keycode::W => Button::R1,
+        Keycode::E => Button::L2,
+        Keycode::R => Button::R2,
+        Keycode::Return => Button::Start,
+        Keycode::Backspace => Button::Select,
+        _ => return false,
+    };
+
+    core.set_button(Port::Port1, Slot::A, button, pressed);
+    true
+}
+
+/// Forwards the host mouse to any mouse / GunCon connected to slot A of either port.
+/// Never considered handled, as motion events arrive continuously and should not hold up emulation.
+fn handle_pointer(core: &mut Core, window: &Window, event: InputEvent) -> bool {
+    const PORTS: [Port; 2] = [Port::Port1, Port::Port2];
+
+    match event {
+        InputEvent::PointerMotion(x, y, xrel, yrel) => {
+            let (width, height) = window.size();
+            let position = (x as f32 / width as f32, y as f32 / height as f32);
+
+            for &port in PORTS.iter() {
+                core.move_pointer(port, Slot::A, xrel, yrel);
+                core.set_pointer_position(port, Slot::A, Some(position));
+            }
+        },
+        InputEvent::PointerButton(mouse_button, pressed) => {
+            let button = match mouse_button {
+                MouseButton::Left => PointerButton::Left,
+                MouseButton::Right => PointerButton::Right,
+                MouseButton::Middle => PointerButton::Middle,
+                _ => return false,
+            };
+
+            for &port in PORTS.iter() {
+                core.set_pointer_button(port, Slot::A, button, pressed);
+            }
+        },
+        InputEvent::Key(..) => {},
+    }
+
+    false
+}
+
 fn handle_change_disc(core: &mut Core) {
     match args().nth(1) {
         Some(disc_path_raw) => {
@@ -218,6 +394,33 @@ fn handle_change_disc(core: &mut Core) {
     }
 }
 
+fn next_disc(core: &mut Core, disc_index: &Cell<usize>) {
+    let disc_count = core.disc_count();
+    if disc_count < 2 {
+        log::info!("No other discs in the image");
+        return;
+    }
+
+    let index = (disc_index.get() + 1) % disc_count;
+    match core.select_disc(index) {
+        Ok(()) => {
+            disc_index.set(index);
+            log::info!("Changed to disc {} of {}", index + 1, disc_count);
+        },
+        Err(e) => log::error!("Changing disc failed: {}", &e),
+    }
+}
+
+fn toggle_lid(core: &mut Core) {
+    if core.lid_open() {
+        core.close_lid();
+        log::info!("Lid closed");
+    } else {
+        core.open_lid();
+        log::info!("Lid opened");
+    }
+}
+
 fn load_state(core: &mut Core) -> bool {
     match core.load_state(None) {
         Ok(()) => {